@@ -0,0 +1,522 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{Debug, Formatter, Result};
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+
+use crate::builder::fpag_builder::find_constant_index_locals;
+use crate::graph::call_graph::{CGCallSite, CGFunction, CallGraph};
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::call_site::BaseCallSite;
+use crate::mir::function::FuncId;
+use crate::mir::path::{Path, PathEnum, PathSelector, ProjectionElems};
+use crate::pta::flow_sensitive::FlowSensitivePointsTo;
+use crate::pta::DiffPTDataTy;
+use crate::pts_set::points_to::PointsToSet;
+use crate::util::type_util;
+
+/// The index of a memory object in the analysis result.
+pub type ObjectId = usize;
+
+/// The base of a memory object, i.e. a local variable, a heap allocation, a static variable, ...
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ObjectBase {
+    /// A local variable (including the parameters and the return value) of a function instance.
+    Local { func: FuncId, local: mir::Local },
+    /// An auxiliary local variable created by the analysis.
+    Auxiliary { func: FuncId, ordinal: usize },
+    /// A dynamically allocated memory object, identified by its allocation site.
+    Heap { func: FuncId, location: mir::Location },
+    StaticVariable(DefId),
+    PromotedConstant { def_id: DefId, ordinal: usize },
+    /// A function instance pointed to by a function pointer.
+    Function(FuncId),
+    /// An abstract object pointed to by the arguments of the entry functions.
+    AbstractObject { type_index: usize },
+    /// A type instance identified by the type's index in the type cache.
+    Type(usize),
+    /// A constant, e.g. a string literal.
+    Constant,
+}
+
+impl Debug for ObjectBase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ObjectBase::Local { func, local } => f.write_fmt(format_args!("{:?}::{:?}", func, local)),
+            ObjectBase::Auxiliary { func, ordinal } => f.write_fmt(format_args!("{:?}::aux_{}", func, ordinal)),
+            ObjectBase::Heap { func, location } => f.write_fmt(format_args!("{:?}::heap_{:?}", func, location)),
+            ObjectBase::StaticVariable(def_id) => f.write_fmt(format_args!("static_variable::{:?}", def_id)),
+            ObjectBase::PromotedConstant { def_id, ordinal } => {
+                f.write_fmt(format_args!("{:?}::promoted_{}", def_id, ordinal))
+            }
+            ObjectBase::Function(func) => func.fmt(f),
+            ObjectBase::AbstractObject { type_index } => f.write_fmt(format_args!("abstract_obj::Ty({:?})", type_index)),
+            ObjectBase::Type(type_index) => f.write_fmt(format_args!("Ty({:?})", type_index)),
+            ObjectBase::Constant => f.write_str("constant"),
+        }
+    }
+}
+
+/// A memory location, i.e. an object base qualified by a projection of fields, elements,
+/// variants, ... The locations pointed to by the pointers are the memory objects of the result.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MemoryObject {
+    pub base: ObjectBase,
+    pub projection: ProjectionElems,
+}
+
+impl Debug for MemoryObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.base.fmt(f)?;
+        for elem in &self.projection {
+            f.write_fmt(format_args!(".{:?}", elem))?;
+        }
+        Ok(())
+    }
+}
+
+impl MemoryObject {
+    /// Converts a path of the analysis into a memory location. The offset paths are only
+    /// converted if `truncate_offsets` is set, in which case they are represented by their bases.
+    pub(crate) fn from_path(path: &Path, truncate_offsets: bool) -> Option<MemoryObject> {
+        let base = match &path.value {
+            PathEnum::LocalVariable { func_id, ordinal } | PathEnum::Parameter { func_id, ordinal } => {
+                ObjectBase::Local { func: *func_id, local: mir::Local::from_usize(*ordinal) }
+            }
+            PathEnum::ReturnValue { func_id } => ObjectBase::Local { func: *func_id, local: mir::RETURN_PLACE },
+            PathEnum::Auxiliary { func_id, ordinal } => ObjectBase::Auxiliary { func: *func_id, ordinal: *ordinal },
+            PathEnum::HeapObj { func_id, location } => ObjectBase::Heap { func: *func_id, location: *location },
+            PathEnum::StaticVariable { def_id } => ObjectBase::StaticVariable(*def_id),
+            PathEnum::PromotedConstant { def_id, ordinal } => {
+                ObjectBase::PromotedConstant { def_id: *def_id, ordinal: *ordinal }
+            }
+            PathEnum::Function(func_id) => ObjectBase::Function(*func_id),
+            PathEnum::AbstractObject { type_index } => ObjectBase::AbstractObject { type_index: *type_index },
+            PathEnum::Type(type_index) => ObjectBase::Type(*type_index),
+            PathEnum::Constant | PathEnum::PromotedStrRefArray | PathEnum::PromotedArgumentV1Array => {
+                ObjectBase::Constant
+            }
+            PathEnum::QualifiedPath { base, projection } => {
+                let mut object = Self::from_path(base, truncate_offsets)?;
+                object.projection.extend(projection.iter().copied());
+                return Some(object);
+            }
+            PathEnum::OffsetPath { base, .. } => {
+                return if truncate_offsets { Self::from_path(base, truncate_offsets) } else { None };
+            }
+        };
+        Some(MemoryObject { base, projection: vec![] })
+    }
+
+    /// Returns true if the two memory locations may overlap, i.e. one of them may contain the other.
+    /// The variants of enums are not distinguished, and a cast location may overlap any location
+    /// of the same base.
+    pub fn may_overlap(&self, other: &MemoryObject) -> bool {
+        if self.base != other.base {
+            return false;
+        }
+        let mut elems1 = self.projection.iter().filter(|elem| !matches!(elem, PathSelector::Downcast(..)));
+        let mut elems2 = other.projection.iter().filter(|elem| !matches!(elem, PathSelector::Downcast(..)));
+        loop {
+            match (elems1.next(), elems2.next()) {
+                (Some(elem1), Some(elem2)) => match (elem1, elem2) {
+                    (PathSelector::Cast(..), _) | (_, PathSelector::Cast(..)) => return true,
+                    (PathSelector::UnionField(..), PathSelector::UnionField(..)) => continue,
+                    (PathSelector::Index | PathSelector::Subslice { .. }, PathSelector::Index | PathSelector::ConstantIndex(..) | PathSelector::Subslice { .. })
+                    | (PathSelector::ConstantIndex(..), PathSelector::Index | PathSelector::Subslice { .. }) => continue,
+                    _ => {
+                        if elem1 != elem2 {
+                            return false;
+                        }
+                    }
+                },
+                // One location is a prefix of the other
+                _ => return true,
+            }
+        }
+    }
+}
+
+/// A MIR place of a function instance, whose projection is given in the form of the analysis.
+/// Use `AnalysisResult::place_from_mir` to convert a `mir::Place` of the function's body.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Place {
+    pub func: FuncId,
+    pub local: mir::Local,
+    pub projection: ProjectionElems,
+}
+
+impl Place {
+    pub fn new(func: FuncId, local: mir::Local, projection: ProjectionElems) -> Self {
+        Place { func, local, projection }
+    }
+}
+
+/// Converts the projection of the place of the function's MIR to the form of the analysis.
+/// The constant indices of the arrays no longer than `index_sensitive_arrays` are converted to
+/// constant-index selectors, as the PAG builder does.
+pub(crate) fn place_projection<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    mir: &mir::Body<'tcx>,
+    place: &mir::Place<'tcx>,
+    index_sensitive_arrays: Option<usize>,
+) -> ProjectionElems {
+    let mut constant_index_locals = None;
+    let mut projection = ProjectionElems::with_capacity(place.projection.len());
+    let mut place_ty = mir::tcx::PlaceTy::from_ty(mir.local_decls[place.local].ty);
+    for elem in place.projection {
+        match elem {
+            mir::ProjectionElem::Deref => {
+                if place_ty.ty.is_box() {
+                    // Box.0 = Unique, Unique.0 = NonNull, NonNull.0 = source thin pointer
+                    projection.extend([PathSelector::Field(0), PathSelector::Field(0), PathSelector::Field(0)]);
+                }
+                projection.push(PathSelector::Deref);
+            }
+            mir::ProjectionElem::Field(field, _) => {
+                if place_ty.ty.is_union() {
+                    projection.push(PathSelector::UnionField(field.index()));
+                } else {
+                    projection.push(PathSelector::Field(field.index()));
+                }
+            }
+            mir::ProjectionElem::Index(local) => {
+                let array_len = type_util::index_sensitive_array_len(tcx, place_ty.ty, index_sensitive_arrays);
+                let index = array_len.and_then(|_| {
+                    constant_index_locals
+                        .get_or_insert_with(|| find_constant_index_locals(tcx, mir))
+                        .get(&local)
+                        .copied()
+                });
+                match (array_len, index) {
+                    (Some(len), Some(index)) if index < len => projection.push(PathSelector::ConstantIndex(index)),
+                    _ => projection.push(PathSelector::Index),
+                }
+            }
+            mir::ProjectionElem::ConstantIndex { offset, from_end, .. } => {
+                match type_util::index_sensitive_array_len(tcx, place_ty.ty, index_sensitive_arrays) {
+                    Some(len) if !from_end && (offset as usize) < len => {
+                        projection.push(PathSelector::ConstantIndex(offset as usize))
+                    }
+                    Some(len) if from_end && (offset as usize) <= len && offset > 0 => {
+                        projection.push(PathSelector::ConstantIndex(len - offset as usize))
+                    }
+                    _ => projection.push(PathSelector::Index),
+                }
+            }
+            mir::ProjectionElem::Downcast(_, variant_idx) => {
+                projection.push(PathSelector::Downcast(variant_idx.as_usize()));
+            }
+            mir::ProjectionElem::Subslice { .. }
+            | mir::ProjectionElem::OpaqueCast(..)
+            | mir::ProjectionElem::Subtype(..) => {}
+        }
+        place_ty = place_ty.projection_ty(tcx, elem);
+    }
+    projection
+}
+
+/// Information of a reachable function instance.
+#[derive(Clone, Debug)]
+pub struct FunctionInfo {
+    pub def_id: DefId,
+    /// The name of the function instance, including its generic arguments.
+    pub name: String,
+}
+
+/// The results of a pointer analysis, i.e. the points-to sets of the pointers and the call graph,
+/// which do not depend on the lifetime of the compiler's type context and can be queried after the
+/// compilation has finished. The results of a context-sensitive analysis are merged over contexts.
+pub struct AnalysisResult {
+    /// The reachable function instances.
+    functions: BTreeMap<FuncId, FunctionInfo>,
+    /// The reachable instances of each function.
+    instances: HashMap<DefId, BTreeSet<FuncId>>,
+    /// The memory objects pointed to by any pointer.
+    objects: Vec<MemoryObject>,
+    object_ids: HashMap<MemoryObject, ObjectId>,
+    /// The points-to sets of the pointers.
+    pts: HashMap<MemoryObject, BTreeSet<ObjectId>>,
+    callees: HashMap<BaseCallSite, BTreeSet<FuncId>>,
+    callers: HashMap<FuncId, HashSet<BaseCallSite>>,
+    /// The maximum length of the arrays whose elements are distinguished by constant indices.
+    index_sensitive_arrays: Option<usize>,
+    /// The flow-sensitive points-to sets of the pointers rooted at the locals of the functions
+    /// selected with `--flow-sensitive-funcs`, before each location.
+    fs_pts: HashMap<FuncId, HashMap<mir::Location, HashMap<MemoryObject, BTreeSet<ObjectId>>>>,
+}
+
+impl AnalysisResult {
+    pub(crate) fn new<P: PAGPath, F, S>(
+        acx: &AnalysisContext,
+        call_graph: &CallGraph<F, S>,
+        pt_data: &DiffPTDataTy,
+        pag: &PAG<P>,
+        fs_pts: &[FlowSensitivePointsTo],
+    ) -> AnalysisResult
+    where
+        F: CGFunction + Into<FuncId>,
+        S: CGCallSite + Into<BaseCallSite>,
+    {
+        let mut result = AnalysisResult {
+            functions: BTreeMap::new(),
+            instances: HashMap::new(),
+            objects: Vec::new(),
+            object_ids: HashMap::new(),
+            pts: HashMap::new(),
+            callees: HashMap::new(),
+            callers: HashMap::new(),
+            index_sensitive_arrays: acx.analysis_options.index_sensitive_arrays,
+            fs_pts: HashMap::new(),
+        };
+
+        for func in call_graph.reach_funcs_iter() {
+            let func_id: FuncId = func.into();
+            if result.functions.contains_key(&func_id) {
+                continue;
+            }
+            let func_ref = acx.get_function_reference(func_id);
+            result.instances.entry(func_ref.def_id).or_default().insert(func_id);
+            result.functions.insert(func_id, FunctionInfo { def_id: func_ref.def_id, name: func_ref.to_string() });
+        }
+
+        for (callsite, edges) in &call_graph.callsite_to_edges {
+            let ci_callsite: BaseCallSite = (*callsite).into();
+            for edge in edges {
+                let callee: FuncId = call_graph.get_callee_id_of_edge(*edge).unwrap().into();
+                result.callees.entry(ci_callsite).or_default().insert(callee);
+                result.callers.entry(callee).or_default().insert(ci_callsite);
+            }
+        }
+
+        for (node, pts) in &pt_data.propa_pts_map {
+            if pts.is_empty() {
+                continue;
+            }
+            let Some(pointer) = MemoryObject::from_path(&pag.node_path(*node).ci_path(), false) else {
+                continue;
+            };
+            let mut pointee_ids = BTreeSet::new();
+            for pointee in pts {
+                if let Some(object) = MemoryObject::from_path(&pag.node_path(pointee).ci_path(), true) {
+                    pointee_ids.insert(result.get_or_insert_object(object));
+                }
+            }
+            result.pts.entry(pointer).or_default().extend(pointee_ids);
+        }
+
+        for func_fs_pts in fs_pts {
+            let mut pts_before = HashMap::new();
+            for (location, state) in &func_fs_pts.pts_before {
+                let mut state_pts: HashMap<MemoryObject, BTreeSet<ObjectId>> = HashMap::new();
+                for (ptr, pointees) in state {
+                    let Some(pointer) = MemoryObject::from_path(ptr, false) else {
+                        continue;
+                    };
+                    let pointee_ids: BTreeSet<ObjectId> = pointees
+                        .iter()
+                        .filter_map(|pointee| MemoryObject::from_path(pointee, true))
+                        .map(|object| result.get_or_insert_object(object))
+                        .collect();
+                    state_pts.entry(pointer).or_default().extend(pointee_ids);
+                }
+                pts_before.insert(*location, state_pts);
+            }
+            result.fs_pts.insert(func_fs_pts.func_id, pts_before);
+        }
+        result
+    }
+
+    fn get_or_insert_object(&mut self, object: MemoryObject) -> ObjectId {
+        if let Some(id) = self.object_ids.get(&object) {
+            return *id;
+        }
+        let id = self.objects.len();
+        self.objects.push(object.clone());
+        self.object_ids.insert(object, id);
+        id
+    }
+
+    /// Converts the `place` of the given function instance's body. The deref of a `Box` is
+    /// converted to the deref of the raw pointer wrapped in the box, and the constant indices
+    /// of the index-sensitive arrays to constant-index selectors, as the analysis does.
+    pub fn place_from_mir<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        body: &mir::Body<'tcx>,
+        func: FuncId,
+        place: mir::Place<'tcx>,
+    ) -> Place {
+        let projection = place_projection(tcx, body, &place, self.index_sensitive_arrays);
+        Place { func, local: place.local, projection }
+    }
+
+    /// Returns the memory object with the given id.
+    pub fn object(&self, id: ObjectId) -> &MemoryObject {
+        &self.objects[id]
+    }
+
+    /// Returns the ids of the objects pointed to by the pointer at the given memory location.
+    pub fn pointees(&self, pointer: &MemoryObject) -> Option<&BTreeSet<ObjectId>> {
+        self.pts.get(pointer)
+    }
+
+    /// Returns the objects pointed to by the pointer at the place `local.projection` of all the
+    /// reachable instances of the function `def_id`.
+    pub fn points_to(&self, def_id: DefId, local: mir::Local, projection: &[PathSelector]) -> Vec<&MemoryObject> {
+        let mut pointees = BTreeSet::new();
+        for func in self.function_instances(def_id) {
+            pointees.extend(self.place_points_to(&Place::new(func, local, projection.to_vec())));
+        }
+        pointees.into_iter().map(|id| &self.objects[id]).collect()
+    }
+
+    /// Returns the ids of the objects pointed to by the pointer at the place.
+    pub fn place_points_to(&self, place: &Place) -> BTreeSet<ObjectId> {
+        self.locations_of(place)
+            .iter()
+            .filter_map(|location| self.pts.get(location))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the ids of the objects pointed to by the pointer at the place before the given
+    /// location of its function, according to the flow-sensitive results. Returns `None` if the
+    /// function is not selected with `--flow-sensitive-funcs` or the location is unreachable.
+    /// The pointers that are not rooted at the function's locals are resolved flow-insensitively.
+    pub fn pts_at(&self, place: &Place, location: mir::Location) -> Option<BTreeSet<ObjectId>> {
+        let state = self.fs_pts.get(&place.func)?.get(&location)?;
+        let pointees = |pointer: &MemoryObject| match pointer.base {
+            ObjectBase::Local { func, .. } if func == place.func => state.get(pointer),
+            _ => self.pts.get(pointer),
+        };
+        Some(
+            self.resolve_locations(place, pointees)
+                .iter()
+                .filter_map(pointees)
+                .flatten()
+                .copied()
+                .collect(),
+        )
+    }
+
+    /// Returns true if the two places may denote overlapping memory locations.
+    pub fn may_alias(&self, place_a: &Place, place_b: &Place) -> bool {
+        let locations_a = self.locations_of(place_a);
+        let locations_b = self.locations_of(place_b);
+        locations_a
+            .iter()
+            .any(|location_a| locations_b.iter().any(|location_b| location_a.may_overlap(location_b)))
+    }
+
+    /// Returns the callees of the callsite.
+    pub fn callees(&self, callsite: &BaseCallSite) -> Vec<FuncId> {
+        self.callees
+            .get(callsite)
+            .map_or_else(Vec::new, |callees| callees.iter().copied().collect())
+    }
+
+    /// Returns the callsites calling the function instance.
+    pub fn callers(&self, func: FuncId) -> Vec<BaseCallSite> {
+        self.callers
+            .get(&func)
+            .map_or_else(Vec::new, |callsites| callsites.iter().copied().collect())
+    }
+
+    /// Returns the reachable function instances.
+    pub fn reachable_functions(&self) -> impl Iterator<Item = FuncId> + '_ {
+        self.functions.keys().copied()
+    }
+
+    /// Returns the reachable instances of the function `def_id`.
+    pub fn function_instances(&self, def_id: DefId) -> impl Iterator<Item = FuncId> + '_ {
+        self.instances.get(&def_id).into_iter().flatten().copied()
+    }
+
+    /// Returns the information of a reachable function instance.
+    pub fn function_info(&self, func: FuncId) -> Option<&FunctionInfo> {
+        self.functions.get(&func)
+    }
+
+    /// Returns the memory locations that may be denoted by the place.
+    fn locations_of(&self, place: &Place) -> Vec<MemoryObject> {
+        self.resolve_locations(place, |location| self.pts.get(location))
+    }
+
+    /// Resolves the memory locations denoted by the place, dereferencing the pointers with the
+    /// given points-to sets.
+    fn resolve_locations<'a>(
+        &'a self,
+        place: &Place,
+        pointees: impl Fn(&MemoryObject) -> Option<&'a BTreeSet<ObjectId>>,
+    ) -> Vec<MemoryObject> {
+        let mut locations = vec![MemoryObject {
+            base: ObjectBase::Local { func: place.func, local: place.local },
+            projection: vec![],
+        }];
+        for elem in &place.projection {
+            if let PathSelector::Deref = elem {
+                locations = locations
+                    .iter()
+                    .filter_map(&pointees)
+                    .flatten()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .map(|id| self.objects[*id].clone())
+                    .collect();
+            } else {
+                for location in locations.iter_mut() {
+                    location.projection.push(*elem);
+                }
+            }
+        }
+        locations
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MemoryObject, ObjectBase};
+    use crate::mir::function::FuncId;
+    use crate::mir::path::PathSelector;
+    use rustc_middle::mir;
+
+    fn local_object(local: usize, projection: Vec<PathSelector>) -> MemoryObject {
+        MemoryObject {
+            base: ObjectBase::Local { func: FuncId::from_u32(0), local: mir::Local::from_usize(local) },
+            projection,
+        }
+    }
+
+    #[test]
+    fn may_overlap_test() {
+        let s = local_object(1, vec![]);
+        let s_0 = local_object(1, vec![PathSelector::Field(0)]);
+        let s_1 = local_object(1, vec![PathSelector::Field(1)]);
+        assert!(s.may_overlap(&s_0));
+        assert!(s_0.may_overlap(&s));
+        assert!(!s_0.may_overlap(&s_1));
+        assert!(!s_0.may_overlap(&local_object(2, vec![PathSelector::Field(0)])));
+
+        let elem_1 = local_object(1, vec![PathSelector::ConstantIndex(1)]);
+        let elem_2 = local_object(1, vec![PathSelector::ConstantIndex(2)]);
+        assert!(!elem_1.may_overlap(&elem_2));
+        assert!(elem_1.may_overlap(&local_object(1, vec![PathSelector::Index])));
+
+        let union_0 = local_object(1, vec![PathSelector::UnionField(0)]);
+        let union_1 = local_object(1, vec![PathSelector::UnionField(1), PathSelector::Field(1)]);
+        assert!(union_0.may_overlap(&union_1));
+
+        let variant_0 = local_object(1, vec![PathSelector::Downcast(1), PathSelector::Field(0)]);
+        assert!(variant_0.may_overlap(&s_0));
+        assert!(local_object(1, vec![PathSelector::Cast(3)]).may_overlap(&s_1));
+    }
+}
@@ -0,0 +1,11 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! The library-level API for the tools linking RUPTA as a crate. The results of an analysis
+//! run by `PTACallbacks` can be obtained with a result hook and queried without a type context.
+
+pub mod analysis_result;
+
+pub use analysis_result::{AnalysisResult, FunctionInfo, MemoryObject, ObjectBase, ObjectId, Place};
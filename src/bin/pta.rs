@@ -18,6 +18,7 @@ use log::*;
 use std::env;
 
 use rupta::pta::PTACallbacks;
+use rupta::mir::entry_points::EntryMode;
 use rupta::util;
 use rupta::util::options::AnalysisOptions;
 
@@ -83,6 +84,11 @@ fn main() {
             rustc_command_line_arguments.push("-Z".into());
             rustc_command_line_arguments.push(always_encode_mir);
         }
+        let test: String = "--test".into();
+        if options.entry_mode == EntryMode::Tests && !rustc_command_line_arguments.contains(&test) {
+            // Tell compiler to build the test harness so that the test functions are registered.
+            rustc_command_line_arguments.push(test);
+        }
         debug!("rustc command line arguments: {:?}", rustc_command_line_arguments);
         
         let mut callbacks = PTACallbacks::new(options);
@@ -0,0 +1,741 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Provides abstract models for the std collections, i.e. `Vec`, `VecDeque`, `HashMap`,
+//! `HashSet`, `BTreeMap` and `BTreeSet`. The models are only enabled with the
+//! `--model-std-collections` option.
+//!
+//! The elements of a collection are stored in an abstract storage object of type `[E]`, where
+//! `E` is `(K, V)` for maps and `T` for the other collections. All the elements are merged into
+//! the `Index` field of the storage object. A collection value points to its storage object
+//! through every pointer field of the collection type (e.g. `Vec.buf.ptr` or `HashMap.base.table.ctrl`),
+//! and an iterator points to the `Index` field of the storage object through every pointer field
+//! of the iterator type. Inserting an element stores it into the `Index` field, while references
+//! and iterators returned from `get`/`iter` point to the `Index` field.
+//!
+//! The collections are created by `new`, `with_capacity`, `default` (and the `with_hasher` variants
+//! of the hash collections), or by `into_vec` for the `vec!` macro. The collections created by
+//! `FromIterator::from_iter` (`collect`), `From::from`, `Clone::clone` and `to_vec` get a new
+//! storage object holding the items of the source, and `Extend::extend`, `append` and
+//! `extend_from_slice` store the items of the source into the existing storage. The items of
+//! collections, arrays and slices are read from their elements, while the items of other
+//! iterables are obtained by calling `IntoIterator::into_iter` and `Iterator::next`. Cloning a
+//! collection copies its elements without calling their `clone` methods.
+//!
+//! A map entry returned from `entry` points to the storage object of its map like the map itself,
+//! and the methods of `OccupiedEntry` and `VacantEntry` (and of the hashbrown entries wrapped by the
+//! entries of `HashMap`) access the elements through it. The other entry methods, e.g. `or_insert`
+//! and `or_default`, are analyzed and call these methods.
+//!
+//! The methods that do not access the elements, e.g. `len`, `clear` and `reserve`, are modeled as
+//! no-ops, so that their bodies do not touch the abstract storage.
+
+use log::*;
+use std::rc::Rc;
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::{GenericArgsRef, Ty, TyCtxt, TyKind};
+
+use crate::builder::call_graph_builder;
+use crate::builder::fpag_builder::FuncPAGBuilder;
+use crate::builder::special_function_handler::{self, copy_value, option_some};
+use crate::mir::known_names::KnownNames;
+use crate::mir::path::{Path, PathSelector, PathSupport, ProjectionElems};
+use crate::util::{self, type_util};
+
+/// Handles the call to a method of the std collections or their iterators.
+///
+/// Returns true if the callee is modeled.
+pub fn handled_as_collection_call<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_def_id: &DefId,
+    gen_args: &GenericArgsRef<'tcx>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) -> bool {
+    // Trait methods such as `Deref::deref`, `Index::index` and `Iterator::next` are devirtualized first.
+    let callee_def_id =
+        match call_graph_builder::try_to_devirtualize(fpb.acx.tcx, *callee_def_id, gen_args) {
+            Some((def_id, _)) => def_id,
+            None => *callee_def_id,
+        };
+    let callee_known_name = fpb.acx.get_known_name_for(callee_def_id);
+    match callee_known_name {
+        KnownNames::StdVecNew
+        | KnownNames::StdVecDequeNew
+        | KnownNames::StdHashMapNew
+        | KnownNames::StdHashSetNew
+        | KnownNames::StdBTreeMapNew
+        | KnownNames::StdBTreeSetNew => {
+            handle_collection_new(fpb, destination, location);
+        }
+        KnownNames::StdVecPush
+        | KnownNames::StdVecDequePush
+        | KnownNames::StdHashSetInsert
+        | KnownNames::StdBTreeSetInsert => {
+            store_element(fpb, &args[0], args[1].clone(), vec![]);
+        }
+        KnownNames::StdVecInsert | KnownNames::StdVecDequeInsert => {
+            store_element(fpb, &args[0], args[2].clone(), vec![]);
+        }
+        KnownNames::StdHashMapInsert | KnownNames::StdBTreeMapInsert => {
+            // The previous value is returned in `Option<V>`
+            load_element(fpb, &args[0], vec![PathSelector::Field(1)], option_some(destination));
+            store_element(fpb, &args[0], args[1].clone(), vec![PathSelector::Field(0)]);
+            store_element(fpb, &args[0], args[2].clone(), vec![PathSelector::Field(1)]);
+        }
+        KnownNames::StdVecPop
+        | KnownNames::StdVecDequePop
+        | KnownNames::StdHashSetTake
+        | KnownNames::StdBTreeSetTake => {
+            load_element(fpb, &args[0], vec![], option_some(destination));
+        }
+        KnownNames::StdVecRemove => {
+            load_element(fpb, &args[0], vec![], destination.clone());
+        }
+        KnownNames::StdHashMapRemove | KnownNames::StdBTreeMapRemove => {
+            load_element(fpb, &args[0], vec![PathSelector::Field(1)], option_some(destination));
+        }
+        KnownNames::StdVecDequeGet | KnownNames::StdHashSetGet | KnownNames::StdBTreeSetGet => {
+            borrow_element(fpb, &args[0], vec![], option_some(destination));
+        }
+        KnownNames::StdHashMapGet | KnownNames::StdBTreeMapGet => {
+            borrow_element(fpb, &args[0], vec![PathSelector::Field(1)], option_some(destination));
+        }
+        KnownNames::StdHashMapGetKeyValue | KnownNames::StdBTreeMapGetKeyValue => {
+            let key_value_path = option_some(destination);
+            for i in 0..2 {
+                let dst_path = Path::new_field(key_value_path.clone(), i);
+                borrow_element(fpb, &args[0], vec![PathSelector::Field(i)], dst_path);
+            }
+        }
+        KnownNames::StdVecIndex | KnownNames::StdVecDequeIndex => {
+            // The output of `Vec::index` is a slice if the index is a range
+            let dst_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+            if type_util::get_dereferenced_type(dst_ty).is_slice() {
+                borrow_storage(fpb, &args[0], destination.clone());
+            } else {
+                borrow_element(fpb, &args[0], vec![], destination.clone());
+            }
+        }
+        KnownNames::StdHashMapIndex | KnownNames::StdBTreeMapIndex => {
+            borrow_element(fpb, &args[0], vec![PathSelector::Field(1)], destination.clone());
+        }
+        KnownNames::StdVecDeref => {
+            borrow_storage(fpb, &args[0], destination.clone());
+        }
+        KnownNames::StdVecAsPtr => {
+            borrow_element(fpb, &args[0], vec![], destination.clone());
+        }
+        KnownNames::StdSliceIntoVec => {
+            handle_slice_into_vec(fpb, args, destination);
+        }
+        KnownNames::StdVecIntoIter
+        | KnownNames::StdVecDequeIter
+        | KnownNames::StdHashMapIter
+        | KnownNames::StdHashSetIter
+        | KnownNames::StdBTreeMapIter
+        | KnownNames::StdBTreeSetIter => {
+            handle_collection_iter(fpb, &args[0], destination);
+        }
+        KnownNames::StdHashMapIterNext
+        | KnownNames::StdHashSetIterNext
+        | KnownNames::StdBTreeMapIterNext
+        | KnownNames::StdBTreeSetIterNext => {
+            handle_iterator_next(fpb, callee_known_name, args, destination);
+        }
+        KnownNames::StdCollectionFrom => {
+            handle_collection_new(fpb, destination, location);
+            store_items(fpb, destination, args[0].clone(), location);
+        }
+        KnownNames::StdCollectionExtend => {
+            store_items(fpb, &args[0], args[1].clone(), location);
+        }
+        KnownNames::StdMapEntry => {
+            // The key is kept in the entry, which is inserted along with the value
+            store_element(fpb, &args[0], args[1].clone(), vec![PathSelector::Field(0)]);
+            if let Some((storage_ptr, _)) = get_storage_pointer(fpb, &args[0]) {
+                let entry_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+                point_to(fpb, storage_ptr, destination, entry_ty);
+            }
+        }
+        KnownNames::StdMapEntryKey => {
+            borrow_element(fpb, &args[0], vec![PathSelector::Field(0)], destination.clone());
+        }
+        KnownNames::StdMapEntryIntoKey => {
+            load_element(fpb, &args[0], vec![PathSelector::Field(0)], destination.clone());
+        }
+        KnownNames::StdMapEntryGet => {
+            borrow_element(fpb, &args[0], vec![PathSelector::Field(1)], destination.clone());
+        }
+        KnownNames::StdMapEntryInsert => {
+            store_element(fpb, &args[0], args[1].clone(), vec![PathSelector::Field(1)]);
+            borrow_element(fpb, &args[0], vec![PathSelector::Field(1)], destination.clone());
+        }
+        KnownNames::StdMapEntryReplace => {
+            load_element(fpb, &args[0], vec![PathSelector::Field(1)], destination.clone());
+            store_element(fpb, &args[0], args[1].clone(), vec![PathSelector::Field(1)]);
+        }
+        KnownNames::StdMapEntryRemove => {
+            load_element(fpb, &args[0], vec![PathSelector::Field(1)], destination.clone());
+        }
+        KnownNames::StdMapEntryRemoveEntry => {
+            load_element(fpb, &args[0], vec![], destination.clone());
+        }
+        // These methods do not access the elements. Removing elements is not modeled, as the
+        // abstract storage is flow-insensitive.
+        KnownNames::StdVecQuery
+        | KnownNames::StdVecDequeQuery
+        | KnownNames::StdHashMapQuery
+        | KnownNames::StdHashSetQuery
+        | KnownNames::StdBTreeMapQuery
+        | KnownNames::StdBTreeSetQuery => {}
+        _ => return false,
+    }
+    true
+}
+
+/// Allocates a storage object for the new collection at `destination`.
+///
+/// ```let storage: [E]; let aux: *const [E] = &storage; dst.ptr_1 = aux; ... dst.ptr_n = aux;```
+fn handle_collection_new<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    destination: &Rc<Path>,
+    location: mir::Location,
+) {
+    let tcx = fpb.acx.tcx;
+    let collection_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+    let elem_ty = element_type(tcx, collection_ty);
+    let storage_ty = Ty::new_slice(tcx, elem_ty);
+
+    let heap_object_path = Path::new_heap_obj(fpb.fpag.func_id, location);
+    fpb
+        .acx
+        .set_path_rustc_type(heap_object_path.clone(), tcx.types.u8);
+    fpb
+        .acx
+        .concretized_heap_objs
+        .insert(heap_object_path.clone(), elem_ty);
+    let storage_path = fpb
+        .acx
+        .cast_to(&heap_object_path, storage_ty)
+        .expect("Cast Error");
+
+    let storage_ptr_ty = Ty::new_imm_ptr(tcx, storage_ty);
+    let aux = fpb.create_aux_local(storage_ptr_ty);
+    fpb.add_addr_edge(storage_path, aux.clone());
+    point_to(fpb, aux, destination, collection_ty);
+}
+
+/// `fn into_vec(self: Box<[T]>) -> Vec<T>`, which is called by the `vec!` macro.
+/// The boxed slice is used as the storage of the vector directly.
+fn handle_slice_into_vec<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let box_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+    let Some((box_ptr_proj, box_ptr_ty)) = storage_pointer_projections(tcx, box_ty).into_iter().next() else {
+        return;
+    };
+    let box_ptr_path = Path::append_projection(&args[0], &box_ptr_proj);
+    fpb.acx.set_path_rustc_type(box_ptr_path.clone(), box_ptr_ty);
+    let aux = fpb.create_aux_local(box_ptr_ty);
+    fpb.add_direct_edge(box_ptr_path, aux.clone());
+    let vec_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+    point_to(fpb, aux, destination, vec_ty);
+}
+
+/// Creates an iterator at `destination` that points to the elements of the collection `collection`,
+/// which is either a reference to a collection or a collection passed by value.
+///
+/// ```let aux: *const E = &(*storage).index; dst.ptr_1 = aux; ... dst.ptr_n = aux;```
+fn handle_collection_iter<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection: &Rc<Path>,
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let Some((storage_ptr, elem_ty)) = get_storage_pointer(fpb, collection) else {
+        return;
+    };
+    let elem_path = element_path(fpb, storage_ptr, elem_ty, vec![]);
+    let aux = fpb.create_aux_local(Ty::new_imm_ptr(tcx, elem_ty));
+    fpb.add_gep_edge(elem_path, aux.clone());
+    let iter_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+    point_to(fpb, aux, destination, iter_ty);
+}
+
+/// `fn next(&mut self) -> Option<Self::Item>` of the iterators of maps and sets.
+///
+/// The pointer fields of an iterator point to the elements of the iterated collection,
+/// the result is read or borrowed from the element according to the type of the item.
+fn handle_iterator_next<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_known_name: KnownNames,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let iter_ref_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+    let iter_ty = type_util::get_dereferenced_type(iter_ref_ty);
+    let (iter_name, iter_types) = match iter_ty.kind() {
+        TyKind::Adt(def, args) => (tcx.item_name(def.did()), args.types().collect::<Vec<_>>()),
+        _ => unreachable!("Expect an iterator type, found {:?}", iter_ty),
+    };
+    let elem_ty = match callee_known_name {
+        KnownNames::StdHashMapIterNext | KnownNames::StdBTreeMapIterNext => {
+            Ty::new_tup(tcx, &[iter_types[0], iter_types[1]])
+        }
+        _ => iter_types[0],
+    };
+    let Some((iter_ptr_proj, iter_ptr_ty)) = storage_pointer_projections(tcx, iter_ty).into_iter().next() else {
+        return;
+    };
+
+    // let aux: *const E = (*self).ptr;
+    let iter_path = fpb.create_dereference(args[0].clone(), iter_ref_ty);
+    let iter_ptr_path = Path::append_projection(&iter_path, &iter_ptr_proj);
+    fpb.acx.set_path_rustc_type(iter_ptr_path.clone(), iter_ptr_ty);
+    let iter_ptr_aux = fpb.create_aux_local(iter_ptr_ty);
+    fpb.add_load_edge(iter_ptr_path, iter_ptr_aux.clone());
+    let elem_ptr_ty = Ty::new_imm_ptr(tcx, elem_ty);
+    let aux = fpb.create_aux_local(elem_ptr_ty);
+    add_pointer_edge(fpb, iter_ptr_aux, aux.clone());
+
+    let item_path = option_some(destination);
+    let option_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+    let item_ty = match option_ty.kind() {
+        TyKind::Adt(_, args) => args.type_at(0),
+        _ => unreachable!("Expect an Option type, found {:?}", option_ty),
+    };
+    // The field of the element yielded by the iterators of keys or values
+    let field = if matches!(iter_name.as_str(), "Values" | "ValuesMut" | "IntoValues") {
+        Some(1)
+    } else if matches!(iter_name.as_str(), "Keys" | "IntoKeys") {
+        Some(0)
+    } else {
+        None
+    };
+    let is_owned = matches!(iter_name.as_str(), "IntoIter" | "IntoKeys" | "IntoValues");
+    match (field, is_owned) {
+        (Some(i), true) => {
+            let elem_path = deref_element(fpb, aux, elem_ty, vec![PathSelector::Field(i)]);
+            let field_ty = fpb.acx.get_path_rustc_type(&elem_path).expect("rustc type error");
+            fpb.acx.set_path_rustc_type(item_path.clone(), field_ty);
+            copy_value(fpb, elem_path, field_ty, item_path);
+        }
+        (Some(i), false) => {
+            let elem_path = deref_element(fpb, aux, elem_ty, vec![PathSelector::Field(i)]);
+            fpb.add_gep_edge(elem_path, item_path);
+        }
+        (None, true) => {
+            let elem_path = deref_element(fpb, aux, elem_ty, vec![]);
+            fpb.acx.set_path_rustc_type(item_path.clone(), elem_ty);
+            copy_value(fpb, elem_path, elem_ty, item_path);
+        }
+        (None, false) => {
+            if let TyKind::Tuple(..) = item_ty.kind() {
+                // `(&K, &V)` yielded by the iterators of maps
+                for i in 0..2 {
+                    let elem_path = deref_element(fpb, aux.clone(), elem_ty, vec![PathSelector::Field(i)]);
+                    fpb.add_gep_edge(elem_path, Path::new_field(item_path.clone(), i));
+                }
+            } else {
+                fpb.add_direct_edge(aux, item_path);
+            }
+        }
+    }
+}
+
+/// Stores the items of `iterable` into the elements of `collection`, which is either a reference
+/// to a collection or a collection passed by value.
+fn store_items<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection: &Rc<Path>,
+    iterable: Rc<Path>,
+    location: mir::Location,
+) {
+    let tcx = fpb.acx.tcx;
+    let iterable_ty = fpb.acx.get_path_rustc_type(&iterable).expect("rustc type error");
+    let value_ty = if iterable_ty.is_ref() {
+        type_util::get_dereferenced_type(iterable_ty)
+    } else {
+        iterable_ty
+    };
+    let (item_path, item_ty) = if is_collection_type(tcx, value_ty) {
+        let Some((storage_ptr, elem_ty)) = get_storage_pointer(fpb, &iterable) else {
+            return;
+        };
+        (element_path(fpb, storage_ptr, elem_ty, vec![]), elem_ty)
+    } else if let TyKind::Array(elem_ty, _) | TyKind::Slice(elem_ty) = value_ty.kind() {
+        let value_path = if iterable_ty.is_ref() {
+            fpb.create_dereference(iterable, iterable_ty)
+        } else {
+            iterable
+        };
+        let index_path = Path::new_index(value_path);
+        fpb.acx.set_path_rustc_type(index_path.clone(), *elem_ty);
+        (index_path, *elem_ty)
+    } else {
+        let Some(item) = next_item(fpb, iterable, iterable_ty, location) else {
+            return;
+        };
+        item
+    };
+    let collection_ty = fpb.acx.get_path_rustc_type(collection).expect("rustc type error");
+    let elem_ty = element_type(tcx, type_util::get_dereferenced_type(collection_ty));
+    store_item(fpb, collection, item_path, item_ty, elem_ty, vec![]);
+}
+
+/// Stores an item of type `item_ty` into the elements of the collection, where the projection `proj`
+/// of the element has type `target_ty`. The item is dereferenced if it is a reference to the target
+/// type, e.g. the items of `Extend<&'a T>`, and the fields of tuples are stored separately, e.g. the
+/// items `(&K, &V)` of `Extend<(&'a K, &'a V)>`.
+fn store_item<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection: &Rc<Path>,
+    item: Rc<Path>,
+    item_ty: Ty<'tcx>,
+    target_ty: Ty<'tcx>,
+    proj: ProjectionElems,
+) {
+    if item_ty == target_ty {
+        store_element(fpb, collection, item, proj);
+    } else if item_ty.is_ref() && type_util::get_dereferenced_type(item_ty) == target_ty {
+        let item = fpb.create_dereference(item, item_ty);
+        store_element(fpb, collection, item, proj);
+    } else if let (TyKind::Tuple(item_types), TyKind::Tuple(target_types)) = (item_ty.kind(), target_ty.kind()) {
+        if item_types.len() != target_types.len() {
+            return;
+        }
+        for (i, (field_ty, target_field_ty)) in item_types.iter().zip(target_types.iter()).enumerate() {
+            let field_path = Path::new_field(item.clone(), i);
+            fpb.acx.set_path_rustc_type(field_path.clone(), field_ty);
+            let mut field_proj = proj.clone();
+            field_proj.push(PathSelector::Field(i));
+            store_item(fpb, collection, field_path, field_ty, target_field_ty, field_proj);
+        }
+    } else {
+        debug!("Unsupported item type {:?} for the elements of type {:?}", item_ty, target_ty);
+    }
+}
+
+/// Obtains an item of `iterable` by calling `IntoIterator::into_iter` on it and `Iterator::next`
+/// on the returned iterator. Returns the path of the item and its type.
+///
+/// ```let iter = IntoIterator::into_iter(iterable); let next = Iterator::next(&mut iter); item = (next as Some).0;```
+fn next_item<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    iterable: Rc<Path>,
+    iterable_ty: Ty<'tcx>,
+    location: mir::Location,
+) -> Option<(Rc<Path>, Ty<'tcx>)> {
+    let tcx = fpb.acx.tcx;
+    let into_iter_fn = tcx.lang_items().into_iter_fn()?;
+    let next_fn = tcx.lang_items().next_fn()?;
+    let (into_iter_def_id, into_iter_args) =
+        call_graph_builder::try_to_devirtualize(tcx, into_iter_fn, tcx.mk_args(&[iterable_ty.into()]))?;
+    let iter_ty = type_util::function_return_type(tcx, into_iter_def_id, into_iter_args);
+    let (next_def_id, next_args) =
+        call_graph_builder::try_to_devirtualize(tcx, next_fn, tcx.mk_args(&[iter_ty.into()]))?;
+    let option_ty = type_util::function_return_type(tcx, next_def_id, next_args);
+    let TyKind::Adt(_, option_args) = option_ty.kind() else {
+        return None;
+    };
+    let item_ty = option_args.type_at(0);
+
+    let iter = fpb.create_aux_local(iter_ty);
+    call_function(fpb, into_iter_def_id, into_iter_args, vec![iterable], iter.clone(), location, 1);
+    let iter_ref = fpb.create_aux_local(Ty::new_mut_ref(tcx, tcx.lifetimes.re_erased, iter_ty));
+    fpb.add_addr_edge(iter, iter_ref.clone());
+    let next = fpb.create_aux_local(option_ty);
+    call_function(fpb, next_def_id, next_args, vec![iter_ref], next.clone(), location, 2);
+
+    let item_path = option_some(&next);
+    fpb.acx.set_path_rustc_type(item_path.clone(), item_ty);
+    Some((item_path, item_ty))
+}
+
+/// Calls the function `callee_def_id` from a model, where the callee is handled as a special
+/// function if possible, e.g. by the models of the collections.
+fn call_function<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_def_id: DefId,
+    gen_args: GenericArgsRef<'tcx>,
+    args: Vec<Rc<Path>>,
+    destination: Rc<Path>,
+    location: mir::Location,
+    nth_call: usize,
+) {
+    // The callsites of the model must differ from the callsite of the modeled function.
+    let mut new_location = location;
+    new_location.statement_index += nth_call;
+    if !special_function_handler::handled_as_special_function_call(
+        fpb,
+        &callee_def_id,
+        &gen_args,
+        &args,
+        &destination,
+        new_location,
+    ) {
+        fpb.add_static_call(callee_def_id, gen_args, args, destination, new_location);
+    }
+}
+
+/// Stores `value` into the elements of the collection pointed to by `collection_ref`.
+///
+/// ```let aux: *const [E] = (*collection_ref).ptr; (*aux).index.proj = value;```
+fn store_element<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection_ref: &Rc<Path>,
+    value: Rc<Path>,
+    proj: ProjectionElems,
+) {
+    let Some((storage_ptr, elem_ty)) = get_storage_pointer(fpb, collection_ref) else {
+        return;
+    };
+    let elem_path = element_path(fpb, storage_ptr, elem_ty, proj);
+    let value_ty = fpb.acx.get_path_rustc_type(&elem_path).expect("rustc type error");
+    copy_value(fpb, value, value_ty, elem_path);
+}
+
+/// Reads the elements of the collection pointed to by `collection_ref` into `destination`.
+///
+/// ```let aux: *const [E] = (*collection_ref).ptr; dst = (*aux).index.proj;```
+fn load_element<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection_ref: &Rc<Path>,
+    proj: ProjectionElems,
+    destination: Rc<Path>,
+) {
+    let Some((storage_ptr, elem_ty)) = get_storage_pointer(fpb, collection_ref) else {
+        return;
+    };
+    let elem_path = element_path(fpb, storage_ptr, elem_ty, proj);
+    let value_ty = fpb.acx.get_path_rustc_type(&elem_path).expect("rustc type error");
+    fpb.acx.set_path_rustc_type(destination.clone(), value_ty);
+    copy_value(fpb, elem_path, value_ty, destination);
+}
+
+/// Borrows the elements of the collection pointed to by `collection_ref`.
+///
+/// ```let aux: *const [E] = (*collection_ref).ptr; dst = &(*aux).index.proj;```
+fn borrow_element<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection_ref: &Rc<Path>,
+    proj: ProjectionElems,
+    destination: Rc<Path>,
+) {
+    let Some((storage_ptr, elem_ty)) = get_storage_pointer(fpb, collection_ref) else {
+        return;
+    };
+    let elem_path = element_path(fpb, storage_ptr, elem_ty, proj);
+    fpb.add_gep_edge(elem_path, destination);
+}
+
+/// Borrows the storage of the collection pointed to by `collection_ref` as a slice.
+///
+/// ```dst = (*collection_ref).ptr;```
+fn borrow_storage<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection_ref: &Rc<Path>,
+    destination: Rc<Path>,
+) {
+    if let Some((storage_ptr, _)) = get_storage_pointer(fpb, collection_ref) {
+        fpb.add_direct_edge(storage_ptr, destination);
+    }
+}
+
+/// Reads the pointer to the storage of a collection into an auxiliary local variable of
+/// type `*const [E]`, where `collection` is either a reference to a collection or map entry, or a
+/// collection or map entry passed by value. Returns the auxiliary local variable and the element type `E`.
+fn get_storage_pointer<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    collection: &Rc<Path>,
+) -> Option<(Rc<Path>, Ty<'tcx>)> {
+    let tcx = fpb.acx.tcx;
+    let arg_ty = fpb.acx.get_path_rustc_type(collection).expect("rustc type error");
+    let (collection_path, collection_ty) = if arg_ty.is_ref() {
+        let collection_ty = type_util::get_dereferenced_type(arg_ty);
+        (fpb.create_dereference(collection.clone(), arg_ty), collection_ty)
+    } else {
+        (collection.clone(), arg_ty)
+    };
+    let (storage_proj, storage_field_ty) = storage_pointer_projections(tcx, collection_ty).into_iter().next()?;
+    let storage_field_path = Path::append_projection(&collection_path, &storage_proj);
+    fpb.acx.set_path_rustc_type(storage_field_path.clone(), storage_field_ty);
+
+    let field_aux = fpb.create_aux_local(storage_field_ty);
+    if storage_field_path.is_deref_path() {
+        fpb.add_load_edge(storage_field_path, field_aux.clone());
+    } else {
+        fpb.add_direct_edge(storage_field_path, field_aux.clone());
+    }
+    let elem_ty = element_type(tcx, collection_ty);
+    let aux = fpb.create_aux_local(Ty::new_imm_ptr(tcx, Ty::new_slice(tcx, elem_ty)));
+    add_pointer_edge(fpb, field_aux, aux.clone());
+    Some((aux, elem_ty))
+}
+
+/// Returns the path `(*storage_ptr).index.proj`.
+fn element_path<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    storage_ptr: Rc<Path>,
+    elem_ty: Ty<'tcx>,
+    proj: ProjectionElems,
+) -> Rc<Path> {
+    let mut projection = vec![PathSelector::Index];
+    projection.extend(proj);
+    deref_element(fpb, storage_ptr, elem_ty, projection)
+}
+
+/// Returns the path `(*ptr).proj`, where `ptr` points to a value of `pointee_ty`.
+fn deref_element<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    ptr: Rc<Path>,
+    pointee_ty: Ty<'tcx>,
+    proj: ProjectionElems,
+) -> Rc<Path> {
+    let tcx = fpb.acx.tcx;
+    let mut ty = match proj.first() {
+        Some(PathSelector::Index) => Ty::new_slice(tcx, pointee_ty),
+        _ => pointee_ty,
+    };
+    for selector in &proj {
+        ty = match selector {
+            PathSelector::Index => pointee_ty,
+            PathSelector::Field(i) => type_util::get_field_type(tcx, ty, *i),
+            _ => unreachable!(),
+        };
+    }
+    let mut projection = vec![PathSelector::Deref];
+    projection.extend(proj);
+    let path = Path::new_qualified(ptr, projection);
+    fpb.acx.set_path_rustc_type(path.clone(), ty);
+    path
+}
+
+/// Lets all the storage pointer fields of the value at `destination` point to the
+/// object pointed to by `ptr`.
+fn point_to<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    ptr: Rc<Path>,
+    destination: &Rc<Path>,
+    dst_ty: Ty<'tcx>,
+) {
+    for (proj, ptr_ty) in storage_pointer_projections(fpb.acx.tcx, dst_ty) {
+        let dst_ptr_path = Path::append_projection(destination, &proj);
+        fpb.acx.set_path_rustc_type(dst_ptr_path.clone(), ptr_ty);
+        add_pointer_edge(fpb, ptr.clone(), dst_ptr_path);
+    }
+}
+
+/// Adds a direct edge from `src` to `dst` if both pointers have equivalent types, otherwise
+/// adds a cast edge, e.g. from `*const [E]` to the `*const u8` control pointer of a hash table.
+fn add_pointer_edge<'tcx>(fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>, src: Rc<Path>, dst: Rc<Path>) {
+    let src_ty = fpb.acx.get_path_rustc_type(&src).expect("rustc type error");
+    let dst_ty = fpb.acx.get_path_rustc_type(&dst).expect("rustc type error");
+    if type_util::equivalent_ptr_types(fpb.acx.tcx, src_ty, dst_ty) {
+        fpb.add_direct_edge(src, dst);
+    } else {
+        fpb.add_cast_edge(src, dst);
+    }
+}
+
+/// Returns the type of the abstract elements of a collection, i.e. `(K, V)` for maps and their
+/// entries and `T` for the other collections.
+fn element_type<'tcx>(tcx: TyCtxt<'tcx>, collection_ty: Ty<'tcx>) -> Ty<'tcx> {
+    match collection_ty.kind() {
+        TyKind::Adt(def, args) => {
+            let types = args.types().collect::<Vec<_>>();
+            match tcx.item_name(def.did()).as_str() {
+                "HashMap" | "BTreeMap" | "Entry" | "OccupiedEntry" | "VacantEntry" | "RustcEntry"
+                | "RustcOccupiedEntry" | "RustcVacantEntry" => {
+                    Ty::new_tup(tcx, &[types[0], types[1]])
+                }
+                _ => types[0],
+            }
+        }
+        _ => unreachable!("Expect a collection type, found {:?}", collection_ty),
+    }
+}
+
+/// Returns true if `ty` is one of the modeled std collections.
+fn is_collection_type(tcx: TyCtxt<'_>, ty: Ty<'_>) -> bool {
+    match ty.kind() {
+        TyKind::Adt(def, _) => {
+            util::is_std_lib_func(tcx, def.did())
+                && matches!(
+                    tcx.item_name(def.did()).as_str(),
+                    "Vec" | "VecDeque" | "HashMap" | "HashSet" | "BTreeMap" | "BTreeSet"
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Returns the projections of the pointer fields of a collection or iterator type, excluding
+/// the pointer fields contained in its generic arguments (e.g. the elements, the allocator and
+/// the hasher), which do not point to the storage of the collection.
+fn storage_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(ProjectionElems, Ty<'tcx>)> {
+    let generic_types = match ty.kind() {
+        TyKind::Adt(_, args) => args.types().collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+    let mut ptr_projs = Vec::new();
+    collect_storage_pointer_projections(tcx, ty, &generic_types, Vec::new(), &mut ptr_projs);
+    ptr_projs
+}
+
+fn collect_storage_pointer_projections<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    generic_types: &[Ty<'tcx>],
+    prefix: ProjectionElems,
+    ptr_projs: &mut Vec<(ProjectionElems, Ty<'tcx>)>,
+) {
+    let mut visit_field = |proj: ProjectionElems, field_ty: Ty<'tcx>| {
+        if generic_types.contains(&field_ty) {
+            return;
+        }
+        if field_ty.is_unsafe_ptr() || field_ty.is_ref() {
+            ptr_projs.push((proj, field_ty));
+        } else {
+            collect_storage_pointer_projections(tcx, field_ty, generic_types, proj, ptr_projs);
+        }
+    };
+    match ty.kind() {
+        TyKind::Adt(def, args) if def.is_struct() => {
+            for (i, field) in def.non_enum_variant().fields.iter().enumerate() {
+                let mut proj = prefix.clone();
+                proj.push(PathSelector::Field(i));
+                visit_field(proj, type_util::field_ty(tcx, field, args));
+            }
+        }
+        TyKind::Adt(def, args) if def.is_enum() => {
+            for (variant_idx, variant) in def.variants().iter().enumerate() {
+                for (i, field) in variant.fields.iter().enumerate() {
+                    let mut proj = prefix.clone();
+                    proj.push(PathSelector::Downcast(variant_idx));
+                    proj.push(PathSelector::Field(i));
+                    visit_field(proj, type_util::field_ty(tcx, field, args));
+                }
+            }
+        }
+        TyKind::Tuple(tuple_types) => {
+            for (i, field_ty) in tuple_types.iter().enumerate() {
+                let mut proj = prefix.clone();
+                proj.push(PathSelector::Field(i));
+                visit_field(proj, field_ty);
+            }
+        }
+        _ => {}
+    }
+}
+
@@ -9,7 +9,7 @@
 
 use log::*;
 use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter, Result};
 use std::rc::Rc;
 
@@ -18,6 +18,7 @@ use rustc_hir::def_id::DefId;
 use rustc_index::IndexVec;
 use rustc_middle::mir;
 use rustc_middle::mir::interpret::{GlobalAlloc, Scalar};
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
 use rustc_middle::ty::adjustment::PointerCoercion;
 use rustc_middle::ty;
 use rustc_middle::ty::{Const, Ty, TyCtxt, TyKind, GenericArgsRef};
@@ -50,6 +51,10 @@ pub struct FuncPAGBuilder<'pta, 'tcx, 'compilation> {
 
     /// Caches the path for each place visited in this function
     path_cache: HashMap<mir::Place<'tcx>, Rc<Path>>,
+
+    /// The locals that are only assigned with a constant index once, e.g. `_3 = const 1_usize`,
+    /// which makes `_1[_3]` a constant-index access. Only computed for `--index-sensitive-arrays`.
+    constant_index_locals: HashMap<mir::Local, usize>,
 }
 
 impl<'pta, 'tcx, 'compilation> Debug for FuncPAGBuilder<'pta, 'tcx, 'compilation> {
@@ -77,6 +82,11 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
         );
         let aux_local_index = mir.local_decls.len();
         acx.aux_local_indexer.insert(func_id, aux_local_index);
+        let constant_index_locals = if acx.analysis_options.index_sensitive_arrays.is_some() {
+            find_constant_index_locals(acx.tcx, mir)
+        } else {
+            HashMap::new()
+        };
         FuncPAGBuilder {
             acx,
             func_id,
@@ -85,6 +95,7 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
             fpag,
             substs_specializer,
             path_cache: HashMap::new(),
+            constant_index_locals,
         }
     }
 
@@ -142,6 +153,59 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
             self.acx.set_path_rustc_type(static_variable.clone(), ret_type);
             self.add_internal_edges(ret_path, ret_type, static_variable, ret_type);
         }
+
+        if self.acx.entry_points.contains(&self.func_id) {
+            self.add_entry_argument_objects();
+        }
+    }
+
+    /// Models the arguments of an entry function in the style of a most general client, i.e. 
+    /// each pointer in the arguments points to the abstract object of its pointee type, and the 
+    /// pointers in an abstract object point to the abstract objects of their pointee types.
+    fn add_entry_argument_objects(&mut self) {
+        let mut visited_types = HashSet::new();
+        for ordinal in 1..=self.mir.arg_count {
+            let param = Path::new_parameter(self.func_id, ordinal);
+            let param_ty = self
+                .substs_specializer
+                .specialize_generic_argument_type(self.mir.local_decls[ordinal.into()].ty);
+            self.acx.set_path_rustc_type(param.clone(), param_ty);
+            self.add_abstract_pointees(param, param_ty, &mut visited_types);
+        }
+    }
+
+    /// Lets each pointer contained in `path` point to the abstract object of its pointee type.
+    fn add_abstract_pointees(
+        &mut self,
+        path: Rc<Path>,
+        path_ty: Ty<'tcx>,
+        visited_types: &mut HashSet<Ty<'tcx>>,
+    ) {
+        let ptr_projs = if path_ty.is_any_ptr() {
+            vec![(vec![], path_ty)]
+        } else {
+            self.acx.get_pointer_projections(path_ty).clone()
+        };
+        for (proj, ptr_ty) in ptr_projs {
+            let pointee_ty = type_util::get_dereferenced_type(ptr_ty);
+            // The callees of function pointers and the concrete types of trait objects are unknown.
+            if ptr_ty.is_fn_ptr() || matches!(pointee_ty.kind(), TyKind::Dynamic(..) | TyKind::Foreign(..)) {
+                continue;
+            }
+            let ptr_path = Path::append_projection(&path, &proj);
+            self.acx.set_path_rustc_type(ptr_path.clone(), ptr_ty);
+            let type_index = self.acx.get_type_index(&pointee_ty);
+            let object = Path::new_abstract_object(type_index);
+            self.acx.set_path_rustc_type(object.clone(), pointee_ty);
+
+            // ``` let aux = &object; ptr_path = aux; ```
+            let aux = self.create_aux_local(ptr_ty);
+            self.add_addr_edge(object.clone(), aux.clone());
+            self.add_direct_edge(aux, ptr_path);
+            if visited_types.insert(pointee_ty) {
+                self.add_abstract_pointees(object, pointee_ty, visited_types);
+            }
+        }
     }
 
     pub fn visit_body(&mut self) {
@@ -239,7 +303,12 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
         };
         let src_ptr = get_ptr_path(&copy_info.src);
         let dst_ptr = get_ptr_path(&copy_info.dst);
+        self.copy_pointees(src_ptr, dst_ptr);
+    }
 
+    /// Copies the value pointed to by `src_ptr` to the memory pointed to by `dst_ptr`, both of 
+    /// which are pointers to the pointee type of `src_ptr`.
+    pub fn copy_pointees(&mut self, src_ptr: Rc<Path>, dst_ptr: Rc<Path>) {
         // convert it to `` let aux = *src_ptr; *dst_ptr = aux ``
         let deref_ty = type_util::get_dereferenced_type(self.acx.get_path_rustc_type(&src_ptr).unwrap());
         let aux = self.create_aux_local(deref_ty);
@@ -411,7 +480,11 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
             mir::Rvalue::Ref(_, _, place) | mir::Rvalue::AddressOf(_, place) => {
                 self.visit_ref_or_address_of(lh_path, place);
             }
-            mir::Rvalue::ThreadLocalRef(_def_id) => {}
+            mir::Rvalue::ThreadLocalRef(def_id) => {
+                if self.acx.analysis_options.model_lazy_globals {
+                    self.visit_thread_local_ref(lh_path, *def_id);
+                }
+            }
             mir::Rvalue::Len(_place) => {}
             mir::Rvalue::Cast(cast_kind, operand, ty) => {
                 let specialized_ty = self.substs_specializer.specialize_generic_argument_type(*ty);
@@ -635,6 +708,26 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
         }
     }
 
+    /// Analyzes the `thread_local_ref` assignment, e.g. `path = &/*tls*/ KEY`.
+    /// 
+    /// The thread local static is modeled as a static variable shared by all threads.
+    fn visit_thread_local_ref(&mut self, lh_path: Rc<Path>, def_id: DefId) {
+        let static_variable = Path::new_static_variable(def_id);
+        let static_variable_ty = self.tcx().type_of(def_id).skip_binder();
+        self.acx
+            .set_path_rustc_type(static_variable.clone(), static_variable_ty);
+        self.fpag.add_static_variables_involved(static_variable.clone());
+
+        if lh_path.is_deref_path() {
+            let lh_type = self.acx.get_path_rustc_type(&lh_path).unwrap();
+            let aux = self.create_aux_local(lh_type);
+            self.add_addr_edge(static_variable, aux.clone());
+            self.add_store_edge(aux, lh_path);
+        } else {
+            self.add_addr_edge(static_variable, lh_path);
+        }
+    }
+
     /// Analyzes the `ref` and `address_of` assignments.
     /// 
     /// Ref: Creates a reference of the indicated kind to the place. e.g. `path = &x` or `&mut x`
@@ -920,11 +1013,17 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
     ) {
         match aggregate_kind {
             mir::AggregateKind::Array(ty) => {
-                let index_path = Path::new_index(lh_path.clone());
                 let index_ty = self.substs_specializer.specialize_generic_argument_type(*ty);
-                self.acx.set_path_rustc_type(index_path.clone(), index_ty);
-                for (_i, operand) in operands.iter().enumerate() {
-                    self.visit_use(index_path.clone(), operand);
+                let lh_ty = self.acx.get_path_rustc_type(&lh_path).unwrap();
+                let index_sensitive = self.acx.index_sensitive_array_len(lh_ty).is_some();
+                for (i, operand) in operands.iter().enumerate() {
+                    let index_path = if index_sensitive {
+                        Path::append_projection_elem(&lh_path, PathSelector::ConstantIndex(i))
+                    } else {
+                        Path::new_index(lh_path.clone())
+                    };
+                    self.acx.set_path_rustc_type(index_path.clone(), index_ty);
+                    self.visit_use(index_path, operand);
                 }
             }
             mir::AggregateKind::Tuple => {
@@ -1060,8 +1159,10 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
         if !util::is_trait_method(self.tcx(), *callee_def_id)
         {
             // Static functions or methods or associated functions not declared on a trait.
+            // Functions declared in `extern` blocks are resolved to their definitions if exist.
+            let callee_def_id = self.acx.resolve_foreign_item(*callee_def_id).unwrap_or(*callee_def_id);
             let callsite = self.new_callsite(self.func_id, location, args, destination);
-            let callee_func_id = self.acx.get_func_id(*callee_def_id, gen_args);
+            let callee_func_id = self.acx.get_func_id(callee_def_id, gen_args);
             self.fpag.add_static_dispatch_callsite(callsite, callee_func_id);
         } else if let Some((callee_def_id, callee_substs)) =
             call_graph_builder::try_to_devirtualize(self.tcx(), *callee_def_id, gen_args)
@@ -1089,7 +1190,8 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
         }
     }
 
-    fn resolve_fntrait_call(
+    /// Resolves a call to `Fn::call`, `FnMut::call_mut` or `FnOnce::call_once`.
+    pub fn resolve_fntrait_call(
         &mut self,
         callee_def_id: &DefId,
         gen_args: &GenericArgsRef<'tcx>,
@@ -1451,8 +1553,7 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
     }
 
     /// Creates a dereference path for the given pointer or reference path.
-    #[allow(unused)]
-    fn create_dereference(&mut self, ptr_path: Rc<Path>, ptr_ty: Ty<'tcx>) -> Rc<Path> {
+    pub fn create_dereference(&mut self, ptr_path: Rc<Path>, ptr_ty: Ty<'tcx>) -> Rc<Path> {
         let deref_path = if let PathEnum::QualifiedPath { .. } = ptr_path.value {
             let aux = self.create_aux_local(ptr_ty);
             self.add_direct_edge(ptr_path, aux.clone());
@@ -1465,6 +1566,64 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
         deref_path
     }
 
+    /// Returns the pointer that `ptr` is cast from, e.g. `_4` for `_5 = move _4 as *mut c_void`,
+    /// which is assigned before `location` in the chain of basic blocks with a single predecessor.
+    /// The cast chains are followed until a pointer that is not cast from another pointer is found.
+    /// A raw pointer taken to the pointee of a reference, e.g. `_4 = &raw const (*_3)` generated for
+    /// `_3 as *const T`, is regarded as cast from the reference.
+    pub fn get_cast_source(&mut self, ptr: Rc<Path>, location: mir::Location) -> Rc<Path> {
+        let mut visited = HashSet::from([location.block]);
+        self.get_cast_source_in_chain(ptr, location, &mut visited)
+    }
+
+    /// Follows the cast chain of `ptr` backwards from `location`, stopping at the blocks that
+    /// have been visited, which are only revisited through a cycle of single predecessors.
+    fn get_cast_source_in_chain(
+        &mut self,
+        ptr: Rc<Path>,
+        location: mir::Location,
+        visited: &mut HashSet<mir::BasicBlock>,
+    ) -> Rc<Path> {
+        let PathEnum::LocalVariable { ordinal, .. } = ptr.value else {
+            return ptr;
+        };
+        let mut block = location.block;
+        let mut end = location.statement_index;
+        loop {
+            let statements = &self.mir.basic_blocks[block].statements;
+            for statement_index in (0..end.min(statements.len())).rev() {
+                let mir::StatementKind::Assign(box (place, rvalue)) = &statements[statement_index].kind else {
+                    continue;
+                };
+                if place.local.as_usize() != ordinal || !place.projection.is_empty() {
+                    continue;
+                }
+                return match rvalue {
+                    mir::Rvalue::Cast(mir::CastKind::PtrToPtr, mir::Operand::Copy(source), _)
+                    | mir::Rvalue::Cast(mir::CastKind::PtrToPtr, mir::Operand::Move(source), _) => {
+                        let source_path = self.get_path_for_place(source);
+                        self.get_cast_source_in_chain(source_path, mir::Location { block, statement_index }, visited)
+                    }
+                    mir::Rvalue::AddressOf(_, source)
+                        if source.projection.as_slice() == [mir::ProjectionElem::Deref]
+                            && self.mir.local_decls[source.local].ty.is_ref() =>
+                    {
+                        let source_path = self.get_path_for_place(&mir::Place::from(source.local));
+                        self.get_cast_source_in_chain(source_path, mir::Location { block, statement_index }, visited)
+                    }
+                    _ => ptr,
+                };
+            }
+            match self.mir.basic_blocks.predecessors()[block].as_slice() {
+                [predecessor] if visited.insert(*predecessor) => {
+                    block = *predecessor;
+                    end = usize::MAX;
+                }
+                _ => return ptr,
+            }
+        }
+    }
+
     /// Returns the parameter environment for the current function.
     pub fn get_param_env(&self) -> rustc_middle::ty::ParamEnv<'tcx> {
         let def_id = self.def_id();
@@ -1490,12 +1649,13 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
             source_path, source_rustc_type, target_path, target_rustc_type
         );
         let param_env = self.get_param_env();
+        let variant_sensitive_enums = self.acx.analysis_options.variant_sensitive_enums;
         let src_flattened_fields =
-            type_util::flatten_fields(self.tcx(), param_env, source_path, source_rustc_type);
+            type_util::flatten_fields(self.tcx(), param_env, source_path, source_rustc_type, variant_sensitive_enums);
         debug!("flattened fields of source value: {:?}", src_flattened_fields);
 
         let tgt_flattened_fields =
-            type_util::flatten_fields(self.tcx(), param_env, target_path, target_rustc_type);
+            type_util::flatten_fields(self.tcx(), param_env, target_path, target_rustc_type, variant_sensitive_enums);
         debug!("flattened fields of target value: {:?}", tgt_flattened_fields);
 
         self.copy_flattened_fields(src_flattened_fields, tgt_flattened_fields);
@@ -1506,21 +1666,12 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
         src_flattened_fields: Vec<(usize, Rc<Path>, Ty<'tcx>)>,
         tgt_flattened_fields: Vec<(usize, Rc<Path>, Ty<'tcx>)>,
     ) {
-        let src_len = src_flattened_fields.len();
-        let tgt_len = tgt_flattened_fields.len();
-        let mut src_field_index = 0;
-        let mut tgt_field_index = 0;
-        while tgt_field_index < tgt_len && src_field_index < src_len {
+        for (src_field_index, tgt_field_index) in
+            type_util::pair_flattened_fields(&src_flattened_fields, &tgt_flattened_fields)
+        {
             // Both the src_type and tgt_type should have been specialized.
-            let (tgt_offset, tgt_field, tgt_type) = &tgt_flattened_fields[tgt_field_index];
-            let (src_offset, src_field, src_type) = &src_flattened_fields[src_field_index];
-            if *tgt_offset < *src_offset {
-                tgt_field_index += 1;
-                continue;
-            } else if *tgt_offset > *src_offset {
-                src_field_index += 1;
-                continue;
-            }
+            let (_, tgt_field, tgt_type) = &tgt_flattened_fields[tgt_field_index];
+            let (_, src_field, src_type) = &src_flattened_fields[src_field_index];
 
             // if source type and target type are any kind of primitive pointer type (reference, raw pointer, fn pointer).
             if src_type.is_any_ptr() && tgt_type.is_any_ptr() {
@@ -1528,8 +1679,6 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
                 self.acx.set_path_rustc_type(tgt_field.clone(), *tgt_type);
                 self.transmute_pointers(src_field.clone(), *src_type, tgt_field.clone(), *tgt_type);
             }
-            tgt_field_index += 1;
-            src_field_index += 1;
         }
     }
 
@@ -1719,8 +1868,25 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
                 }
                 PathSelector::Field(field.index())
             }
-            mir::ProjectionElem::Index(_)
-            | mir::ProjectionElem::ConstantIndex { .. } => PathSelector::Index,
+            mir::ProjectionElem::Index(local) => {
+                match (self.acx.index_sensitive_array_len(base_ty), self.constant_index_locals.get(local)) {
+                    (Some(len), Some(index)) if *index < len => PathSelector::ConstantIndex(*index),
+                    _ => PathSelector::Index,
+                }
+            }
+            mir::ProjectionElem::ConstantIndex { offset, min_length: _, from_end } => {
+                // The length of a slice is unknown, so the elements counted from the end
+                // can only be distinguished for arrays.
+                match self.acx.index_sensitive_array_len(base_ty) {
+                    Some(len) if !*from_end && (*offset as usize) < len => {
+                        PathSelector::ConstantIndex(*offset as usize)
+                    }
+                    Some(len) if *from_end && (*offset as usize) <= len && *offset > 0 => {
+                        PathSelector::ConstantIndex(len - *offset as usize)
+                    }
+                    _ => PathSelector::Index,
+                }
+            }
             mir::ProjectionElem::Downcast(_name, index) => PathSelector::Downcast(index.as_usize()),
             mir::ProjectionElem::Subslice { from, to, from_end } => PathSelector::Subslice {
                 from: *from,
@@ -1766,10 +1932,21 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
 
     /// Adds a store edge from `src` to `dst`.
     /// Given a store statement ```(*p).f1.f2...fn = q```, a store edge of format `q --STORE(f1.f2...fn)--> p` is added.
+    ///
+    /// With the `--prune-shared-ref-stores` option, the store edge is dropped if `p` is a shared
+    /// reference to a type without interior mutability, as the pointee cannot be written through it.
     #[inline]
     pub fn add_store_edge(&mut self, src: Rc<Path>, dst: Rc<Path>) {
         if let PathEnum::QualifiedPath { base, projection } = &dst.value {
             assert_eq!(projection[0], PathSelector::Deref);
+            if self.acx.analysis_options.prune_shared_ref_stores {
+                if let Some(base_ty) = self.acx.get_path_rustc_type(base) {
+                    if type_util::is_shared_ref_to_freeze(self.tcx(), base_ty) {
+                        debug!("Pruned the store from {:?} to {:?} through a shared reference", src, dst);
+                        return;
+                    }
+                }
+            }
             let store_proj = Vec::from_iter(projection[1..].iter().cloned());
             self.add_edge(src, base.clone(), PAGEdgeEnum::StorePAGEdge(store_proj));
         } else {
@@ -1815,10 +1992,34 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
     }
 
     /// Adds an internal edge from `src` to `dst` of `kind` to the function pag.
+    ///
+    /// If `dst` is an element of an index-sensitive array, the edge is also added to the paths
+    /// referring to the same element, so that the summarizing element stays consistent with
+    /// the constant-index elements.
     pub fn add_edge(&mut self, src: Rc<Path>, dst: Rc<Path>, kind: PAGEdgeEnum) {
+        if !matches!(kind, PAGEdgeEnum::StorePAGEdge(..)) {
+            for variant in self.acx.get_index_variants(&dst) {
+                self.fpag.add_internal_edge(src.clone(), variant, kind.clone());
+            }
+        }
         self.fpag.add_internal_edge(src, dst, kind);
     }
     
+    /// Adds a call to the function `callee_def_id` instantiated with `gen_args` that does not
+    /// appear in the MIR, e.g. a call made by the runtime on behalf of the current function.
+    pub fn add_static_call(
+        &mut self,
+        callee_def_id: DefId,
+        gen_args: GenericArgsRef<'tcx>,
+        args: Vec<Rc<Path>>,
+        destination: Rc<Path>,
+        location: mir::Location,
+    ) {
+        let callsite = self.new_callsite(self.func_id, location, args, destination);
+        let callee_func_id = self.acx.get_func_id(callee_def_id, gen_args);
+        self.fpag.add_static_dispatch_callsite(callsite, callee_func_id);
+    }
+
     /// Creates a new callsite.
     fn new_callsite(
         &mut self,
@@ -1831,3 +2032,39 @@ impl<'pta, 'tcx, 'compilation> FuncPAGBuilder<'pta, 'tcx, 'compilation> {
     }
 
 }
+
+/// Finds the locals that are assigned exactly once with a constant `usize` and never mutated
+/// otherwise, so that indexing with them is indexing with a constant.
+pub(crate) fn find_constant_index_locals<'tcx>(tcx: TyCtxt<'tcx>, mir: &mir::Body<'tcx>) -> HashMap<mir::Local, usize> {
+    struct MutatingUseCounter {
+        counts: HashMap<mir::Local, usize>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for MutatingUseCounter {
+        fn visit_local(&mut self, local: mir::Local, context: PlaceContext, _location: mir::Location) {
+            if context.is_mutating_use() {
+                *self.counts.entry(local).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut counter = MutatingUseCounter { counts: HashMap::new() };
+    counter.visit_body(mir);
+
+    let param_env = ty::ParamEnv::reveal_all();
+    let mut constant_index_locals = HashMap::new();
+    for bb_data in mir.basic_blocks.iter() {
+        for statement in &bb_data.statements {
+            if let mir::StatementKind::Assign(box (place, mir::Rvalue::Use(mir::Operand::Constant(const_op)))) =
+                &statement.kind
+            {
+                if place.projection.is_empty() && counter.counts.get(&place.local) == Some(&1) {
+                    if let Some(index) = const_op.const_.try_eval_target_usize(tcx, param_env) {
+                        constant_index_locals.insert(place.local, index as usize);
+                    }
+                }
+            }
+        }
+    }
+    constant_index_locals
+}
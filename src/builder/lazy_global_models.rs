@@ -0,0 +1,255 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Provides summaries for the lazily initialized globals, i.e. the thread locals declared with
+//! `thread_local!`, `OnceCell`, `OnceLock`, `LazyCell`, `LazyLock` and the globals declared with
+//! `lazy_static!`.
+//!
+//! These globals are initialized by a closure on their first access, which goes through function
+//! pointers, `Once` and `UnsafeCell` machinery in the standard library. Instead, each global is
+//! treated like a static variable: the value returned by the initializer is stored into the slot
+//! of the global that holds the value, and the accessors return the address of that slot.
+//! The summaries are only enabled with the `--model-lazy-globals` option.
+
+use std::rc::Rc;
+
+use rustc_hir::def_id::DefId;
+use rustc_hir::lang_items::LangItem;
+use rustc_middle::mir;
+use rustc_middle::ty::{GenericArgsRef, Ty, TyCtxt, TyKind};
+
+use crate::builder::call_graph_builder;
+use crate::builder::fpag_builder::FuncPAGBuilder;
+use crate::builder::special_function_handler::{copy_value, option_some, RESULT_ERR};
+use crate::mir::known_names::KnownNames;
+use crate::mir::path::{Path, PathSelector, PathSupport, ProjectionElems};
+use crate::util::type_util;
+
+/// Handles the calls to the accessors of the lazily initialized globals.
+///
+/// Returns true if the callee is modeled, false if the call should be analyzed as usual.
+pub fn handled_as_lazy_global_call<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_def_id: &DefId,
+    gen_args: &GenericArgsRef<'tcx>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) -> bool {
+    // `Deref::deref` of `LazyCell` and `LazyLock` is devirtualized first, whose generic
+    // arguments are the ones of the impl instead of the trait.
+    let (callee_def_id, gen_args) =
+        match call_graph_builder::try_to_devirtualize(fpb.acx.tcx, *callee_def_id, gen_args) {
+            Some((def_id, args)) => (def_id, args),
+            None => (*callee_def_id, *gen_args),
+        };
+    let callee_known_name = fpb.acx.get_known_name_for(callee_def_id);
+    match callee_known_name {
+        KnownNames::StdOnceCellGetOrInit
+        | KnownNames::StdOnceLockGetOrInit
+        | KnownNames::StdThreadLocalKeyGet
+        | KnownNames::LazyStaticLazyGet => {
+            let value_ty = gen_args.type_at(0);
+            let Some(slot) = value_slot(fpb, &args[0], value_ty) else {
+                return false;
+            };
+            let init_ty = gen_args.type_at(1);
+            let value = call_initializer(fpb, args[1].clone(), init_ty, value_ty, location);
+            copy_value(fpb, value, value_ty, slot.clone());
+            let dst = if callee_known_name == KnownNames::StdThreadLocalKeyGet {
+                option_some(destination)
+            } else {
+                destination.clone()
+            };
+            borrow_slot(fpb, slot, dst);
+        }
+        KnownNames::StdOnceCellGet | KnownNames::StdOnceLockGet => {
+            let value_ty = gen_args.type_at(0);
+            let Some(slot) = value_slot(fpb, &args[0], value_ty) else {
+                return false;
+            };
+            borrow_slot(fpb, slot, option_some(destination));
+        }
+        KnownNames::StdOnceCellSet | KnownNames::StdOnceLockSet => {
+            let value_ty = gen_args.type_at(0);
+            let Some(slot) = value_slot(fpb, &args[0], value_ty) else {
+                return false;
+            };
+            copy_value(fpb, args[1].clone(), value_ty, slot);
+            // The value is given back in `Err` if the cell has been initialized.
+            let err_path = Path::append_projection(
+                destination,
+                &vec![PathSelector::Downcast(RESULT_ERR), PathSelector::Field(0)],
+            );
+            fpb.acx.set_path_rustc_type(err_path.clone(), value_ty);
+            copy_value(fpb, args[1].clone(), value_ty, err_path);
+        }
+        KnownNames::StdOnceCellIntoInner | KnownNames::StdOnceLockIntoInner => {
+            let value_ty = gen_args.type_at(0);
+            let Some(slot) = value_slot(fpb, &args[0], value_ty) else {
+                return false;
+            };
+            let dst = option_some(destination);
+            fpb.acx.set_path_rustc_type(dst.clone(), value_ty);
+            copy_value(fpb, slot, value_ty, dst);
+        }
+        KnownNames::StdLazyCellNew | KnownNames::StdLazyLockNew => {
+            // The initializer is kept in the global until the first access.
+            let init_ty = gen_args.type_at(1);
+            let Some(init_slot) = value_slot(fpb, destination, init_ty) else {
+                return false;
+            };
+            copy_value(fpb, args[0].clone(), init_ty, init_slot);
+        }
+        KnownNames::StdThreadLocalKeyNew => {
+            // `LocalKey::new` is called in the constant initializers of the thread locals, which
+            // are not reachable in the call graph, therefore the accessor is stored directly.
+            let inner = args[0].clone();
+            let inner_ty = fpb.acx.get_path_rustc_type(&inner).expect("rustc type error");
+            let Some(inner_slot) = value_slot(fpb, destination, inner_ty) else {
+                return false;
+            };
+            copy_value(fpb, inner, inner_ty, inner_slot);
+        }
+        KnownNames::StdLazyCellForce | KnownNames::StdLazyLockForce => {
+            let value_ty = gen_args.type_at(0);
+            let init_ty = gen_args.type_at(1);
+            let (Some(slot), Some(init_slot)) = (
+                value_slot(fpb, &args[0], value_ty),
+                value_slot(fpb, &args[0], init_ty),
+            ) else {
+                return false;
+            };
+            let init = fpb.create_aux_local(init_ty);
+            copy_value(fpb, init_slot, init_ty, init.clone());
+            let value = call_initializer(fpb, init, init_ty, value_ty, location);
+            copy_value(fpb, value, value_ty, slot.clone());
+            borrow_slot(fpb, slot, destination.clone());
+        }
+        _ => {
+            return false;
+        }
+    }
+    true
+}
+
+/// Calls the initializer `init` of type `init_ty` via `FnOnce::call_once` and returns the
+/// auxiliary local variable that receives the initialized value.
+fn call_initializer<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    init: Rc<Path>,
+    init_ty: Ty<'tcx>,
+    value_ty: Ty<'tcx>,
+    location: mir::Location,
+) -> Rc<Path> {
+    let tcx = fpb.acx.tcx;
+    let fn_once_def_id = tcx.require_lang_item(LangItem::FnOnce, None);
+    let fn_once_args = tcx.mk_args(&[init_ty.into(), tcx.types.unit.into()]);
+    // The initializer takes no arguments, which are passed as an empty tuple.
+    let aux_arg = fpb.create_aux_local(tcx.types.unit);
+    let aux_dst = fpb.create_aux_local(value_ty);
+    // The callsite of the initializer must differ from the callsite of the accessor.
+    let mut new_location = location;
+    new_location.statement_index += 1;
+    fpb.inline_indirectly_called_function(
+        &fn_once_def_id,
+        &fn_once_args,
+        vec![init, aux_arg],
+        aux_dst.clone(),
+        new_location,
+    );
+    aux_dst
+}
+
+/// Returns the path of the slot of type `target_ty` in the global `global`, which is either a
+/// reference to the global or the global passed by value. Returns `None` if the global does not
+/// hold such a slot in place, e.g. the thread locals stored with OS-based keys.
+fn value_slot<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    global: &Rc<Path>,
+    target_ty: Ty<'tcx>,
+) -> Option<Rc<Path>> {
+    let tcx = fpb.acx.tcx;
+    let arg_ty = fpb.acx.get_path_rustc_type(global).expect("rustc type error");
+    let global_ty = if arg_ty.is_any_ptr() {
+        type_util::get_dereferenced_type(arg_ty)
+    } else {
+        arg_ty
+    };
+    let projection = slot_projection(tcx, global_ty, target_ty)?;
+    let global_path = if arg_ty.is_any_ptr() {
+        fpb.create_dereference(global.clone(), arg_ty)
+    } else {
+        global.clone()
+    };
+    let slot = Path::append_projection(&global_path, &projection);
+    fpb.acx.set_path_rustc_type(slot.clone(), target_ty);
+    Some(slot)
+}
+
+/// Returns the projection from a value of `ty` to the field of `target_ty` wrapped in it, e.g.
+/// `inner.value.as_variant#1.0` for `OnceCell<T> { inner: UnsafeCell<Option<T>> }`.
+///
+/// At each level, a field of `target_ty` is preferred, otherwise the first field whose type
+/// contains `target_ty` is followed. The marker fields such as `PhantomData<T>` are skipped.
+fn slot_projection<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, target_ty: Ty<'tcx>) -> Option<ProjectionElems> {
+    if ty == target_ty {
+        return Some(vec![]);
+    }
+    let TyKind::Adt(def, args) = ty.kind() else {
+        return None;
+    };
+    if def.is_phantom_data() {
+        return None;
+    }
+    let mut candidates = Vec::new();
+    for (variant_index, variant) in def.variants().iter_enumerated() {
+        for (i, field) in variant.fields.iter().enumerate() {
+            let field_ty = type_util::field_ty(tcx, field, args);
+            let selectors = if def.is_enum() {
+                vec![PathSelector::Downcast(variant_index.as_usize()), PathSelector::Field(i)]
+            } else if def.is_union() {
+                vec![PathSelector::UnionField(i)]
+            } else {
+                vec![PathSelector::Field(i)]
+            };
+            candidates.push((selectors, field_ty));
+        }
+    }
+    let exact = candidates.iter().position(|(_, field_ty)| *field_ty == target_ty);
+    let ordered = exact.into_iter().chain(0..candidates.len());
+    for index in ordered {
+        let (selectors, field_ty) = &candidates[index];
+        if !field_ty.walk().any(|arg| arg == target_ty.into()) {
+            continue;
+        }
+        if let Some(rest) = slot_projection(tcx, *field_ty, target_ty) {
+            let mut projection = selectors.clone();
+            projection.extend(rest);
+            return Some(projection);
+        }
+    }
+    None
+}
+
+/// Takes the address of `slot`.
+///
+/// ```let aux = &slot; dst = aux;```
+fn borrow_slot<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    slot: Rc<Path>,
+    destination: Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let slot_ty = fpb.acx.get_path_rustc_type(&slot).expect("rustc type error");
+    let aux = fpb.create_aux_local(Ty::new_imm_ref(tcx, tcx.lifetimes.re_erased, slot_ty));
+    if slot.is_deref_path() {
+        fpb.add_gep_edge(slot, aux.clone());
+    } else {
+        fpb.add_addr_edge(slot, aux.clone());
+    }
+    fpb.add_direct_edge(aux, destination);
+}
+
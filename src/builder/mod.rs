@@ -1,4 +1,8 @@
 pub mod call_graph_builder;
+pub mod collection_models;
 pub mod fpag_builder;
+pub mod lazy_global_models;
+pub mod runtime_models;
+pub mod smart_pointer_models;
 pub mod special_function_handler;
 pub mod substs_specializer;
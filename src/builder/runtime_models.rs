@@ -0,0 +1,229 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Connects the calls into the Rust runtime to the functions registered by the program, i.e. the
+//! `#[global_allocator]`, the `#[panic_handler]` and the panic hooks.
+//!
+//! With the `--resolve-global-allocator` option, each allocation or deallocation is still modeled
+//! with a heap object, and is additionally connected to the allocator shim generated for the
+//! registered global allocator (e.g. `__rust_alloc`), which forwards to `GlobalAlloc::alloc`.
+//! The pointers returned by the global allocator also flow to the result of the allocation.
+//!
+//! With the `--resolve-panic-handler` option, the functions that start panicking, e.g.
+//! `core::panicking::panic_fmt` and `std::panicking::begin_panic`, call the function registered
+//! with `#[panic_handler]`. If the handler is the one of the standard library, the panic hooks are
+//! called instead, which are also called from `std::panicking::rust_panic_with_hook`.
+//! The hooks registered with `std::panic::set_hook` are stored in a global variable identified
+//! by the def id of `set_hook`.
+
+use std::rc::Rc;
+
+use rustc_hir::def_id::{DefId, CRATE_DEF_INDEX};
+use rustc_hir::lang_items::LangItem;
+use rustc_middle::mir;
+use rustc_middle::ty::{Ty, TyCtxt, TyKind};
+use rustc_span::symbol::sym;
+
+use crate::builder::fpag_builder::FuncPAGBuilder;
+use crate::builder::special_function_handler::const_u8_rawptr_type;
+use crate::mir::known_names::KnownNames;
+use crate::mir::path::{Path, PathSelector};
+use crate::util::type_util;
+
+/// Adds a call from an allocation or deallocation to the corresponding allocator shim of the
+/// registered global allocator. Does nothing if no global allocator is registered.
+pub fn call_global_allocator<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_known_name: KnownNames,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) {
+    // The shim to be called and the pointer passed to the shim, if any.
+    let (shim_name, ptr_arg) = match callee_known_name {
+        KnownNames::RustAlloc
+        | KnownNames::StdAllocAlloc
+        | KnownNames::StdAllocExchangeMalloc
+        | KnownNames::StdAllocAllocatorAllocate => ("__rust_alloc", None),
+        KnownNames::RustAllocZeroed
+        | KnownNames::StdAllocAllocZeroed
+        | KnownNames::StdAllocAllocatorAllocateZeroed => ("__rust_alloc_zeroed", None),
+        KnownNames::RustDealloc | KnownNames::StdAllocDealloc => ("__rust_dealloc", Some(args[0].clone())),
+        KnownNames::StdAllocAllocatorDeallocate => ("__rust_dealloc", Some(Path::new_field(args[1].clone(), 0))),
+        KnownNames::RustRealloc | KnownNames::StdAllocRealloc => ("__rust_realloc", Some(args[0].clone())),
+        KnownNames::StdAllocAllocatorGrow
+        | KnownNames::StdAllocAllocatorGrowZeroed
+        | KnownNames::StdAllocAllocatorShrink => ("__rust_realloc", Some(Path::new_field(args[1].clone(), 0))),
+        _ => return,
+    };
+    let Some(shim_def_id) = fpb.acx.get_global_allocator_shim(shim_name) else {
+        return;
+    };
+    let tcx = fpb.acx.tcx;
+    let shim_sig = tcx.instantiate_bound_regions_with_erased(tcx.fn_sig(shim_def_id).instantiate_identity());
+
+    // The shims take the pointer to be freed or reallocated as the first argument,
+    // the other arguments are sizes and alignments.
+    let shim_args: Vec<Rc<Path>> = shim_sig.inputs().iter().map(|ty| fpb.create_aux_local(*ty)).collect();
+    if let Some(ptr_arg) = ptr_arg {
+        let ptr_ty = type_util::try_eval_path_type(fpb.acx, &ptr_arg).expect("rustc type error");
+        fpb.acx.set_path_rustc_type(ptr_arg.clone(), ptr_ty);
+        if type_util::equivalent_ptr_types(tcx, ptr_ty, shim_sig.inputs()[0]) {
+            fpb.add_direct_edge(ptr_arg, shim_args[0].clone());
+        } else {
+            fpb.add_cast_edge(ptr_arg, shim_args[0].clone());
+        }
+    }
+
+    // The shims for allocation and reallocation return `*mut u8`.
+    let output_ty = shim_sig.output();
+    let shim_destination = if !output_ty.is_unsafe_ptr() {
+        fpb.create_aux_local(output_ty)
+    } else {
+        let destination_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+        if type_util::equivalent_ptr_types(tcx, destination_ty, output_ty) {
+            destination.clone()
+        } else {
+            // The `Allocator` methods return `Result<NonNull<[u8]>, AllocError>`.
+            // ((dst as Ok).0).0: *const [u8]
+            let shim_destination = fpb.create_aux_local(output_ty);
+            let projection = vec![
+                PathSelector::Downcast(0),
+                PathSelector::Field(0),
+                PathSelector::Field(0),
+            ];
+            let dst_ptr_path = Path::new_qualified(destination.clone(), projection);
+            fpb.acx.set_path_rustc_type(dst_ptr_path.clone(), const_u8_rawptr_type(tcx));
+            fpb.add_cast_edge(shim_destination.clone(), dst_ptr_path);
+            shim_destination
+        }
+    };
+
+    // The callsite of the shim must differ from the callsite of the allocation.
+    let mut new_location = location;
+    new_location.statement_index += 1;
+    fpb.add_static_call(shim_def_id, tcx.mk_args(&[]), shim_args, shim_destination, new_location);
+}
+
+/// Handles the calls that register the panic hooks or start panicking.
+///
+/// Returns true if the callee is modeled, false if the call should be analyzed as usual.
+pub fn handled_as_panic_call<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_def_id: &DefId,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) -> bool {
+    match fpb.acx.get_known_name_for(*callee_def_id) {
+        KnownNames::StdPanickingSetHook => {
+            let hooks = panic_hooks(fpb, *callee_def_id);
+            let hook_ty = fpb.acx.get_path_rustc_type(&hooks).expect("rustc type error");
+            fpb.add_internal_edges(args[0].clone(), hook_ty, hooks, hook_ty);
+        }
+        KnownNames::StdPanickingTakeHook => {
+            let Some(set_hook) = find_std_set_hook(fpb.acx.tcx) else {
+                return false;
+            };
+            let hooks = panic_hooks(fpb, set_hook);
+            let hook_ty = fpb.acx.get_path_rustc_type(&hooks).expect("rustc type error");
+            fpb.add_internal_edges(hooks, hook_ty, destination.clone(), hook_ty);
+        }
+        KnownNames::StdPanickingAssertFailed
+        | KnownNames::StdPanickingBeginPanic
+        | KnownNames::StdPanickingBeginPanicFmt => {
+            let tcx = fpb.acx.tcx;
+            match tcx.lang_items().get(LangItem::PanicImpl) {
+                // The panic handler of the standard library calls the panic hooks.
+                Some(handler) if tcx.crate_name(handler.krate) != sym::std && tcx.is_mir_available(handler) => {
+                    call_panic_handler(fpb, handler, location);
+                }
+                _ => call_panic_hooks(fpb, location),
+            }
+        }
+        KnownNames::StdPanickingRustPanicWithHook => {
+            call_panic_hooks(fpb, location);
+        }
+        _ => {
+            return false;
+        }
+    }
+    true
+}
+
+/// Calls the `#[panic_handler]` function with an unknown `&PanicInfo`.
+fn call_panic_handler(fpb: &mut FuncPAGBuilder<'_, '_, '_>, handler: DefId, location: mir::Location) {
+    let tcx = fpb.acx.tcx;
+    let handler_sig = tcx.instantiate_bound_regions_with_erased(tcx.fn_sig(handler).instantiate_identity());
+    let handler_args: Vec<Rc<Path>> = handler_sig.inputs().iter().map(|ty| fpb.create_aux_local(*ty)).collect();
+    let handler_destination = fpb.create_aux_local(handler_sig.output());
+    // The callsite of the handler must differ from the callsite of the panic function.
+    let mut new_location = location;
+    new_location.statement_index += 1;
+    fpb.add_static_call(handler, tcx.mk_args(&[]), handler_args, handler_destination, new_location);
+}
+
+/// Calls the panic hooks via `<Box<dyn Fn(&PanicInfo)> as Fn>::call` with an unknown `&PanicInfo`.
+fn call_panic_hooks(fpb: &mut FuncPAGBuilder<'_, '_, '_>, location: mir::Location) {
+    let tcx = fpb.acx.tcx;
+    let Some(set_hook) = find_std_set_hook(tcx) else {
+        return;
+    };
+    let hooks = panic_hooks(fpb, set_hook);
+    let hook_ty = fpb.acx.get_path_rustc_type(&hooks).expect("rustc type error");
+    let TyKind::Dynamic(predicates, ..) = type_util::get_dereferenced_type(hook_ty).kind() else {
+        unreachable!("The panic hook is expected to be a boxed trait object");
+    };
+    let Some(principal) = predicates.principal() else {
+        return;
+    };
+    // `dyn Fn(&PanicInfo)` has the principal trait `Fn<(&PanicInfo,)>`.
+    let hook_args_ty = tcx.instantiate_bound_regions_with_erased(principal).args.type_at(0);
+    let hooks_ref = fpb.create_aux_local(Ty::new_imm_ref(tcx, tcx.lifetimes.re_erased, hook_ty));
+    fpb.add_addr_edge(hooks, hooks_ref.clone());
+    let hook_args = fpb.create_aux_local(hook_args_ty);
+    let hook_destination = fpb.create_aux_local(tcx.types.unit);
+
+    let fn_trait_def_id = tcx.require_lang_item(LangItem::Fn, None);
+    let fn_def_id = tcx
+        .associated_items(fn_trait_def_id)
+        .filter_by_name_unhygienic(sym::call)
+        .next()
+        .expect("Fn::call")
+        .def_id;
+    let fn_args = tcx.mk_args(&[hook_ty.into(), hook_args_ty.into()]);
+    // The callsite of the hooks must differ from the callsite of the panic function.
+    let mut new_location = location;
+    new_location.statement_index += 1;
+    fpb.resolve_fntrait_call(
+        &fn_def_id,
+        &fn_args,
+        vec![hooks_ref, hook_args],
+        hook_destination,
+        new_location,
+    );
+}
+
+/// Returns the global variable that holds the panic hooks registered with `set_hook`.
+fn panic_hooks(fpb: &mut FuncPAGBuilder<'_, '_, '_>, set_hook: DefId) -> Rc<Path> {
+    let tcx = fpb.acx.tcx;
+    let hooks = Path::new_static_variable(set_hook);
+    let set_hook_sig = tcx.instantiate_bound_regions_with_erased(tcx.fn_sig(set_hook).instantiate_identity());
+    fpb.acx.set_path_rustc_type(hooks.clone(), set_hook_sig.inputs()[0]);
+    hooks
+}
+
+/// Returns the def id of `std::panic::set_hook` if the standard library is linked.
+fn find_std_set_hook(tcx: TyCtxt<'_>) -> Option<DefId> {
+    let std_crate = tcx.crates(()).iter().find(|cnum| tcx.crate_name(**cnum) == sym::std)?;
+    let find_child = |parent: DefId, name: &str| {
+        tcx.module_children(parent)
+            .iter()
+            .find(|child| child.ident.as_str() == name)
+            .and_then(|child| child.res.opt_def_id())
+    };
+    let panic_module = find_child(DefId { krate: *std_crate, index: CRATE_DEF_INDEX }, "panic")?;
+    find_child(panic_module, "set_hook")
+}
@@ -0,0 +1,428 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Provides summaries for the methods of the smart pointer and interior mutability types
+//! in the standard library, i.e. `Rc`, `Arc`, `Cell`, `RefCell`, `Mutex` and `RwLock`.
+//!
+//! The bodies of these methods are full of raw pointer casts, atomic operations and
+//! `UnsafeCell::get` calls, which are analyzed imprecisely and expensively. Instead, each
+//! method is modeled as loads and stores of the wrapped value. The summaries are only
+//! enabled with the `--model-std-smart-pointers` option.
+
+use std::rc::Rc;
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::{GenericArgsRef, Ty, TyCtxt, TyKind};
+
+use crate::builder::call_graph_builder;
+use crate::builder::fpag_builder::FuncPAGBuilder;
+use crate::builder::special_function_handler::{copy_value, option_some, RESULT_ERR, RESULT_OK};
+use crate::mir::known_names::KnownNames;
+use crate::mir::path::{Path, PathSelector};
+use crate::util::type_util;
+
+/// Returns the known name of the callee if it is a method of the smart pointer types.
+/// Trait methods such as `Clone::clone` and `Deref::deref` are devirtualized first.
+pub fn get_smart_pointer_known_name<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_def_id: DefId,
+    gen_args: &GenericArgsRef<'tcx>,
+) -> KnownNames {
+    let callee_def_id =
+        match call_graph_builder::try_to_devirtualize(fpb.acx.tcx, callee_def_id, gen_args) {
+            Some((def_id, _)) => def_id,
+            None => callee_def_id,
+        };
+    match fpb.acx.get_known_name_for(callee_def_id) {
+        known_name @ (KnownNames::StdRcNew
+        | KnownNames::StdRcClone
+        | KnownNames::StdRcDeref
+        | KnownNames::StdRcIntoInner
+        | KnownNames::StdArcNew
+        | KnownNames::StdArcClone
+        | KnownNames::StdArcDeref
+        | KnownNames::StdArcIntoInner
+        | KnownNames::StdCellNew
+        | KnownNames::StdCellGet
+        | KnownNames::StdCellSet
+        | KnownNames::StdCellReplace
+        | KnownNames::StdCellIntoInner
+        | KnownNames::StdRefCellNew
+        | KnownNames::StdRefCellBorrow
+        | KnownNames::StdRefCellBorrowMut
+        | KnownNames::StdRefCellReplace
+        | KnownNames::StdRefCellIntoInner
+        | KnownNames::StdMutexNew
+        | KnownNames::StdMutexLock
+        | KnownNames::StdMutexIntoInner
+        | KnownNames::StdMutexGuardDeref
+        | KnownNames::StdRwLockNew
+        | KnownNames::StdRwLockRead
+        | KnownNames::StdRwLockWrite
+        | KnownNames::StdRwLockIntoInner
+        | KnownNames::StdRwLockWriteGuardDeref) => known_name,
+        _ => KnownNames::None,
+    }
+}
+
+/// Handles the call to a method of the smart pointer types.
+///
+/// Returns true if the callee is modeled by a summary.
+pub fn handled_as_smart_pointer_call<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_def_id: &DefId,
+    gen_args: &GenericArgsRef<'tcx>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) -> bool {
+    match get_smart_pointer_known_name(fpb, *callee_def_id, gen_args) {
+        KnownNames::StdRcNew | KnownNames::StdArcNew => {
+            handle_rc_new(fpb, args, destination, location);
+        }
+        KnownNames::StdRcClone | KnownNames::StdArcClone => {
+            let rc_ref_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+            let rc_ty = type_util::get_dereferenced_type(rc_ref_ty);
+            let rc_path = fpb.create_dereference(args[0].clone(), rc_ref_ty);
+            copy_value(fpb, rc_path, rc_ty, destination.clone());
+        }
+        KnownNames::StdRcDeref | KnownNames::StdArcDeref => {
+            handle_rc_deref(fpb, args, destination);
+        }
+        KnownNames::StdRcIntoInner | KnownNames::StdArcIntoInner => {
+            handle_rc_into_inner(fpb, args, destination);
+        }
+        KnownNames::StdCellNew
+        | KnownNames::StdRefCellNew
+        | KnownNames::StdMutexNew
+        | KnownNames::StdRwLockNew => {
+            let cell_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+            let (payload_path, value_ty) = cell_payload(fpb, destination.clone(), cell_ty);
+            copy_value(fpb, args[0].clone(), value_ty, payload_path);
+        }
+        KnownNames::StdCellGet => {
+            let (payload_path, value_ty) = deref_cell_payload(fpb, args[0].clone());
+            copy_value(fpb, payload_path, value_ty, destination.clone());
+        }
+        KnownNames::StdCellSet => {
+            let (payload_path, value_ty) = deref_cell_payload(fpb, args[0].clone());
+            copy_value(fpb, args[1].clone(), value_ty, payload_path);
+        }
+        KnownNames::StdCellReplace | KnownNames::StdRefCellReplace => {
+            let (payload_path, value_ty) = deref_cell_payload(fpb, args[0].clone());
+            copy_value(fpb, payload_path.clone(), value_ty, destination.clone());
+            copy_value(fpb, args[1].clone(), value_ty, payload_path);
+        }
+        KnownNames::StdCellIntoInner | KnownNames::StdRefCellIntoInner => {
+            let cell_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+            let (payload_path, value_ty) = cell_payload(fpb, args[0].clone(), cell_ty);
+            copy_value(fpb, payload_path, value_ty, destination.clone());
+        }
+        KnownNames::StdMutexIntoInner | KnownNames::StdRwLockIntoInner => {
+            let cell_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+            let (payload_path, value_ty) = cell_payload(fpb, args[0].clone(), cell_ty);
+            for dst_path in lock_result_values(fpb, destination) {
+                copy_value(fpb, payload_path.clone(), value_ty, dst_path);
+            }
+        }
+        KnownNames::StdRefCellBorrow | KnownNames::StdRefCellBorrowMut => {
+            // `Ref<'b, T> { value: NonNull<T>, .. }`, `RefMut<'b, T> { value: NonNull<T>, .. }`
+            let guard_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+            let guard_ptr_path = guard_pointer_path(fpb, destination.clone(), guard_ty, "value");
+            borrow_cell_payload(fpb, args[0].clone(), guard_ptr_path);
+        }
+        KnownNames::StdRwLockRead => {
+            // `RwLockReadGuard<'a, T> { data: NonNull<T>, .. }`
+            for (guard_path, guard_ty) in lock_result_guards(fpb, destination) {
+                let guard_ptr_path = guard_pointer_path(fpb, guard_path, guard_ty, "data");
+                borrow_cell_payload(fpb, args[0].clone(), guard_ptr_path);
+            }
+        }
+        KnownNames::StdMutexLock | KnownNames::StdRwLockWrite => {
+            // `MutexGuard<'a, T> { lock: &'a Mutex<T>, .. }`, `RwLockWriteGuard<'a, T> { lock: &'a RwLock<T>, .. }`
+            let lock_ref_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+            for (guard_path, guard_ty) in lock_result_guards(fpb, destination) {
+                let lock_index = field_index(guard_ty, "lock");
+                let guard_lock_path = Path::new_field(guard_path, lock_index);
+                fpb.acx.set_path_rustc_type(guard_lock_path.clone(), lock_ref_ty);
+                fpb.add_direct_edge(args[0].clone(), guard_lock_path);
+            }
+        }
+        KnownNames::StdMutexGuardDeref | KnownNames::StdRwLockWriteGuardDeref => {
+            handle_guard_deref(fpb, args, destination);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// `fn new(value: T) -> Rc<T>`, where
+/// `Rc<T> { ptr: NonNull<RcBox<T>>, .. }` and `RcBox<T> { strong, weak, value: T }`.
+/// `Arc<T>` is modeled in the same way, with `ArcInner<T> { strong, weak, data: T }`.
+///
+/// ```let heap: RcBox<T>; heap.value = arg; let aux: *const RcBox<T> = &heap; dst.ptr.pointer = aux;```
+fn handle_rc_new<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) {
+    let tcx = fpb.acx.tcx;
+    let rc_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+    let (inner_ptr_proj, inner_ptr_ty) = rc_inner_pointer(tcx, rc_ty);
+    let inner_ty = type_util::get_dereferenced_type(inner_ptr_ty);
+
+    let heap_object_path = Path::new_heap_obj(fpb.fpag.func_id, location);
+    fpb
+        .acx
+        .set_path_rustc_type(heap_object_path.clone(), tcx.types.u8);
+    fpb
+        .acx
+        .concretized_heap_objs
+        .insert(heap_object_path.clone(), inner_ty);
+    let cast_heap_object_path = fpb
+        .acx
+        .cast_to(&heap_object_path, inner_ty)
+        .expect("Cast Error");
+
+    let (value_index, value_ty) = rc_inner_value_field(tcx, inner_ty);
+    let heap_value_path = Path::new_field(cast_heap_object_path.clone(), value_index);
+    fpb.acx.set_path_rustc_type(heap_value_path.clone(), value_ty);
+    copy_value(fpb, args[0].clone(), value_ty, heap_value_path);
+
+    let dst_ptr_path = Path::append_projection(destination, &inner_ptr_proj);
+    fpb.acx.set_path_rustc_type(dst_ptr_path.clone(), inner_ptr_ty);
+    let aux = fpb.create_aux_local(inner_ptr_ty);
+    fpb.add_addr_edge(cast_heap_object_path, aux.clone());
+    fpb.add_direct_edge(aux, dst_ptr_path);
+}
+
+/// `fn deref(&self) -> &T`.
+///
+/// ```let aux: *const RcBox<T> = (*self).ptr.pointer; dst = &(*aux).value;```
+fn handle_rc_deref<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let rc_ref_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+    let rc_ty = type_util::get_dereferenced_type(rc_ref_ty);
+    let (inner_ptr_proj, inner_ptr_ty) = rc_inner_pointer(tcx, rc_ty);
+    let inner_ty = type_util::get_dereferenced_type(inner_ptr_ty);
+
+    let rc_path = fpb.create_dereference(args[0].clone(), rc_ref_ty);
+    let inner_ptr_path = Path::append_projection(&rc_path, &inner_ptr_proj);
+    fpb.acx.set_path_rustc_type(inner_ptr_path.clone(), inner_ptr_ty);
+    let aux = fpb.create_aux_local(inner_ptr_ty);
+    fpb.add_load_edge(inner_ptr_path, aux.clone());
+
+    let (value_index, value_ty) = rc_inner_value_field(tcx, inner_ty);
+    let value_path = Path::new_qualified(
+        aux,
+        vec![PathSelector::Deref, PathSelector::Field(value_index)],
+    );
+    fpb.acx.set_path_rustc_type(value_path.clone(), value_ty);
+    fpb.add_gep_edge(value_path, destination.clone());
+}
+
+/// `fn into_inner(this: Rc<T>) -> Option<T>`.
+///
+/// ```let aux: *const RcBox<T> = this.ptr.pointer; (dst as Some).0 = (*aux).value;```
+fn handle_rc_into_inner<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let rc_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+    let (inner_ptr_proj, inner_ptr_ty) = rc_inner_pointer(tcx, rc_ty);
+    let inner_ty = type_util::get_dereferenced_type(inner_ptr_ty);
+
+    let inner_ptr_path = Path::append_projection(&args[0], &inner_ptr_proj);
+    fpb.acx.set_path_rustc_type(inner_ptr_path.clone(), inner_ptr_ty);
+    let inner_path = fpb.create_dereference(inner_ptr_path, inner_ptr_ty);
+    let (value_index, value_ty) = rc_inner_value_field(tcx, inner_ty);
+    let value_path = Path::new_field(inner_path, value_index);
+    fpb.acx.set_path_rustc_type(value_path.clone(), value_ty);
+
+    let dst_value_path = option_some(destination);
+    fpb.acx.set_path_rustc_type(dst_value_path.clone(), value_ty);
+    copy_value(fpb, value_path, value_ty, dst_value_path);
+}
+
+/// `fn deref(&self) -> &T` of `MutexGuard<'a, T> { lock: &'a Mutex<T>, .. }` and
+/// `RwLockWriteGuard<'a, T> { lock: &'a RwLock<T>, .. }`.
+///
+/// ```let aux: &Mutex<T> = (*self).lock; dst = &(*aux).data.value;```
+fn handle_guard_deref<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let guard_ref_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+    let guard_ty = type_util::get_dereferenced_type(guard_ref_ty);
+    let lock_index = field_index(guard_ty, "lock");
+    let lock_ref_ty = type_util::get_field_type(tcx, guard_ty, lock_index);
+
+    let guard_path = fpb.create_dereference(args[0].clone(), guard_ref_ty);
+    let guard_lock_path = Path::new_field(guard_path, lock_index);
+    fpb.acx.set_path_rustc_type(guard_lock_path.clone(), lock_ref_ty);
+    let aux = fpb.create_aux_local(lock_ref_ty);
+    fpb.add_load_edge(guard_lock_path, aux.clone());
+    borrow_cell_payload(fpb, aux, destination.clone());
+}
+
+/// Takes the address of the payload of the cell pointed to by `cell_ref`.
+///
+/// ```let aux = &(*cell_ref).value.value; dst = aux;```
+fn borrow_cell_payload<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    cell_ref: Rc<Path>,
+    destination: Rc<Path>,
+) {
+    let dst_ty = fpb.acx.get_path_rustc_type(&destination).expect("rustc type error");
+    let (payload_path, _) = deref_cell_payload(fpb, cell_ref);
+    let aux = fpb.create_aux_local(dst_ty);
+    fpb.add_gep_edge(payload_path, aux.clone());
+    fpb.add_direct_edge(aux, destination);
+}
+
+/// Returns the path and the type of the payload of the cell pointed to by `cell_ref`.
+fn deref_cell_payload<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    cell_ref: Rc<Path>,
+) -> (Rc<Path>, Ty<'tcx>) {
+    let cell_ref_ty = fpb.acx.get_path_rustc_type(&cell_ref).expect("rustc type error");
+    let cell_ty = type_util::get_dereferenced_type(cell_ref_ty);
+    let cell_path = fpb.create_dereference(cell_ref, cell_ref_ty);
+    cell_payload(fpb, cell_path, cell_ty)
+}
+
+/// Returns the path and the type of the payload wrapped in the `UnsafeCell` of `cell_path`, i.e.
+/// `cell.value.value` for `Cell` and `RefCell`, and `cell.data.value` for `Mutex` and `RwLock`.
+fn cell_payload<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    cell_path: Rc<Path>,
+    cell_ty: Ty<'tcx>,
+) -> (Rc<Path>, Ty<'tcx>) {
+    let tcx = fpb.acx.tcx;
+    let field_name = match cell_ty.kind() {
+        TyKind::Adt(def, _) => match tcx.item_name(def.did()).as_str() {
+            "Mutex" | "RwLock" => "data",
+            _ => "value",
+        },
+        _ => unreachable!("Expect a cell type, found {:?}", cell_ty),
+    };
+    let unsafe_cell_index = field_index(cell_ty, field_name);
+    let unsafe_cell_ty = type_util::get_field_type(tcx, cell_ty, unsafe_cell_index);
+    let value_ty = type_util::get_field_type(tcx, unsafe_cell_ty, 0);
+    let payload_path = Path::append_projection(
+        &cell_path,
+        &vec![PathSelector::Field(unsafe_cell_index), PathSelector::Field(0)],
+    );
+    fpb.acx.set_path_rustc_type(payload_path.clone(), value_ty);
+    (payload_path, value_ty)
+}
+
+/// Returns the path of the raw pointer wrapped in the `NonNull` field `field_name` of a guard.
+fn guard_pointer_path<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    guard_path: Rc<Path>,
+    guard_ty: Ty<'tcx>,
+    field_name: &str,
+) -> Rc<Path> {
+    let tcx = fpb.acx.tcx;
+    let non_null_index = field_index(guard_ty, field_name);
+    let non_null_ty = type_util::get_field_type(tcx, guard_ty, non_null_index);
+    let ptr_ty = type_util::get_field_type(tcx, non_null_ty, 0);
+    let guard_ptr_path = Path::append_projection(
+        &guard_path,
+        &vec![PathSelector::Field(non_null_index), PathSelector::Field(0)],
+    );
+    fpb.acx.set_path_rustc_type(guard_ptr_path.clone(), ptr_ty);
+    guard_ptr_path
+}
+
+/// Returns the paths and the type of the guards in a `LockResult<G> = Result<G, PoisonError<G>>`,
+/// i.e. `(dst as Ok).0` and `(dst as Err).0.guard`.
+fn lock_result_guards<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    destination: &Rc<Path>,
+) -> Vec<(Rc<Path>, Ty<'tcx>)> {
+    let result_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+    let (guard_ty, poison_error_ty) = match result_ty.kind() {
+        TyKind::Adt(_, args) => (args.type_at(0), args.type_at(1)),
+        _ => unreachable!("Expect a LockResult type, found {:?}", result_ty),
+    };
+    let ok_guard_path = Path::append_projection(
+        destination,
+        &vec![PathSelector::Downcast(RESULT_OK), PathSelector::Field(0)],
+    );
+    let guard_index = field_index(poison_error_ty, "guard");
+    let err_guard_path = Path::append_projection(
+        destination,
+        &vec![
+            PathSelector::Downcast(RESULT_ERR),
+            PathSelector::Field(0),
+            PathSelector::Field(guard_index),
+        ],
+    );
+    let guards = vec![(ok_guard_path, guard_ty), (err_guard_path, guard_ty)];
+    for (guard_path, guard_ty) in &guards {
+        fpb.acx.set_path_rustc_type(guard_path.clone(), *guard_ty);
+    }
+    guards
+}
+
+/// Returns the paths of the values in a `LockResult<T>`.
+fn lock_result_values<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    destination: &Rc<Path>,
+) -> Vec<Rc<Path>> {
+    lock_result_guards(fpb, destination)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+}
+
+/// Returns the projection to and the type of the raw pointer wrapped in an `Rc<T>` or `Arc<T>`,
+/// i.e. `.ptr.pointer` of type `*const RcBox<T>` or `*const ArcInner<T>`.
+fn rc_inner_pointer<'tcx>(tcx: TyCtxt<'tcx>, rc_ty: Ty<'tcx>) -> (Vec<PathSelector>, Ty<'tcx>) {
+    let non_null_index = field_index(rc_ty, "ptr");
+    let non_null_ty = type_util::get_field_type(tcx, rc_ty, non_null_index);
+    let ptr_index = field_index(non_null_ty, "pointer");
+    let ptr_ty = type_util::get_field_type(tcx, non_null_ty, ptr_index);
+    (
+        vec![PathSelector::Field(non_null_index), PathSelector::Field(ptr_index)],
+        ptr_ty,
+    )
+}
+
+/// Returns the index and the type of the value field of an `RcBox<T>` or `ArcInner<T>`.
+fn rc_inner_value_field<'tcx>(tcx: TyCtxt<'tcx>, inner_ty: Ty<'tcx>) -> (usize, Ty<'tcx>) {
+    let field_name = match inner_ty.kind() {
+        TyKind::Adt(def, _) if tcx.item_name(def.did()).as_str() == "ArcInner" => "data",
+        _ => "value",
+    };
+    let value_index = field_index(inner_ty, field_name);
+    (value_index, type_util::get_field_type(tcx, inner_ty, value_index))
+}
+
+/// Returns the index of the field named `field_name` in the struct type `ty`.
+fn field_index(ty: Ty<'_>, field_name: &str) -> usize {
+    match ty.kind() {
+        TyKind::Adt(def, _) => def
+            .non_enum_variant()
+            .fields
+            .iter()
+            .position(|field| field.name.as_str() == field_name)
+            .unwrap_or_else(|| panic!("No field `{}` in {:?}", field_name, ty)),
+        _ => unreachable!("Expect a struct type, found {:?}", ty),
+    }
+}
+
@@ -14,8 +14,14 @@ use rustc_hir::def_id::DefId;
 use rustc_hir::lang_items::LangItem;
 use rustc_middle::mir;
 use rustc_middle::ty::{List, GenericArgsRef, Ty, TyCtxt, TyKind};
+use rustc_span::symbol::sym;
 
+use crate::builder::call_graph_builder;
+use crate::builder::collection_models;
 use crate::builder::fpag_builder::FuncPAGBuilder;
+use crate::builder::lazy_global_models;
+use crate::builder::runtime_models;
+use crate::builder::smart_pointer_models;
 use crate::mir::analysis_context::AnalysisContext;
 use crate::mir::known_names::KnownNames;
 use crate::mir::path::{Path, PathEnum, PathSelector};
@@ -76,6 +82,37 @@ lazy_static! {
         set.insert(KnownNames::StdAllocBoxFree);
         set.insert(KnownNames::StdAllocHandleAllocError);
         set.insert(KnownNames::StdAllocAllocatorDeallocate);
+        set.insert(KnownNames::LibcMalloc);
+        set.insert(KnownNames::LibcCalloc);
+        set.insert(KnownNames::LibcRealloc);
+        set.insert(KnownNames::LibcFree);
+        set.insert(KnownNames::LibcMemcpy);
+        set.insert(KnownNames::LibcMemmove);
+        set.insert(KnownNames::LibcStrdup);
+        set.insert(KnownNames::LibcMmap);
+        set.insert(KnownNames::StdIntrinsicsAtomicLoad);
+        set.insert(KnownNames::StdIntrinsicsAtomicStore);
+        set.insert(KnownNames::StdIntrinsicsAtomicXchg);
+        set.insert(KnownNames::StdIntrinsicsAtomicCxchg);
+        set.insert(KnownNames::StdSyncAtomicPtrLoad);
+        set.insert(KnownNames::StdSyncAtomicPtrStore);
+        set.insert(KnownNames::StdSyncAtomicPtrSwap);
+        set.insert(KnownNames::StdSyncAtomicPtrCompareExchange);
+        set.insert(KnownNames::StdPtrRead);
+        set.insert(KnownNames::StdPtrWrite);
+        set.insert(KnownNames::StdPtrReplace);
+        set.insert(KnownNames::StdPtrSwap);
+        set.insert(KnownNames::StdPtrSwapNonOverlapping);
+        set.insert(KnownNames::StdIntrinsicsCopy);
+        set.insert(KnownNames::StdIntrinsicsCopyNonOverlapping);
+        set.insert(KnownNames::StdMemReplace);
+        set.insert(KnownNames::StdMemSwap);
+        set.insert(KnownNames::StdMemTake);
+        set.insert(KnownNames::StdAnyIs);
+        set.insert(KnownNames::StdAnyDowncastRef);
+        set.insert(KnownNames::StdAnyDowncastRefUnchecked);
+        set.insert(KnownNames::StdBoxDowncast);
+        set.insert(KnownNames::StdBoxDowncastUnchecked);
         set
     };
 }
@@ -98,6 +135,50 @@ pub fn handled_as_special_function_call<'tcx>(
     destination: &Rc<Path>,
     location: mir::Location,
 ) -> bool {
+    if fpb.acx.analysis_options.model_std_smart_pointers
+        && smart_pointer_models::handled_as_smart_pointer_call(
+            fpb,
+            callee_def_id,
+            gen_args,
+            args,
+            destination,
+            location,
+        )
+    {
+        return true;
+    }
+    if fpb.acx.analysis_options.model_std_collections
+        && collection_models::handled_as_collection_call(
+            fpb,
+            callee_def_id,
+            gen_args,
+            args,
+            destination,
+            location,
+        )
+    {
+        return true;
+    }
+
+    if fpb.acx.analysis_options.model_lazy_globals
+        && lazy_global_models::handled_as_lazy_global_call(
+            fpb,
+            callee_def_id,
+            gen_args,
+            args,
+            destination,
+            location,
+        )
+    {
+        return true;
+    }
+
+    if fpb.acx.analysis_options.resolve_panic_handler
+        && runtime_models::handled_as_panic_call(fpb, callee_def_id, args, destination, location)
+    {
+        return true;
+    }
+
     let callee_known_name = fpb.acx.get_known_name_for(*callee_def_id);
     match callee_known_name {
         KnownNames::StdIntrinsicsTransmute => {
@@ -158,6 +239,48 @@ pub fn handled_as_special_function_call<'tcx>(
             handle_result_map_err(fpb, gen_args, args, destination);
             return true;
         }
+        KnownNames::StdIntrinsicsAtomicLoad
+        | KnownNames::StdIntrinsicsAtomicStore
+        | KnownNames::StdIntrinsicsAtomicXchg
+        | KnownNames::StdIntrinsicsAtomicCxchg => {
+            handle_atomic_intrinsic(fpb, callee_known_name, gen_args, args, destination);
+            return true;
+        }
+        KnownNames::StdSyncAtomicPtrLoad
+        | KnownNames::StdSyncAtomicPtrStore
+        | KnownNames::StdSyncAtomicPtrSwap
+        | KnownNames::StdSyncAtomicPtrCompareExchange => {
+            handle_atomic_ptr_method(fpb, callee_known_name, args, destination);
+            return true;
+        }
+        KnownNames::StdPtrRead
+        | KnownNames::StdPtrWrite
+        | KnownNames::StdPtrReplace
+        | KnownNames::StdPtrSwap
+        | KnownNames::StdPtrSwapNonOverlapping
+        | KnownNames::StdIntrinsicsCopy
+        | KnownNames::StdIntrinsicsCopyNonOverlapping
+        | KnownNames::StdMemReplace
+        | KnownNames::StdMemSwap
+        | KnownNames::StdMemTake => {
+            handle_value_movement(fpb, callee_known_name, gen_args, args, destination, location);
+            return true;
+        }
+        KnownNames::LibcMemcpy | KnownNames::LibcMemmove => {
+            handle_libc_memcpy(fpb, args, destination, location);
+            return true;
+        }
+        KnownNames::StdAnyIs => {
+            // Comparing the type ids does not move any pointer
+            return true;
+        }
+        KnownNames::StdAnyDowncastRef
+        | KnownNames::StdAnyDowncastRefUnchecked
+        | KnownNames::StdBoxDowncast
+        | KnownNames::StdBoxDowncastUnchecked => {
+            handle_any_downcast(fpb, callee_known_name, gen_args, args, destination);
+            return true;
+        }
         KnownNames::StdConvertInto => {
             let tcx = fpb.acx.tcx;
             let generic_types = gen_args.into_type_list(tcx);
@@ -276,7 +399,7 @@ fn handle_thread_builder_spawn_unchecked<'tcx>(
     fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
     gen_args: &GenericArgsRef<'tcx>,
     args: &Vec<Rc<Path>>,
-    _destination: &Rc<Path>,
+    destination: &Rc<Path>,
     location: mir::Location,
 ) {
     let fn_once_defid = fpb.acx.tcx.require_lang_item(LangItem::FnOnce, None);
@@ -295,8 +418,9 @@ fn handle_thread_builder_spawn_unchecked<'tcx>(
         aux_dst,
         new_location,
     );
+    add_join_handle_packet(fpb, destination, location);
 
-    // Todo: Add edges from `aux_dst` to `destination`, to do so, we need to allocate a heap memory for the packet field.
+    // Todo: Add edges from `aux_dst` to the result field of the packet.
     // Destination type: io::Result<JoinHandle<T>>, where struct JoinHandle<T>(JoinInner<'static, T>);
     // struct JoinInner<'scope, T> {
     //     native: imp::Thread,
@@ -310,6 +434,55 @@ fn handle_thread_builder_spawn_unchecked<'tcx>(
     // }
 }
 
+/// Allocates the packet of the `JoinHandle` returned by `spawn_unchecked` at the callsite, which
+/// identifies the thread spawned there, i.e.
+/// ```let heap: ArcInner<Packet<T>>; let aux: *const ArcInner<Packet<T>> = &heap; (dst as Ok).0.0.packet.0.0 = aux;```
+fn add_join_handle_packet<'tcx>(fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>, destination: &Rc<Path>, location: mir::Location) {
+    let tcx = fpb.acx.tcx;
+    let result_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+    let TyKind::Adt(_, result_args) = result_ty.kind() else {
+        unreachable!("Expect an io::Result type, found {:?}", result_ty);
+    };
+    let join_inner_ty = type_util::get_field_type(tcx, result_args.type_at(0), 0);
+    let TyKind::Adt(join_inner_def, _) = join_inner_ty.kind() else {
+        unreachable!("Expect a JoinInner type, found {:?}", join_inner_ty);
+    };
+    let Some(packet_index) = join_inner_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .position(|field| field.name.as_str() == "packet")
+    else {
+        return;
+    };
+    // Arc.0 = NonNull, NonNull.0 = *const ArcInner
+    let arc_ty = type_util::get_field_type(tcx, join_inner_ty, packet_index);
+    let non_null_ty = type_util::get_field_type(tcx, arc_ty, 0);
+    let inner_ptr_ty = type_util::get_field_type(tcx, non_null_ty, 0);
+    let inner_ty = type_util::get_dereferenced_type(inner_ptr_ty);
+
+    let heap_object_path = Path::new_heap_obj(fpb.fpag.func_id, location);
+    fpb.acx.set_path_rustc_type(heap_object_path.clone(), tcx.types.u8);
+    fpb.acx.concretized_heap_objs.insert(heap_object_path.clone(), inner_ty);
+    let cast_heap_object_path = fpb.acx.cast_to(&heap_object_path, inner_ty).expect("Cast Error");
+
+    let packet_ptr_path = Path::append_projection(
+        destination,
+        &vec![
+            PathSelector::Downcast(0),
+            PathSelector::Field(0),
+            PathSelector::Field(0),
+            PathSelector::Field(packet_index),
+            PathSelector::Field(0),
+            PathSelector::Field(0),
+        ],
+    );
+    fpb.acx.set_path_rustc_type(packet_ptr_path.clone(), inner_ptr_ty);
+    let aux = fpb.create_aux_local(inner_ptr_ty);
+    fpb.add_addr_edge(cast_heap_object_path, aux.clone());
+    fpb.add_direct_edge(aux, packet_ptr_path);
+}
+
 fn handle_non_null_as_ptr<'tcx>(
     fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
     args: &Vec<Rc<Path>>,
@@ -337,6 +510,68 @@ fn handle_unique_new_unchecked<'tcx>(
     fpb.add_direct_edge(args[0].clone(), dst_field_path);
 }
 
+/// Handles the downcasts of `dyn Any` trait objects:
+/// ```fn downcast_ref<T: Any>(&self) -> Option<&T>
+/// fn downcast_ref_unchecked<T: Any>(&self) -> &T
+/// fn downcast<T: Any>(self: Box<dyn Any, A>) -> Result<Box<T, A>, Box<dyn Any, A>>
+/// fn downcast_unchecked<T: Any>(self: Box<dyn Any, A>) -> Box<T, A>
+/// ```
+/// The trait object pointer is assigned to the pointer to `T` directly, instead of analyzing the
+/// bodies that compare the type ids via dynamic `type_id` calls and cast the pointer. As the source
+/// pointer points to a trait object and the target pointer does not, only the objects of type `T`
+/// are propagated, i.e. the failed downcasts get no flow. The `Err` variant of `Box::downcast`
+/// holds the original box.
+fn handle_any_downcast<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    known_name: KnownNames,
+    gen_args: &GenericArgsRef<'tcx>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let target_ty = gen_args.types().last().expect("rustc type error");
+    let src_ptr_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+    match known_name {
+        KnownNames::StdAnyDowncastRef => {
+            // `(dst as Some).0 = self`
+            let dst_ptr_path = option_some(destination);
+            let dst_ty = fpb.acx.get_path_rustc_type(destination).expect("rustc type error");
+            let some_ty = type_util::get_downcast_type(tcx, dst_ty, OPTION_SOME.into());
+            let dst_ptr_ty = type_util::get_field_type(tcx, some_ty, 0);
+            fpb.acx.set_path_rustc_type(dst_ptr_path.clone(), dst_ptr_ty);
+            fpb.add_direct_edge(args[0].clone(), dst_ptr_path);
+        }
+        KnownNames::StdAnyDowncastRefUnchecked => {
+            // `dst = self`
+            fpb.add_direct_edge(args[0].clone(), destination.clone());
+        }
+        KnownNames::StdBoxDowncast | KnownNames::StdBoxDowncastUnchecked => {
+            let box_ptr_proj = vec![PathSelector::Field(0), PathSelector::Field(0), PathSelector::Field(0)];
+            let src_box_ptr = Path::append_projection(&args[0], &box_ptr_proj);
+            fpb.acx.set_path_rustc_type(src_box_ptr.clone(), Ty::new_imm_ptr(tcx, src_ptr_ty.boxed_ty()));
+            let dst_box = if known_name == KnownNames::StdBoxDowncast {
+                // `(dst as Err).0 = self`
+                let err_box = Path::append_projection(
+                    destination,
+                    &vec![PathSelector::Downcast(RESULT_ERR), PathSelector::Field(0)],
+                );
+                let err_box_ptr = Path::append_projection(&err_box, &box_ptr_proj);
+                fpb.acx.set_path_rustc_type(err_box_ptr.clone(), Ty::new_imm_ptr(tcx, src_ptr_ty.boxed_ty()));
+                fpb.add_direct_edge(src_box_ptr.clone(), err_box_ptr);
+                // `(dst as Ok).0.0.0.0 = self.0.0.0`
+                Path::append_projection(destination, &vec![PathSelector::Downcast(RESULT_OK), PathSelector::Field(0)])
+            } else {
+                // `dst.0.0.0 = self.0.0.0`
+                destination.clone()
+            };
+            let dst_box_ptr = Path::append_projection(&dst_box, &box_ptr_proj);
+            fpb.acx.set_path_rustc_type(dst_box_ptr.clone(), Ty::new_imm_ptr(tcx, target_ty));
+            fpb.add_direct_edge(src_box_ptr, dst_box_ptr);
+        }
+        _ => unreachable!(),
+    }
+}
+
 /// ```fn std::result::Result::<T, E>::map_err(_1: std::result::Result<T, E>, _2: O) 
 ///    -> std::result::Result<T, F>
 /// ```
@@ -366,6 +601,208 @@ fn handle_result_map_err<'tcx>(
     );
 }
 
+/// Handles the atomic intrinsics that read or write the value pointed to by `args[0]`.
+/// ```fn atomic_load_*<T: Copy>(src: *const T) -> T```
+/// ```fn atomic_store_*<T: Copy>(dst: *mut T, val: T)```
+/// ```fn atomic_xchg_*<T: Copy>(dst: *mut T, src: T) -> T```
+/// ```fn atomic_cxchg*_*<T: Copy>(dst: *mut T, old: T, src: T) -> (T, bool)```
+/// 
+/// The comparison of `atomic_cxchg` is ignored, i.e. the new value is always assumed to be written.
+fn handle_atomic_intrinsic<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_known_name: KnownNames,
+    gen_args: &GenericArgsRef<'tcx>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let value_ty = gen_args.type_at(0);
+    let ptr_ty = Ty::new_mut_ptr(tcx, value_ty);
+    let value_path = fpb.create_dereference(args[0].clone(), ptr_ty);
+    match callee_known_name {
+        KnownNames::StdIntrinsicsAtomicLoad => {
+            copy_value(fpb, value_path, value_ty, destination.clone());
+        }
+        KnownNames::StdIntrinsicsAtomicStore => {
+            copy_value(fpb, args[1].clone(), value_ty, value_path);
+        }
+        KnownNames::StdIntrinsicsAtomicXchg => {
+            copy_value(fpb, value_path.clone(), value_ty, destination.clone());
+            copy_value(fpb, args[1].clone(), value_ty, value_path);
+        }
+        KnownNames::StdIntrinsicsAtomicCxchg => {
+            // The old value is returned in the first field of the result tuple.
+            let dst_field_path = Path::new_field(destination.clone(), 0);
+            fpb.acx.set_path_rustc_type(dst_field_path.clone(), value_ty);
+            copy_value(fpb, value_path.clone(), value_ty, dst_field_path);
+            copy_value(fpb, args[2].clone(), value_ty, value_path);
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Handles the methods of `AtomicPtr<T> { p: UnsafeCell<*mut T> }`, which read or write the
+/// wrapped pointer `(*self).p.value` directly instead of going through `UnsafeCell::get` and
+/// the atomic intrinsics.
+fn handle_atomic_ptr_method<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_known_name: KnownNames,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+) {
+    let tcx = fpb.acx.tcx;
+    let self_ref_ty = fpb.acx.get_path_rustc_type(&args[0]).expect("rustc type error");
+    let atomic_ptr_ty = type_util::get_dereferenced_type(self_ref_ty);
+    let ptr_ty = match atomic_ptr_ty.kind() {
+        TyKind::Adt(_, args) => Ty::new_mut_ptr(tcx, args.type_at(0)),
+        _ => unreachable!("The receiver is expected to be a reference to AtomicPtr"),
+    };
+    let self_path = fpb.create_dereference(args[0].clone(), self_ref_ty);
+    let value_path = Path::append_projection(
+        &self_path,
+        &vec![PathSelector::Field(0), PathSelector::Field(0)],
+    );
+    fpb.acx.set_path_rustc_type(value_path.clone(), ptr_ty);
+    match callee_known_name {
+        KnownNames::StdSyncAtomicPtrLoad => {
+            copy_value(fpb, value_path, ptr_ty, destination.clone());
+        }
+        KnownNames::StdSyncAtomicPtrStore => {
+            copy_value(fpb, args[1].clone(), ptr_ty, value_path);
+        }
+        KnownNames::StdSyncAtomicPtrSwap => {
+            copy_value(fpb, value_path.clone(), ptr_ty, destination.clone());
+            copy_value(fpb, args[1].clone(), ptr_ty, value_path);
+        }
+        KnownNames::StdSyncAtomicPtrCompareExchange => {
+            // The previous value is returned in both `Ok` and `Err`.
+            for variant in 0..2 {
+                let dst_ptr_path = Path::new_qualified(
+                    destination.clone(),
+                    vec![PathSelector::Downcast(variant), PathSelector::Field(0)],
+                );
+                fpb.acx.set_path_rustc_type(dst_ptr_path.clone(), ptr_ty);
+                copy_value(fpb, value_path.clone(), ptr_ty, dst_ptr_path);
+            }
+            copy_value(fpb, args[2].clone(), ptr_ty, value_path);
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Handles the functions moving values through pointers, i.e. `ptr::{read, write, replace, swap,
+/// swap_nonoverlapping, copy, copy_nonoverlapping}`, the corresponding methods of raw pointers and
+/// `mem::{replace, swap, take}`. The pointer fields of the moved values are loaded from and stored
+/// into the pointees directly. For the functions taking a count, all the copied elements are
+/// represented by the first one.
+fn handle_value_movement<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    callee_known_name: KnownNames,
+    gen_args: &GenericArgsRef<'tcx>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) {
+    let value_ty = gen_args.type_at(0);
+    let deref_arg = |fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>, i: usize| {
+        let ptr_ty = fpb.acx.get_path_rustc_type(&args[i]).expect("rustc type error");
+        fpb.create_dereference(args[i].clone(), ptr_ty)
+    };
+    match callee_known_name {
+        KnownNames::StdPtrRead => {
+            let value_path = deref_arg(fpb, 0);
+            copy_value(fpb, value_path, value_ty, destination.clone());
+        }
+        KnownNames::StdMemTake => {
+            // ret = *dest; *dest = T::default();
+            let value_path = deref_arg(fpb, 0);
+            copy_value(fpb, value_path.clone(), value_ty, destination.clone());
+            call_default(fpb, value_ty, value_path, location);
+        }
+        KnownNames::StdPtrWrite => {
+            let value_path = deref_arg(fpb, 0);
+            copy_value(fpb, args[1].clone(), value_ty, value_path);
+        }
+        KnownNames::StdPtrReplace | KnownNames::StdMemReplace => {
+            let value_path = deref_arg(fpb, 0);
+            copy_value(fpb, value_path.clone(), value_ty, destination.clone());
+            copy_value(fpb, args[1].clone(), value_ty, value_path);
+        }
+        KnownNames::StdPtrSwap | KnownNames::StdPtrSwapNonOverlapping | KnownNames::StdMemSwap => {
+            // let aux_x = *x; let aux_y = *y; *x = aux_y; *y = aux_x;
+            let x_path = deref_arg(fpb, 0);
+            let y_path = deref_arg(fpb, 1);
+            let aux_x = fpb.create_aux_local(value_ty);
+            let aux_y = fpb.create_aux_local(value_ty);
+            fpb.add_internal_edges(x_path.clone(), value_ty, aux_x.clone(), value_ty);
+            fpb.add_internal_edges(y_path.clone(), value_ty, aux_y.clone(), value_ty);
+            fpb.add_internal_edges(aux_y, value_ty, x_path, value_ty);
+            fpb.add_internal_edges(aux_x, value_ty, y_path, value_ty);
+        }
+        KnownNames::StdIntrinsicsCopy | KnownNames::StdIntrinsicsCopyNonOverlapping => {
+            // fn copy<T>(src: *const T, dst: *mut T, count: usize)
+            let src_path = deref_arg(fpb, 0);
+            let dst_path = deref_arg(fpb, 1);
+            copy_value(fpb, src_path, value_ty, dst_path);
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Calls `<value_ty as Default>::default` and stores the returned value into `target`.
+fn call_default<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    value_ty: Ty<'tcx>,
+    target: Rc<Path>,
+    location: mir::Location,
+) {
+    let tcx = fpb.acx.tcx;
+    let Some(default_trait) = tcx.get_diagnostic_item(sym::Default) else {
+        return;
+    };
+    let Some(default_fn) = tcx
+        .associated_item_def_ids(default_trait)
+        .iter()
+        .find(|def_id| tcx.item_name(**def_id).as_str() == "default")
+    else {
+        return;
+    };
+    let Some((callee_def_id, callee_gen_args)) =
+        call_graph_builder::try_to_devirtualize(tcx, *default_fn, tcx.mk_args(&[value_ty.into()]))
+    else {
+        return;
+    };
+    let default_value = fpb.create_aux_local(value_ty);
+    // The callsite of `default` must differ from the callsite of `mem::take`.
+    let mut new_location = location;
+    new_location.statement_index += 1;
+    fpb.add_static_call(callee_def_id, callee_gen_args, vec![], default_value.clone(), new_location);
+    copy_value(fpb, default_value, value_ty, target);
+}
+
+/// The variant indices of `Option` and `Result`, shared by the models of the std functions.
+pub(crate) const OPTION_SOME: usize = 1;
+pub(crate) const RESULT_OK: usize = 0;
+pub(crate) const RESULT_ERR: usize = 1;
+
+/// Copies the value of `value_ty` from `source` to `target` through an auxiliary local variable,
+/// e.g. `let aux = *ptr; dst = aux;` when `source` is a path dereferencing `ptr`.
+pub(crate) fn copy_value<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    source: Rc<Path>,
+    value_ty: Ty<'tcx>,
+    target: Rc<Path>,
+) {
+    let aux = fpb.create_aux_local(value_ty);
+    fpb.add_internal_edges(source, value_ty, aux.clone(), value_ty);
+    fpb.add_internal_edges(aux, value_ty, target, value_ty);
+}
+
+/// Returns the path `(path as Some).0`.
+pub(crate) fn option_some(path: &Rc<Path>) -> Rc<Path> {
+    Path::append_projection(path, &vec![PathSelector::Downcast(OPTION_SOME), PathSelector::Field(0)])
+}
+
 #[allow(unused)]
 fn handle_slice_index_index<'tcx>(
     fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
@@ -426,13 +863,20 @@ fn handle_alloc<'tcx>(
     location: mir::Location,
 ) -> bool {
     let tcx = fpb.acx.tcx;
+    if fpb.acx.analysis_options.resolve_global_allocator {
+        runtime_models::call_global_allocator(fpb, callee_known_name, args, destination, location);
+    }
     match callee_known_name {
         // Allocates memory on the heap and returns the address as `*mut u8`
         KnownNames::RustAlloc
         | KnownNames::RustAllocZeroed
         | KnownNames::StdAllocAlloc
         | KnownNames::StdAllocAllocZeroed
-        | KnownNames::StdAllocExchangeMalloc => {
+        | KnownNames::StdAllocExchangeMalloc
+        | KnownNames::LibcMalloc
+        | KnownNames::LibcCalloc
+        | KnownNames::LibcStrdup
+        | KnownNames::LibcMmap => {
             let heap_object_path = Path::new_heap_obj(fpb.fpag.func_id, location);
             fpb
                 .acx
@@ -478,6 +922,17 @@ fn handle_alloc<'tcx>(
             fpb.add_direct_edge(args[0].clone(), destination.clone());
             true
         }
+        // `realloc(NULL, size)` behaves like `malloc(size)`, therefore the result may also point
+        // to a new heap object besides the original one.
+        KnownNames::LibcRealloc => {
+            let heap_object_path = Path::new_heap_obj(fpb.fpag.func_id, location);
+            fpb
+                .acx
+                .set_path_rustc_type(heap_object_path.clone(), tcx.types.u8);
+            fpb.add_addr_edge(heap_object_path, destination.clone());
+            fpb.add_direct_edge(args[0].clone(), destination.clone());
+            true
+        }
         // Reallocates memory on the heap and returns a result of `Result<NonNull<[u8]>, AllocError>` type.
         KnownNames::StdAllocAllocatorGrow
         | KnownNames::StdAllocAllocatorGrowZeroed
@@ -505,11 +960,39 @@ fn handle_alloc<'tcx>(
         | KnownNames::StdAllocDealloc
         | KnownNames::StdAllocBoxFree
         | KnownNames::StdAllocHandleAllocError
-        | KnownNames::StdAllocAllocatorDeallocate => true,
+        | KnownNames::StdAllocAllocatorDeallocate
+        | KnownNames::LibcFree => true,
         _ => false,
     }
 }
 
+/// Handles `memcpy(dest, src, n)` and `memmove(dest, src, n)`, which copy the value pointed to
+/// by `src` to the memory pointed to by `dest` and return `dest`.
+///
+/// The pointers are usually cast to `*c_void` before the call, therefore the pointers before
+/// the casts are used to copy the values of the pointee type of `src`.
+fn handle_libc_memcpy<'tcx>(
+    fpb: &mut FuncPAGBuilder<'_, 'tcx, '_>,
+    args: &[Rc<Path>],
+    destination: &Rc<Path>,
+    location: mir::Location,
+) {
+    let dst_ptr = fpb.get_cast_source(args[0].clone(), location);
+    let src_ptr = fpb.get_cast_source(args[1].clone(), location);
+    let src_ptr_ty = fpb.acx.get_path_rustc_type(&src_ptr).expect("rustc type error");
+    let dst_ptr_ty = fpb.acx.get_path_rustc_type(&dst_ptr).expect("rustc type error");
+    let dst_ptr = if type_util::equivalent_ptr_types(fpb.acx.tcx, src_ptr_ty, dst_ptr_ty) {
+        dst_ptr
+    } else {
+        // Casts the destination pointer to a pointer of the same type as the source pointer.
+        let aux = fpb.create_aux_local(src_ptr_ty);
+        fpb.add_cast_edge(dst_ptr, aux.clone());
+        aux
+    };
+    fpb.copy_pointees(src_ptr, dst_ptr);
+    fpb.add_direct_edge(args[0].clone(), destination.clone());
+}
+
 fn is_std_ptr_unique<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
     match ty.kind() {
         TyKind::Adt(def, _) => {
@@ -537,7 +1020,7 @@ fn const_rawptr_type<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
     }))
 }
 
-fn const_u8_rawptr_type(tcx: TyCtxt) -> Ty {
+pub fn const_u8_rawptr_type(tcx: TyCtxt) -> Ty {
     tcx.mk_ty_from_kind(TyKind::RawPtr(rustc_middle::ty::TypeAndMut {
         ty: tcx.mk_ty_from_kind(TyKind::Slice(tcx.types.u8)),
         mutbl: rustc_middle::mir::Mutability::Not,
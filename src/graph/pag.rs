@@ -10,6 +10,7 @@ use std::collections::hash_map::Entry;
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::hash::Hash;
 use std::fmt::Debug;
+use std::rc::Rc;
 
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::Ty;
@@ -19,7 +20,7 @@ use crate::builder::fpag_builder;
 use crate::mir::call_site::CallSiteS;
 use crate::mir::function::{FuncId, GenericArgE};
 use crate::mir::analysis_context::AnalysisContext;
-use crate::mir::path::{PathEnum, ProjectionElems};
+use crate::mir::path::{Path, PathEnum, ProjectionElems};
 use crate::util::bit_vec::Idx;
 use crate::util::chunked_queue::{self, ChunkedQueue};
 
@@ -53,12 +54,17 @@ pub trait PAGPath: Clone + PartialEq + Eq + Hash + Debug {
     fn cast_to<'tcx>(&self, acx: &mut AnalysisContext<'tcx, '_>, ty: Ty<'tcx>) -> Option<Self>;
     fn type_variant<'tcx>(&self, acx: &mut AnalysisContext<'tcx, '_>, ty: Ty<'tcx>) -> Option<Self>;
     fn regularize(&self, acx: &mut AnalysisContext) -> Self;
+    fn index_variants(&self, acx: &mut AnalysisContext) -> Vec<Self>;
     fn try_eval_path_type<'tcx>(&self, acx: &mut AnalysisContext<'tcx, '_>) -> Ty<'tcx>;
     fn set_path_rustc_type<'tcx>(&self, acx: &mut AnalysisContext<'tcx, '_>, ty: Ty<'tcx>);
     fn has_been_cast(&self, acx: &AnalysisContext) -> bool;
     fn concretized_heap_type<'tcx>(&self, acx: &AnalysisContext<'tcx, '_>) -> Option<Ty<'tcx>>;
     fn flatten_fields<'tcx>(self, acx: &mut AnalysisContext<'tcx, '_>) -> Vec<(usize, Self, Ty<'tcx>)>;
     fn get_containing_func(&self) -> Option<Self::FuncTy>;
+    /// Returns the path of the object containing this path, i.e. without projections and offsets.
+    fn base_path(&self) -> Self;
+    /// Returns the path without context.
+    fn ci_path(&self) -> Rc<Path>;
 }
 
 
@@ -446,6 +452,10 @@ impl<P: PAGPath> PAG<P> {
 
         if ty.is_any_ptr() {
             add_new_direct_edge(src, dst);
+            // The destination can be an element of an index-sensitive array.
+            for variant in dst.index_variants(acx) {
+                add_new_direct_edge(src, &variant);
+            }
         } else {
             let ptr_projs = acx.get_pointer_projections(ty);
             let ptr_projs = unsafe { &*(ptr_projs as *const Vec<(ProjectionElems, Ty<'_>)>) };
@@ -453,6 +463,9 @@ impl<P: PAGPath> PAG<P> {
                 let src_field = src.append_projection(ptr_proj);
                 let dst_field = dst.append_projection(ptr_proj);
                 add_new_direct_edge(&src_field, &dst_field);
+                for variant in dst_field.index_variants(acx) {
+                    add_new_direct_edge(&src_field, &variant);
+                }
             }
         }
         added_edges
@@ -549,6 +562,20 @@ impl<P: PAGPath> PAG<P> {
                 let mut fpag = FuncPAG::new(func_id);
                 let mut builder = fpag_builder::FuncPAGBuilder::new(acx, func_id, constant_mir, &mut fpag);
                 builder.build();
+
+                // Build function pags for static variables encountered in the promoted constant,
+                // e.g. the `LocalKey` constants referenced by the `thread_local!` accessors.
+                let mut static_funcs = HashSet::new();
+                for static_variable in &fpag.static_variables_involved {
+                    if let PathEnum::StaticVariable { def_id } = static_variable.value {
+                        if let Some(static_func) = self.build_static_pag(acx, def_id) {
+                            static_funcs.insert(static_func);
+                        }
+                    }
+                }
+                if !static_funcs.is_empty() {
+                    self.involved_static_funcs_map.insert(func_id, static_funcs);
+                }
                 self.func_pags.insert(func_id, fpag);
             }
         }
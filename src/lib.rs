@@ -32,6 +32,7 @@ extern crate rustc_session;
 extern crate rustc_span;
 extern crate rustc_target;
 
+pub mod api;
 pub mod builder;
 pub mod graph;
 pub mod mir;
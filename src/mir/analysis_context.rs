@@ -5,16 +5,20 @@
 
 use log::*;
 use rustc_hir::def::DefKind;
-use rustc_hir::def_id::{DefId, DefIndex};
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_index::IndexVec;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::middle::exported_symbols::ExportedSymbol;
 use rustc_middle::mir::Promoted;
 use rustc_middle::ty::{GenericArgsRef, Ty, TyCtxt};
 use rustc_session::Session;
+use rustc_span::symbol::{sym, Symbol};
 
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+use crate::mir::entry_points;
 use crate::mir::function::{FuncId, FunctionReference, GenericArgE};
 use crate::mir::known_names::{KnownNames, KnownNamesCache};
 use crate::mir::path::Path;
@@ -22,7 +26,7 @@ use crate::util;
 use crate::util::options::AnalysisOptions;
 use crate::util::type_util::{self, FieldByteOffsetCache, TypeCache, PointerProjectionsCache, PathCastCache};
 use crate::mir::call_site::{BaseCallSite, CalleeIdentifier};
-use crate::mir::path::{PathEnum, ProjectionElems};
+use crate::mir::path::{PathEnum, PathSelector, ProjectionElems};
 
 /// Global information of the analysis
 pub struct AnalysisContext<'tcx, 'compilation> {
@@ -32,8 +36,8 @@ pub struct AnalysisContext<'tcx, 'compilation> {
     /// Represents the data associated with a compilation session for a single crate.
     pub session: &'compilation Session,
 
-    /// The entry function of the analysis.
-    pub entry_point: DefId,
+    /// The entry functions of the analysis.
+    pub entry_points: Vec<FuncId>,
 
     /// Options of the analysis.
     pub analysis_options: AnalysisOptions,
@@ -71,6 +75,14 @@ pub struct AnalysisContext<'tcx, 'compilation> {
     pub(crate) aux_local_indexer: HashMap<FuncId, usize>,
 
     pub known_names_cache: KnownNamesCache,
+
+    /// The allocator shims generated for the registered `#[global_allocator]`, indexed by their
+    /// names, e.g. `__rust_alloc`. Lazily initialized on the first lookup.
+    global_allocator_shims: Option<HashMap<Symbol, DefId>>,
+
+    /// The functions exported with `#[no_mangle]` or `#[export_name]` by all the crates, indexed
+    /// by their symbol names. Lazily initialized on the first lookup.
+    exported_functions: Option<HashMap<Symbol, DefId>>,
 }
 
 impl<'tcx, 'compilation> AnalysisContext<'tcx, 'compilation> {
@@ -80,63 +92,43 @@ impl<'tcx, 'compilation> AnalysisContext<'tcx, 'compilation> {
         analysis_options: AnalysisOptions,
     ) -> Option<Self> {
         info!("Initializing AnalysisContext");
-        let mut entry_fn_def_id: Option<DefId> = None;
-
-        // Find the DefId for the entry point according to the function name
-        if !analysis_options.entry_func.is_empty() {
-            let entr_func = analysis_options.entry_func.clone();
-            for local_def_id in tcx.hir().body_owners() {
-                let def_kind = tcx.def_kind(local_def_id);
-                if def_kind == DefKind::Fn || def_kind == DefKind::AssocFn {
-                    let item_name = tcx.item_name(local_def_id.to_def_id());
-                    if item_name.to_string() == *entr_func {
-                        entry_fn_def_id = Some(local_def_id.to_def_id());
-                    }
-                }
-            }
-        }
-
-        if entry_fn_def_id.is_none() {
-            // If `entry_def_id` flag is provided, find entry point according to the index
-            entry_fn_def_id = if let Some(entry_def_id) = analysis_options.entry_def_id {
-                Some(DefId::local(DefIndex::from_u32(entry_def_id)))
-            } else {
-                // If no entry point specified, use the default entry
-                if let Some((def_id, _)) = tcx.entry_fn(()) {
-                    Some(def_id)
-                } else {
-                    None
-                }
-            }
+        let entry_points = entry_points::find_entry_points(tcx, &analysis_options);
+        if entry_points.is_empty() {
+            error!("Entry point not found");
+            return None;
         }
 
-        if let Some(entry_def_id) = entry_fn_def_id {
-            let entry_name = tcx.item_name(entry_def_id);
-            info!("Entry Point: {:?}, DefId: {:?}", entry_name, entry_def_id);
-            Some(Self {
-                tcx,
-                session,
-                entry_point: entry_def_id,
-                analysis_options,
-                functions: IndexVec::new(),
-                func_id_map: HashMap::new(),
-                func_name_cache: HashMap::new(),
-                type_cache: TypeCache::new(),
-                path_ty_cache: HashMap::new(),
-                path_cast_cache: PathCastCache::new(),
-                path_memory_size: HashMap::new(),
-                ptr_projs_cache: PointerProjectionsCache::new(),
-                field_byte_offset_cache: FieldByteOffsetCache::new(),
-                dyn_callsite_cache: HashMap::new(),
-                special_functions: HashSet::new(),
-                aux_local_indexer: HashMap::new(),
-                concretized_heap_objs: HashMap::new(),
-                known_names_cache: KnownNamesCache::create_cache_from_language_items(),
-            })
-        } else {
-            error!("Entry point not found");
-            None
+        let mut acx = Self {
+            tcx,
+            session,
+            entry_points: Vec::new(),
+            analysis_options,
+            functions: IndexVec::new(),
+            func_id_map: HashMap::new(),
+            func_name_cache: HashMap::new(),
+            type_cache: TypeCache::new(),
+            path_ty_cache: HashMap::new(),
+            path_cast_cache: PathCastCache::new(),
+            path_memory_size: HashMap::new(),
+            ptr_projs_cache: PointerProjectionsCache::new(),
+            field_byte_offset_cache: FieldByteOffsetCache::new(),
+            dyn_callsite_cache: HashMap::new(),
+            special_functions: HashSet::new(),
+            aux_local_indexer: HashMap::new(),
+            concretized_heap_objs: HashMap::new(),
+            known_names_cache: KnownNamesCache::create_cache_from_language_items(),
+            global_allocator_shims: None,
+            exported_functions: None,
+        };
+        for (entry_def_id, entry_args) in entry_points {
+            let entry_func_id = acx.get_func_id(entry_def_id, entry_args);
+            info!(
+                "Entry Point: {}, DefId: {:?}",
+                acx.get_function_reference(entry_func_id).to_string(), entry_def_id
+            );
+            acx.entry_points.push(entry_func_id);
         }
+        Some(acx)
     }
 
     /// Records the type of `path`.
@@ -228,7 +220,70 @@ impl<'tcx, 'compilation> AnalysisContext<'tcx, 'compilation> {
 
     /// Get the pointer type fields' projections.
     pub fn get_pointer_projections(&mut self, ty: Ty<'tcx>) -> &Vec<(ProjectionElems, Ty<'tcx>)> {
-        self.ptr_projs_cache.get_pointer_projections(self.tcx, ty)
+        self.ptr_projs_cache.get_pointer_projections(self.tcx, ty, self.analysis_options.index_sensitive_arrays)
+    }
+
+    /// Returns the length of the given array type if its elements are distinguished by constant indices.
+    pub fn index_sensitive_array_len(&self, ty: Ty<'tcx>) -> Option<usize> {
+        type_util::index_sensitive_array_len(self.tcx, ty, self.analysis_options.index_sensitive_arrays)
+    }
+
+    /// Returns the paths that refer to the same elements of index-sensitive arrays as the given path,
+    /// i.e. the summarizing element of a constant-index element and all the constant-index elements
+    /// of a summarizing element. A value written to the given path should also be written to them.
+    pub fn get_index_variants(&mut self, path: &Rc<Path>) -> Vec<Rc<Path>> {
+        if self.analysis_options.index_sensitive_arrays.is_none() {
+            return Vec::new();
+        }
+        let PathEnum::QualifiedPath { base, projection } = &path.value else {
+            return Vec::new();
+        };
+        if !projection
+            .iter()
+            .any(|selector| matches!(selector, PathSelector::Index | PathSelector::ConstantIndex(..)))
+        {
+            return Vec::new();
+        }
+
+        let mut variants: Vec<ProjectionElems> = vec![Vec::new()];
+        for (i, selector) in projection.iter().enumerate() {
+            let alternatives = match selector {
+                PathSelector::ConstantIndex(..) => vec![*selector, PathSelector::Index],
+                PathSelector::Index => {
+                    let array = Path::truncate_projection_elems(path, i);
+                    let array_ty = type_util::try_eval_path_type(self, &array).expect("rustc type error");
+                    let mut alternatives = vec![PathSelector::Index];
+                    if let Some(len) = self.index_sensitive_array_len(array_ty) {
+                        alternatives.extend((0..len).map(PathSelector::ConstantIndex));
+                    }
+                    alternatives
+                }
+                _ => vec![*selector],
+            };
+            variants = variants
+                .into_iter()
+                .flat_map(|variant| {
+                    alternatives.iter().map(move |selector| {
+                        let mut variant = variant.clone();
+                        variant.push(*selector);
+                        variant
+                    })
+                })
+                .collect();
+        }
+
+        let path_ty = self.get_path_rustc_type(path);
+        variants
+            .into_iter()
+            .filter(|variant| variant != projection)
+            .map(|variant| {
+                let variant = Path::new_qualified(base.clone(), variant);
+                if let Some(ty) = path_ty {
+                    self.set_path_rustc_type(variant.clone(), ty);
+                }
+                variant
+            })
+            .collect()
     }
 
     /// Get the byte offset of a specific field.
@@ -306,6 +361,82 @@ impl<'tcx, 'compilation> AnalysisContext<'tcx, 'compilation> {
         self.known_names_cache.get(self.tcx, def_id)
     }
 
+    /// Returns the allocator shim named `name` that forwards to the registered global allocator,
+    /// e.g. `__rust_alloc` that calls `GlobalAlloc::alloc`.
+    ///
+    /// The `#[global_allocator]` attribute expands to a set of functions marked with
+    /// `#[rustc_std_internal_symbol]`, which are defined in the crate registering the allocator.
+    pub fn get_global_allocator_shim(&mut self, name: &str) -> Option<DefId> {
+        let tcx = self.tcx;
+        let shims = self.global_allocator_shims.get_or_insert_with(|| {
+            let mut shims = HashMap::new();
+            if tcx.has_global_allocator(LOCAL_CRATE) {
+                for local_def_id in tcx.hir().body_owners() {
+                    if tcx.def_kind(local_def_id) == DefKind::Fn
+                        && tcx.has_attr(local_def_id, sym::rustc_std_internal_symbol)
+                    {
+                        shims.insert(tcx.item_name(local_def_id.to_def_id()), local_def_id.to_def_id());
+                    }
+                }
+            }
+            for cnum in tcx.crates(()) {
+                if !tcx.has_global_allocator(*cnum) {
+                    continue;
+                }
+                for (exported_symbol, _) in tcx.exported_symbols(*cnum) {
+                    if let ExportedSymbol::NonGeneric(def_id) = exported_symbol {
+                        if tcx.def_kind(*def_id) == DefKind::Fn {
+                            shims.insert(tcx.item_name(*def_id), *def_id);
+                        }
+                    }
+                }
+            }
+            shims
+        });
+        shims.get(&Symbol::intern(name)).copied()
+    }
+
+    /// Resolves a function declared in an `extern` block to the function defined with the same
+    /// symbol name via `#[no_mangle]` or `#[export_name]` in any crate, as a linker does.
+    /// Returns `None` if `def_id` is not a foreign item or no such definition is found.
+    pub fn resolve_foreign_item(&mut self, def_id: DefId) -> Option<DefId> {
+        let tcx = self.tcx;
+        if !tcx.is_foreign_item(def_id) {
+            return None;
+        }
+        let symbol_name = tcx.codegen_fn_attrs(def_id).link_name.unwrap_or_else(|| tcx.item_name(def_id));
+        let exported_functions = self.exported_functions.get_or_insert_with(|| {
+            let mut exported_functions = HashMap::new();
+            let mut add_exported_function = |def_id: DefId| {
+                if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+                    return;
+                }
+                let codegen_fn_attrs = tcx.codegen_fn_attrs(def_id);
+                let symbol_name = if let Some(export_name) = codegen_fn_attrs.export_name {
+                    export_name
+                } else if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NO_MANGLE) {
+                    tcx.item_name(def_id)
+                } else {
+                    return;
+                };
+                debug!("Exported function: {:?} => {:?}", symbol_name, def_id);
+                exported_functions.entry(symbol_name).or_insert(def_id);
+            };
+            for local_def_id in tcx.hir().body_owners() {
+                add_exported_function(local_def_id.to_def_id());
+            }
+            for cnum in tcx.crates(()) {
+                for (exported_symbol, _) in tcx.exported_symbols(*cnum) {
+                    if let ExportedSymbol::NonGeneric(def_id) = exported_symbol {
+                        add_exported_function(*def_id);
+                    }
+                }
+            }
+            exported_functions
+        });
+        exported_functions.get(&symbol_name).copied()
+    }
+
     /// Creates an auxiliary local variable with the given type for the given `func_id`.
     /// Returns the path of the auxiliary local variable.
     ///
@@ -0,0 +1,524 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Finds the entry functions from which the analysis begins.
+
+use itertools::Itertools;
+use log::*;
+use std::collections::HashSet;
+
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, DefIndex, LOCAL_CRATE};
+use rustc_hir::LangItem;
+use rustc_middle::ty::{self, GenericArg, GenericArgs, GenericArgsRef, GenericParamDefKind, Ty, TyCtxt};
+use rustc_span::symbol::{sym, Symbol};
+
+use crate::util::options::AnalysisOptions;
+
+/// The maximum number of instantiations of a generic entry function whose type parameters are
+/// instantiated with the local implementors of their trait bounds.
+const MAX_ENTRY_INSTANTIATIONS: usize = 16;
+
+/// The maximum number of combinations of the candidate types tried when instantiating a generic
+/// entry function.
+const MAX_ENTRY_CANDIDATES: usize = 256;
+
+/// The way to select the entry functions of the analysis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryMode {
+    /// Analyze the program from a single entry function, which is `main` by default.
+    Main,
+    /// Analyze a library from all of its public functions and methods.
+    Lib,
+    /// Analyze a crate compiled with `--test` from all of its test functions.
+    Tests,
+}
+
+/// Returns the entry functions and their generic arguments according to the entry mode.
+pub fn find_entry_points<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    analysis_options: &AnalysisOptions,
+) -> Vec<(DefId, GenericArgsRef<'tcx>)> {
+    match analysis_options.entry_mode {
+        EntryMode::Main => find_main_entry_points(tcx, analysis_options),
+        EntryMode::Lib => find_public_functions(tcx),
+        EntryMode::Tests => find_test_functions(tcx),
+    }
+}
+
+/// Finds the entry functions specified by `--entry-func` or `--entry-id`, or the `main` function
+/// of the crate if neither is provided. Returns an empty vector if any of the specified entry
+/// functions cannot be resolved.
+fn find_main_entry_points<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    analysis_options: &AnalysisOptions,
+) -> Vec<(DefId, GenericArgsRef<'tcx>)> {
+    // Find the DefIds for the entry points according to the function names or paths
+    if !analysis_options.entry_func.is_empty() {
+        let mut entry_points = Vec::new();
+        for entry_func in &analysis_options.entry_func {
+            match resolve_entry_func(tcx, entry_func) {
+                Some(instances) => entry_points.extend(instances),
+                None => return Vec::new(),
+            }
+        }
+        return entry_points;
+    }
+
+    // If `entry_def_id` flag is provided, find entry point according to the index
+    if let Some(entry_def_id) = analysis_options.entry_def_id {
+        return vec![(DefId::local(DefIndex::from_u32(entry_def_id)), tcx.mk_args(&[]))];
+    }
+    // If no entry point specified, use the default entry
+    tcx.entry_fn(())
+        .map(|(def_id, _)| (def_id, tcx.mk_args(&[])))
+        .into_iter()
+        .collect()
+}
+
+/// Resolves an entry function from its name or its def path, optionally followed by the generic
+/// arguments of the function, e.g. `crate::module::process::<MyConfig, Vec<u8>>`. A path such as
+/// `crate::module::Type::method` is matched against the full def paths of the local functions,
+/// while a plain name is matched against their item names. A generic function without generic
+/// arguments is instantiated with the local implementors of its trait bounds. Reports an error
+/// if the entry function is not found, is ambiguous or cannot be instantiated.
+fn resolve_entry_func<'tcx>(tcx: TyCtxt<'tcx>, entry_func: &str) -> Option<Vec<(DefId, GenericArgsRef<'tcx>)>> {
+    let (entry_func, generic_args) = split_generic_args(entry_func);
+    let entry_path = strip_crate_prefix(tcx, entry_func);
+    let candidates: Vec<DefId> = local_functions(tcx)
+        .filter(|def_id| {
+            if entry_func.contains("::") {
+                tcx.def_path_str(*def_id) == entry_path
+            } else {
+                tcx.item_name(*def_id).as_str() == entry_path
+            }
+        })
+        .collect();
+    match candidates.len() {
+        0 => {
+            tcx.dcx().err(format!(
+                "entry function `{entry_func}` not found, use `--list-entries` to list the candidates"
+            ));
+            None
+        }
+        1 => {
+            let def_id = candidates[0];
+            let Some(generic_args) = generic_args else {
+                let instances = instantiate_entry_point(tcx, def_id);
+                if instances.is_empty() {
+                    tcx.dcx().err(format!(
+                        "entry function `{entry_func}` cannot be instantiated, specify its generic arguments \
+                        like `{entry_func}::<T>`"
+                    ));
+                }
+                return Some(instances.into_iter().map(|args| (def_id, args)).collect());
+            };
+            match instantiate_entry_point_with(tcx, def_id, generic_args) {
+                Ok(args) => Some(vec![(def_id, args)]),
+                Err(msg) => {
+                    tcx.dcx().err(format!("entry function `{entry_func}` cannot be instantiated: {msg}"));
+                    None
+                }
+            }
+        }
+        _ => {
+            let candidate_list = candidates
+                .iter()
+                .map(|def_id| describe_entry_candidate(tcx, *def_id))
+                .collect::<Vec<_>>()
+                .join("\n");
+            tcx.dcx().err(format!(
+                "entry function `{entry_func}` is ambiguous, candidates are:\n{candidate_list}"
+            ));
+            None
+        }
+    }
+}
+
+/// Prints the functions and methods of the local crate that can be used as entry functions,
+/// with their def paths and the def indices accepted by `--entry-id`.
+pub fn list_entry_candidates(tcx: TyCtxt<'_>) {
+    for def_id in local_functions(tcx) {
+        println!("{}", describe_entry_candidate(tcx, def_id));
+    }
+}
+
+fn describe_entry_candidate(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    format!(
+        "{} (def_id: {:?}, entry-id: {})",
+        tcx.def_path_str(def_id),
+        def_id,
+        def_id.index.as_u32()
+    )
+}
+
+/// Returns the functions and methods with bodies in the local crate.
+fn local_functions(tcx: TyCtxt<'_>) -> impl Iterator<Item = DefId> + '_ {
+    tcx.hir().body_owners().filter_map(move |local_def_id| {
+        let def_kind = tcx.def_kind(local_def_id);
+        if def_kind == DefKind::Fn || def_kind == DefKind::AssocFn {
+            Some(local_def_id.to_def_id())
+        } else {
+            None
+        }
+    })
+}
+
+/// Finds the functions and methods of the local crate that are reachable from other crates,
+/// i.e. the public APIs of a library.
+fn find_public_functions(tcx: TyCtxt<'_>) -> Vec<(DefId, GenericArgsRef<'_>)> {
+    let effective_visibilities = tcx.effective_visibilities(());
+    let mut entry_points = Vec::new();
+    for local_def_id in tcx.hir().body_owners() {
+        let def_kind = tcx.def_kind(local_def_id);
+        if def_kind != DefKind::Fn && def_kind != DefKind::AssocFn {
+            continue;
+        }
+        if !effective_visibilities.is_reachable(local_def_id) {
+            continue;
+        }
+        let def_id = local_def_id.to_def_id();
+        let instances = instantiate_entry_point(tcx, def_id);
+        if instances.is_empty() {
+            warn!(
+                "Skipped the generic entry function that cannot be instantiated: {:?}",
+                tcx.def_path_str(def_id)
+            );
+        }
+        entry_points.extend(instances.into_iter().map(|args| (def_id, args)));
+    }
+    entry_points
+}
+
+/// Finds the test functions registered by the test harness, which generates a constant marked
+/// with `#[rustc_test_marker]` for each test function. The constant is defined in the same module
+/// as the test function and has the same name.
+fn find_test_functions(tcx: TyCtxt<'_>) -> Vec<(DefId, GenericArgsRef<'_>)> {
+    let mut test_markers = HashSet::new();
+    for local_def_id in tcx.hir().body_owners() {
+        if tcx.def_kind(local_def_id) == DefKind::Const && tcx.has_attr(local_def_id, sym::rustc_test_marker) {
+            let def_id = local_def_id.to_def_id();
+            test_markers.insert((tcx.parent(def_id), tcx.item_name(def_id)));
+        }
+    }
+    let mut entry_points = Vec::new();
+    for local_def_id in tcx.hir().body_owners() {
+        if tcx.def_kind(local_def_id) != DefKind::Fn {
+            continue;
+        }
+        let def_id = local_def_id.to_def_id();
+        if test_markers.contains(&(tcx.parent(def_id), tcx.item_name(def_id))) {
+            entry_points.push((def_id, tcx.mk_args(&[])));
+        }
+    }
+    if test_markers.is_empty() {
+        warn!("No test functions found, the crate should be compiled with `--test`");
+    }
+    entry_points
+}
+
+/// Instantiates the generic parameters of an entry function, including the ones of its parent
+/// impl or trait. The lifetimes are erased, the type parameters bounded by traits other than
+/// `Sized` are instantiated with the non-generic local types satisfying their bounds, and the
+/// other type parameters are instantiated with the unit type. Falls back to the unit type for all
+/// the type parameters if no local types satisfy the bounds. Returns the instantiations that
+/// satisfy the bounds of the function, which is empty if it has const parameters.
+pub fn instantiate_entry_point(tcx: TyCtxt<'_>, def_id: DefId) -> Vec<GenericArgsRef<'_>> {
+    let generics = tcx.generics_of(def_id);
+    let bounded_params = trait_bounded_params(tcx, def_id);
+    let local_types = local_non_generic_types(tcx);
+    let mut param_candidates: Vec<Vec<GenericArg<'_>>> = Vec::new();
+    for index in 0..generics.count() {
+        let param = generics.param_at(index, tcx);
+        let candidates = match param.kind {
+            GenericParamDefKind::Lifetime => vec![tcx.lifetimes.re_erased.into()],
+            GenericParamDefKind::Type { .. } if bounded_params.contains(&param.index) => local_types
+                .iter()
+                .filter(|ty| satisfies_param_bounds(tcx, def_id, param.index, **ty))
+                .map(|ty| (*ty).into())
+                .collect(),
+            GenericParamDefKind::Type { .. } => vec![tcx.types.unit.into()],
+            GenericParamDefKind::Const { .. } => return Vec::new(),
+        };
+        param_candidates.push(candidates);
+    }
+
+    let mut instances: Vec<GenericArgsRef<'_>> = param_candidates
+        .into_iter()
+        .multi_cartesian_product()
+        .take(MAX_ENTRY_CANDIDATES)
+        .map(|args| tcx.mk_args(&args))
+        .filter(|args| !tcx.subst_and_check_impossible_predicates((def_id, *args)))
+        .take(MAX_ENTRY_INSTANTIATIONS)
+        .collect();
+    if generics.count() == 0 {
+        // `multi_cartesian_product` yields nothing for an empty list of parameters.
+        instances.push(tcx.mk_args(&[]));
+    } else if instances.is_empty() {
+        let args = GenericArgs::for_item(tcx, def_id, |param, _| match param.kind {
+            GenericParamDefKind::Lifetime => tcx.lifetimes.re_erased.into(),
+            _ => tcx.types.unit.into(),
+        });
+        if !tcx.subst_and_check_impossible_predicates((def_id, args)) {
+            instances.push(args);
+        }
+    }
+    instances
+}
+
+/// Instantiates the generic parameters of an entry function with the given generic arguments,
+/// which are parsed as the types of its type parameters in order, including the ones of its parent
+/// impl or trait. The lifetimes are erased.
+fn instantiate_entry_point_with<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generic_args: &str,
+) -> Result<GenericArgsRef<'tcx>, String> {
+    let mut types = split_top_level(generic_args, ',')
+        .into_iter()
+        .map(|ty| parse_type(tcx, ty))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
+    let mut error = None;
+    let args = GenericArgs::for_item(tcx, def_id, |param, _| match param.kind {
+        GenericParamDefKind::Lifetime => tcx.lifetimes.re_erased.into(),
+        GenericParamDefKind::Type { .. } => types.next().unwrap_or_else(|| {
+            error = Some(format!("missing the generic argument for `{}`", param.name));
+            tcx.types.unit
+        }).into(),
+        GenericParamDefKind::Const { .. } => {
+            error = Some(format!("const parameter `{}` is not supported", param.name));
+            tcx.mk_param_from_def(param)
+        }
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    if types.next().is_some() {
+        return Err("too many generic arguments".to_string());
+    }
+    if tcx.subst_and_check_impossible_predicates((def_id, args)) {
+        return Err("the generic arguments do not satisfy the bounds".to_string());
+    }
+    Ok(args)
+}
+
+/// Returns the indices of the type parameters of a function, including the ones of its parent,
+/// that are bounded by traits other than `Sized`.
+fn trait_bounded_params(tcx: TyCtxt<'_>, def_id: DefId) -> HashSet<u32> {
+    let sized_trait = tcx.lang_items().sized_trait();
+    let mut bounded_params = HashSet::new();
+    for clause in tcx.predicates_of(def_id).instantiate_identity(tcx).predicates {
+        if let Some(trait_pred) = clause.as_trait_clause() {
+            let trait_pred = trait_pred.skip_binder();
+            if Some(trait_pred.def_id()) == sized_trait {
+                continue;
+            }
+            if let ty::Param(param_ty) = trait_pred.self_ty().kind() {
+                bounded_params.insert(param_ty.index);
+            }
+        }
+    }
+    bounded_params
+}
+
+/// Returns true if the type satisfies the bounds of the function on the type parameter at `index`.
+/// The other type parameters are kept, so that the bounds involving them are skipped by
+/// `subst_and_check_impossible_predicates`.
+fn satisfies_param_bounds<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, index: u32, ty: Ty<'tcx>) -> bool {
+    let args = GenericArgs::for_item(tcx, def_id, |param, _| match param.kind {
+        GenericParamDefKind::Lifetime => tcx.lifetimes.re_erased.into(),
+        _ if param.index == index => ty.into(),
+        _ => tcx.mk_param_from_def(param),
+    });
+    !tcx.subst_and_check_impossible_predicates((def_id, args))
+}
+
+/// Returns the non-generic structs, enums and unions defined in the local crate.
+fn local_non_generic_types(tcx: TyCtxt<'_>) -> Vec<Ty<'_>> {
+    local_adts(tcx)
+        .filter(|def_id| tcx.generics_of(*def_id).count() == 0)
+        .map(|def_id| tcx.type_of(def_id).instantiate_identity())
+        .collect()
+}
+
+/// Returns the structs, enums and unions defined in the local crate.
+fn local_adts(tcx: TyCtxt<'_>) -> impl Iterator<Item = DefId> + '_ {
+    tcx.hir_crate_items(()).definitions().filter_map(move |local_def_id| {
+        match tcx.def_kind(local_def_id) {
+            DefKind::Struct | DefKind::Enum | DefKind::Union => Some(local_def_id.to_def_id()),
+            _ => None,
+        }
+    })
+}
+
+/// Strips the leading `crate::` or `<crate name>::` from a path.
+fn strip_crate_prefix<'a>(tcx: TyCtxt<'_>, path: &'a str) -> &'a str {
+    let crate_prefix = format!("{}::", tcx.crate_name(LOCAL_CRATE));
+    path.strip_prefix("crate::")
+        .or_else(|| path.strip_prefix(&crate_prefix))
+        .unwrap_or(path)
+}
+
+/// Splits a function path such as `module::process::<A, B>` into the path and the generic
+/// arguments `A, B`.
+fn split_generic_args(path: &str) -> (&str, Option<&str>) {
+    let mut depth = 0;
+    for (i, c) in path.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ':' if depth == 0 && path[i..].starts_with("::<") && path.ends_with('>') => {
+                return (&path[..i], Some(&path[i + 3..path.len() - 1]));
+            }
+            _ => {}
+        }
+    }
+    (path, None)
+}
+
+/// Splits a string by the separator outside any brackets.
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            _ if c == separator && depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !s[start..].trim().is_empty() {
+        parts.push(s[start..].trim());
+    }
+    parts
+}
+
+/// Parses a type written in Rust syntax. Supports the primitive types, references, tuples, slices,
+/// arrays, and the structs, enums and unions of the local crate or the standard library.
+fn parse_type<'tcx>(tcx: TyCtxt<'tcx>, ty: &str) -> Result<Ty<'tcx>, String> {
+    let ty = ty.trim();
+    if let Some(pointee) = ty.strip_prefix('&') {
+        return match pointee.trim_start().strip_prefix("mut ") {
+            Some(pointee) => Ok(Ty::new_mut_ref(tcx, tcx.lifetimes.re_erased, parse_type(tcx, pointee)?)),
+            None => Ok(Ty::new_imm_ref(tcx, tcx.lifetimes.re_erased, parse_type(tcx, pointee)?)),
+        };
+    }
+    if let Some(elems) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+        let elem_tys = split_top_level(elems, ',')
+            .into_iter()
+            .map(|elem| parse_type(tcx, elem))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Ty::new_tup(tcx, &elem_tys));
+    }
+    if let Some(elem) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        return match split_top_level(elem, ';')[..] {
+            [elem] => Ok(Ty::new_slice(tcx, parse_type(tcx, elem)?)),
+            [elem, len] => {
+                let len = len.parse::<u64>().map_err(|_| format!("invalid array length `{len}`"))?;
+                Ok(Ty::new_array(tcx, parse_type(tcx, elem)?, len))
+            }
+            _ => Err(format!("invalid type `{ty}`")),
+        };
+    }
+    let primitive = match ty {
+        "bool" => Some(tcx.types.bool),
+        "char" => Some(tcx.types.char),
+        "str" => Some(tcx.types.str_),
+        "i8" => Some(tcx.types.i8),
+        "i16" => Some(tcx.types.i16),
+        "i32" => Some(tcx.types.i32),
+        "i64" => Some(tcx.types.i64),
+        "i128" => Some(tcx.types.i128),
+        "isize" => Some(tcx.types.isize),
+        "u8" => Some(tcx.types.u8),
+        "u16" => Some(tcx.types.u16),
+        "u32" => Some(tcx.types.u32),
+        "u64" => Some(tcx.types.u64),
+        "u128" => Some(tcx.types.u128),
+        "usize" => Some(tcx.types.usize),
+        "f32" => Some(tcx.types.f32),
+        "f64" => Some(tcx.types.f64),
+        _ => None,
+    };
+    if let Some(primitive) = primitive {
+        return Ok(primitive);
+    }
+
+    let (path, generic_args) = match ty.find('<') {
+        Some(i) if ty.ends_with('>') => (&ty[..i], Some(&ty[i + 1..ty.len() - 1])),
+        _ => (ty, None),
+    };
+    let adt_def_id = resolve_adt(tcx, path.trim_end_matches("::"))?;
+    let mut types = match generic_args {
+        Some(generic_args) => split_top_level(generic_args, ',')
+            .into_iter()
+            .map(|ty| parse_type(tcx, ty))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => Vec::new(),
+    }
+    .into_iter();
+    let mut error = None;
+    let args = GenericArgs::for_item(tcx, adt_def_id, |param, args| match param.kind {
+        GenericParamDefKind::Lifetime => tcx.lifetimes.re_erased.into(),
+        GenericParamDefKind::Type { has_default, .. } => match types.next() {
+            Some(ty) => ty.into(),
+            None if has_default => tcx.type_of(param.def_id).instantiate(tcx, args).into(),
+            None => {
+                error = Some(format!("missing the generic argument for `{}` of `{path}`", param.name));
+                tcx.types.unit.into()
+            }
+        },
+        GenericParamDefKind::Const { .. } => {
+            error = Some(format!("const parameter `{}` of `{path}` is not supported", param.name));
+            tcx.mk_param_from_def(param)
+        }
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    if types.next().is_some() {
+        return Err(format!("too many generic arguments for `{path}`"));
+    }
+    Ok(Ty::new_adt(tcx, tcx.adt_def(adt_def_id), args))
+}
+
+/// Resolves a struct, enum or union from its name or path. The local types are matched first,
+/// then the types of the standard library that are diagnostic items or lang items, such as `Vec`
+/// and `String`.
+fn resolve_adt(tcx: TyCtxt<'_>, path: &str) -> Result<DefId, String> {
+    let local_path = strip_crate_prefix(tcx, path);
+    let candidates: Vec<DefId> = local_adts(tcx)
+        .filter(|def_id| {
+            if path.contains("::") {
+                tcx.def_path_str(*def_id) == local_path
+            } else {
+                tcx.item_name(*def_id).as_str() == local_path
+            }
+        })
+        .collect();
+    match candidates[..] {
+        [def_id] => return Ok(def_id),
+        [] => {}
+        _ => return Err(format!("type `{path}` is ambiguous")),
+    }
+    let name = path.rsplit("::").next().unwrap_or(path);
+    let std_def_id = if name == "Box" {
+        tcx.lang_items().owned_box()
+    } else {
+        let name = Symbol::intern(name);
+        tcx.get_diagnostic_item(name)
+            .or_else(|| LangItem::from_name(name).and_then(|item| tcx.lang_items().get(item)))
+    };
+    match std_def_id {
+        Some(def_id) if matches!(tcx.def_kind(def_id), DefKind::Struct | DefKind::Enum | DefKind::Union) => Ok(def_id),
+        _ => Err(format!("type `{path}` not found")),
+    }
+}
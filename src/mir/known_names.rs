@@ -6,7 +6,7 @@
 
 use rustc_hir::def_id::DefId;
 use rustc_hir::definitions::{DefPathData, DisambiguatedDefPathData};
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{TyCtxt, TyKind};
 use std::collections::HashMap;
 
 /// Well known definitions (language provided items) that are treated in special ways.
@@ -26,6 +26,17 @@ pub enum KnownNames {
     RustRealloc,           // fn __rust_realloc(ptr: *mut u8, old_size: usize, align: usize, new_size: usize) -> *mut u8;
     RustAllocErrorHandler, // fn __rust_alloc_error_handler(size: usize, align: usize) -> !;
 
+    // Memory functions of the C library, which are foreign functions declared in `libc` or in
+    // the `extern "C"` blocks of the analyzed crate.
+    LibcMalloc,            // fn malloc(size: size_t) -> *mut c_void;
+    LibcCalloc,            // fn calloc(nobj: size_t, size: size_t) -> *mut c_void;
+    LibcRealloc,           // fn realloc(p: *mut c_void, size: size_t) -> *mut c_void;
+    LibcFree,              // fn free(p: *mut c_void);
+    LibcMemcpy,            // fn memcpy(dest: *mut c_void, src: *const c_void, n: size_t) -> *mut c_void;
+    LibcMemmove,           // fn memmove(dest: *mut c_void, src: *const c_void, n: size_t) -> *mut c_void;
+    LibcStrdup,            // fn strdup(cs: *const c_char) -> *mut c_char;
+    LibcMmap,              // fn mmap(addr: *mut c_void, len: size_t, prot: c_int, flags: c_int, fd: c_int, offset: off_t) -> *mut c_void;
+
     // Allocate|Deallocate memory with the global allocator. Wrappers of `__rust_alloc`, `__rust_alloc_zeroed`...
     StdAllocAlloc,            // fn alloc(Layout) -> *mut u8
     StdAllocAllocZeroed,      // fn alloc_zeroed(layout: Layout) -> *mut u8
@@ -61,6 +72,10 @@ pub enum KnownNames {
     StdCloneClone,
     StdFutureFromGenerator,
     StdIntrinsicsArithOffset,
+    StdIntrinsicsAtomicCxchg,   // fn atomic_cxchg*<T: Copy>(dst: *mut T, old: T, src: T) -> (T, bool)
+    StdIntrinsicsAtomicLoad,    // fn atomic_load*<T: Copy>(src: *const T) -> T
+    StdIntrinsicsAtomicStore,   // fn atomic_store*<T: Copy>(dst: *mut T, val: T)
+    StdIntrinsicsAtomicXchg,    // fn atomic_xchg*<T: Copy>(dst: *mut T, src: T) -> T
     StdIntrinsicsBitreverse,
     StdIntrinsicsBswap,
     StdIntrinsicsCeilf32,
@@ -126,7 +141,9 @@ pub enum KnownNames {
     StdIntrinsicsTruncf64,
     StdIntrinsicsWriteBytes,
     StdMarkerPhantomData,
-    StdMemReplace,
+    StdMemReplace, // fn replace<T>(dest: &mut T, src: T) -> T
+    StdMemSwap,    // fn swap<T>(x: &mut T, y: &mut T)
+    StdMemTake,    // fn take<T: Default>(dest: &mut T) -> T
 
     // Indirect function calls via Fn::call|FnOnce::call_once|FnMut::call_mut
     StdOpsFunctionImpls,
@@ -137,8 +154,15 @@ pub enum KnownNames {
     StdPanickingAssertFailed,
     StdPanickingBeginPanic,
     StdPanickingBeginPanicFmt,
+    StdPanickingRustPanicWithHook, // fn rust_panic_with_hook(payload, message, location, can_unwind, force_no_backtrace) -> !
+    StdPanickingSetHook,           // fn set_hook(hook: Box<dyn Fn(&PanicInfo<'_>) + Sync + Send + 'static>)
+    StdPanickingTakeHook,          // fn take_hook() -> Box<dyn Fn(&PanicInfo<'_>) + Sync + Send + 'static>
 
-    StdPtrSwapNonOverlapping,
+    StdPtrRead,               // fn read<T>(src: *const T) -> T
+    StdPtrReplace,            // fn replace<T>(dst: *mut T, src: T) -> T
+    StdPtrSwap,               // fn swap<T>(x: *mut T, y: *mut T)
+    StdPtrSwapNonOverlapping, // fn swap_nonoverlapping<T>(x: *mut T, y: *mut T, count: usize)
+    StdPtrWrite,              // fn write<T>(dst: *mut T, src: T)
     StdPtrNonNullAsPtr,
     StdPtrNonNullAsRef,
     StdPtrNonNullAsMut,
@@ -171,14 +195,138 @@ pub enum KnownNames {
     StdPtrMutPtrWrappingByteSub,
     StdPtrMutPtrWrappingByteOffset,
 
+    // Smart pointers and interior mutability types, modeled with `--model-std-smart-pointers`
+    StdRcNew,                 // fn new(value: T) -> Rc<T>
+    StdRcClone,               // fn clone(&self) -> Rc<T>
+    StdRcDeref,               // fn deref(&self) -> &T
+    StdRcIntoInner,           // fn into_inner(this: Rc<T>) -> Option<T>
+    StdArcNew,                // fn new(data: T) -> Arc<T>
+    StdArcClone,              // fn clone(&self) -> Arc<T>
+    StdArcDeref,              // fn deref(&self) -> &T
+    StdArcIntoInner,          // fn into_inner(this: Arc<T>) -> Option<T>
+    StdCellNew,               // fn new(value: T) -> Cell<T>
+    StdCellGet,               // fn get(&self) -> T
+    StdCellSet,               // fn set(&self, val: T)
+    StdCellReplace,           // fn replace(&self, val: T) -> T
+    StdCellIntoInner,         // fn into_inner(self) -> T
+    StdRefCellNew,            // fn new(value: T) -> RefCell<T>
+    StdRefCellBorrow,         // fn borrow(&self) -> Ref<'_, T>
+    StdRefCellBorrowMut,      // fn borrow_mut(&self) -> RefMut<'_, T>
+    StdRefCellReplace,        // fn replace(&self, t: T) -> T
+    StdRefCellIntoInner,      // fn into_inner(self) -> T
+    StdMutexNew,              // fn new(t: T) -> Mutex<T>
+    StdMutexLock,             // fn lock(&self) -> LockResult<MutexGuard<'_, T>>
+    StdMutexIntoInner,        // fn into_inner(self) -> LockResult<T>
+    StdMutexGuardDeref,       // fn deref(&self) -> &T, fn deref_mut(&mut self) -> &mut T
+    StdRwLockNew,             // fn new(t: T) -> RwLock<T>
+    StdRwLockRead,            // fn read(&self) -> LockResult<RwLockReadGuard<'_, T>>
+    StdRwLockWrite,           // fn write(&self) -> LockResult<RwLockWriteGuard<'_, T>>
+    StdRwLockIntoInner,       // fn into_inner(self) -> LockResult<T>
+    StdRwLockWriteGuardDeref, // fn deref(&self) -> &T, fn deref_mut(&mut self) -> &mut T
+
+    // Std collections, modeled with `--model-std-collections`
+    StdVecNew,                // fn new() -> Vec<T>, fn with_capacity(capacity: usize) -> Vec<T>, fn default() -> Vec<T>
+    StdVecPush,               // fn push(&mut self, value: T)
+    StdVecPop,                // fn pop(&mut self) -> Option<T>
+    StdVecInsert,             // fn insert(&mut self, index: usize, element: T)
+    StdVecRemove,             // fn remove(&mut self, index: usize) -> T, fn swap_remove(&mut self, index: usize) -> T
+    StdVecDeref,              // fn deref(&self) -> &[T], fn as_slice(&self) -> &[T], and their mutable versions
+    StdVecIndex,              // fn index(&self, index: I) -> &I::Output, fn index_mut(&mut self, index: I) -> &mut I::Output
+    StdVecAsPtr,              // fn as_ptr(&self) -> *const T, fn as_mut_ptr(&mut self) -> *mut T
+    StdVecIntoIter,           // fn into_iter(self) -> IntoIter<T, A>
+    StdVecQuery,              // fn len(&self) -> usize, fn clear(&mut self), fn truncate(&mut self, len: usize), fn reserve(&mut self, additional: usize), ...
+    StdSliceIntoVec,          // fn into_vec(self: Box<[T]>) -> Vec<T>
+    StdVecDequeNew,           // fn new() -> VecDeque<T>, fn with_capacity(capacity: usize) -> VecDeque<T>, fn default() -> VecDeque<T>
+    StdVecDequePush,          // fn push_back(&mut self, value: T), fn push_front(&mut self, value: T)
+    StdVecDequeInsert,        // fn insert(&mut self, index: usize, value: T)
+    StdVecDequePop,           // fn pop_back(&mut self) -> Option<T>, fn pop_front(&mut self) -> Option<T>, fn remove(&mut self, index: usize) -> Option<T>
+    StdVecDequeGet,           // fn get(&self, index: usize) -> Option<&T>, fn front(&self) -> Option<&T>, fn back(&self) -> Option<&T>, and their mutable versions
+    StdVecDequeIndex,         // fn index(&self, index: usize) -> &T, fn index_mut(&mut self, index: usize) -> &mut T
+    StdVecDequeIter,          // fn iter(&self) -> Iter<'_, T>, fn iter_mut(&mut self) -> IterMut<'_, T>, fn into_iter(self) -> IntoIter<T, A>
+    StdVecDequeQuery,         // fn len(&self) -> usize, fn clear(&mut self), fn truncate(&mut self, len: usize), fn reserve(&mut self, additional: usize), ...
+    StdHashMapNew,            // fn new() -> HashMap<K, V, RandomState>, fn with_capacity(capacity: usize) -> HashMap<K, V, RandomState>, ...
+    StdHashMapInsert,         // fn insert(&mut self, k: K, v: V) -> Option<V>
+    StdHashMapGet,            // fn get<Q>(&self, k: &Q) -> Option<&V>, fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    StdHashMapGetKeyValue,    // fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
+    StdHashMapRemove,         // fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    StdHashMapIndex,          // fn index(&self, key: &Q) -> &V
+    StdHashMapIter,           // fn iter(&self) -> Iter<'_, K, V>, fn keys(&self) -> Keys<'_, K, V>, fn into_iter(self) -> IntoIter<K, V>, ...
+    StdHashMapQuery,          // fn contains_key<Q>(&self, k: &Q) -> bool, fn len(&self) -> usize, fn clear(&mut self), ...
+    StdHashMapIterNext,       // fn next(&mut self) -> Option<Self::Item> of the iterators of `HashMap`
+    StdHashSetNew,            // fn new() -> HashSet<T, RandomState>, fn with_capacity(capacity: usize) -> HashSet<T, RandomState>, ...
+    StdHashSetInsert,         // fn insert(&mut self, value: T) -> bool
+    StdHashSetGet,            // fn get<Q>(&self, value: &Q) -> Option<&T>
+    StdHashSetTake,           // fn take<Q>(&mut self, value: &Q) -> Option<T>
+    StdHashSetIter,           // fn iter(&self) -> Iter<'_, T>, fn into_iter(self) -> IntoIter<T>
+    StdHashSetQuery,          // fn contains<Q>(&self, value: &Q) -> bool, fn remove<Q>(&mut self, value: &Q) -> bool, fn len(&self) -> usize, ...
+    StdHashSetIterNext,       // fn next(&mut self) -> Option<Self::Item> of the iterators of `HashSet`
+    StdBTreeMapNew,           // fn new() -> BTreeMap<K, V>
+    StdBTreeMapInsert,        // fn insert(&mut self, key: K, value: V) -> Option<V>
+    StdBTreeMapGet,           // fn get<Q>(&self, key: &Q) -> Option<&V>, fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    StdBTreeMapGetKeyValue,   // fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>, fn first_key_value(&self) -> Option<(&K, &V)>, ...
+    StdBTreeMapRemove,        // fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    StdBTreeMapIndex,         // fn index(&self, key: &Q) -> &V
+    StdBTreeMapIter,          // fn iter(&self) -> Iter<'_, K, V>, fn keys(&self) -> Keys<'_, K, V>, fn into_iter(self) -> IntoIter<K, V, A>, ...
+    StdBTreeMapQuery,         // fn contains_key<Q>(&self, key: &Q) -> bool, fn len(&self) -> usize, fn clear(&mut self), ...
+    StdBTreeMapIterNext,      // fn next(&mut self) -> Option<Self::Item> of the iterators of `BTreeMap`
+    StdBTreeSetNew,           // fn new() -> BTreeSet<T>
+    StdBTreeSetInsert,        // fn insert(&mut self, value: T) -> bool
+    StdBTreeSetGet,           // fn get<Q>(&self, value: &Q) -> Option<&T>, fn first(&self) -> Option<&T>, fn last(&self) -> Option<&T>
+    StdBTreeSetTake,          // fn take<Q>(&mut self, value: &Q) -> Option<T>, fn pop_first(&mut self) -> Option<T>, fn pop_last(&mut self) -> Option<T>
+    StdBTreeSetIter,          // fn iter(&self) -> Iter<'_, T>, fn into_iter(self) -> IntoIter<T, A>
+    StdBTreeSetQuery,         // fn contains<Q>(&self, value: &Q) -> bool, fn remove<Q>(&mut self, value: &Q) -> bool, fn len(&self) -> usize, ...
+    StdBTreeSetIterNext,      // fn next(&mut self) -> Option<Self::Item> of the iterators of `BTreeSet`
+    StdCollectionFrom,        // fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self, fn from(value: T) -> Self, fn clone(&self) -> Self, fn to_vec(&self) -> Vec<T>
+    StdCollectionExtend,      // fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I), fn append(&mut self, other: &mut Self), fn extend_from_slice(&mut self, other: &[T])
+    StdMapEntry,              // fn entry(&mut self, key: K) -> Entry<'_, K, V> of `HashMap` and `BTreeMap`
+    StdMapEntryKey,           // fn key(&self) -> &K of `OccupiedEntry` and `VacantEntry` (and the hashbrown `RustcOccupiedEntry` and `RustcVacantEntry`)
+    StdMapEntryIntoKey,       // fn into_key(self) -> K of `VacantEntry`
+    StdMapEntryGet,           // fn get(&self) -> &V, fn get_mut(&mut self) -> &mut V, fn into_mut(self) -> &'a mut V of `OccupiedEntry`
+    StdMapEntryInsert,        // fn insert(self, value: V) -> &'a mut V of `VacantEntry`
+    StdMapEntryReplace,       // fn insert(&mut self, value: V) -> V of `OccupiedEntry`
+    StdMapEntryRemove,        // fn remove(self) -> V of `OccupiedEntry`
+    StdMapEntryRemoveEntry,   // fn remove_entry(self) -> (K, V) of `OccupiedEntry`
+
+    // Lazily initialized globals, whose values are stored in the slots of the globals
+    StdOnceCellGet,           // fn get(&self) -> Option<&T>, fn get_mut(&mut self) -> Option<&mut T>
+    StdOnceCellSet,           // fn set(&self, value: T) -> Result<(), T>
+    StdOnceCellGetOrInit,     // fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T
+    StdOnceCellIntoInner,     // fn into_inner(self) -> Option<T>, fn take(&mut self) -> Option<T>
+    StdOnceLockGet,           // fn get(&self) -> Option<&T>, fn get_mut(&mut self) -> Option<&mut T>
+    StdOnceLockSet,           // fn set(&self, value: T) -> Result<(), T>
+    StdOnceLockGetOrInit,     // fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T
+    StdOnceLockIntoInner,     // fn into_inner(self) -> Option<T>, fn take(&mut self) -> Option<T>
+    StdLazyCellNew,           // fn new(f: F) -> LazyCell<T, F>
+    StdLazyCellForce,         // fn force(this: &LazyCell<T, F>) -> &T, fn deref(&self) -> &T
+    StdLazyLockNew,           // fn new(f: F) -> LazyLock<T, F>
+    StdLazyLockForce,         // fn force(this: &LazyLock<T, F>) -> &T, fn deref(&self) -> &T
+    StdThreadLocalKeyGet,     // fn get<F: FnOnce() -> T>(&self, init: F) -> Option<&'static T> of the keys of `thread_local!`
+    StdThreadLocalKeyNew,     // const unsafe fn new(inner: unsafe fn(Option<&mut Option<T>>) -> Option<&'static T>) -> LocalKey<T>
+    LazyStaticLazyGet,        // fn get<F: FnOnce() -> T>(&'static self, f: F) -> &T of the `Lazy` type of `lazy_static!`
+
     StdSliceCmpMemcmp,
     StdSliceIndexIndex, // slice::index::{impl#3-8}::index<T>(_1: std::ops::Range*<usize>, _2: &[T]) -> &[T]
     StdSliceIndexIndexMut, // slice::index::{impl#3-8}::index_mut<T>(_1: std::ops::Range*<usize>, _2: &mut [T]) -> &mut [T]
 
+    // Methods of `AtomicPtr<T> { p: UnsafeCell<*mut T> }`
+    StdSyncAtomicPtrLoad,            // fn load(&self, order: Ordering) -> *mut T
+    StdSyncAtomicPtrStore,           // fn store(&self, ptr: *mut T, order: Ordering)
+    StdSyncAtomicPtrSwap,            // fn swap(&self, ptr: *mut T, order: Ordering) -> *mut T
+    StdSyncAtomicPtrCompareExchange, // fn compare_exchange(&self, current: *mut T, new: *mut T, success: Ordering, failure: Ordering)
+                                     //     -> Result<*mut T, *mut T>
+
     StdThreadBuilderSpawnUnchecked,
     StdThreadBuilderSpawnUnchecked_, // This function starts a new thread by invoking a function through the passed function closure
+    StdThreadJoinHandleJoin,         // fn join(self) -> Result<T>
 
     StdConvertInto,
+
+    // Downcasts of `dyn Any`, `dyn Any + Send` and `dyn Any + Send + Sync` trait objects
+    StdAnyIs,                     // fn is<T: Any>(&self) -> bool
+    StdAnyDowncastRef,            // fn downcast_ref<T: Any>(&self) -> Option<&T>, fn downcast_mut<T: Any>(&mut self) -> Option<&mut T>
+    StdAnyDowncastRefUnchecked,   // fn downcast_ref_unchecked<T: Any>(&self) -> &T, fn downcast_mut_unchecked<T: Any>(&mut self) -> &mut T
+    StdBoxDowncast,               // fn downcast<T: Any>(self) -> Result<Box<T, A>, Self>
+    StdBoxDowncastUnchecked,      // fn downcast_unchecked<T: Any>(self) -> Box<T, A>
 }
 
 /// An analysis lifetime cache that contains a map from def ids to known names.
@@ -247,7 +395,7 @@ impl KnownNamesCache {
             if is_foreign_module(def_path_data) {
                 get_path_data_elem_name(def_path_data_iter.next())
                     .map(|n| match n.as_str() {
-                        "RawVec" => KnownNames::RustAlloc,
+                        "RawVec" | "__rust_alloc" => KnownNames::RustAlloc,
                         "__rust_alloc_zeroed" => KnownNames::RustAllocZeroed,
                         "__rust_dealloc" => KnownNames::RustDealloc,
                         "__rust_realloc" => KnownNames::RustRealloc,
@@ -376,6 +524,12 @@ impl KnownNamesCache {
                         "transmute" => KnownNames::StdIntrinsicsTransmute,
                         "truncf32" => KnownNames::StdIntrinsicsTruncf32,
                         "truncf64" => KnownNames::StdIntrinsicsTruncf64,
+                        // The atomic intrinsics are suffixed with their memory orderings,
+                        // e.g. `atomic_load_seqcst`, `atomic_cxchgweak_acquire_relaxed`.
+                        n if n.starts_with("atomic_cxchg") => KnownNames::StdIntrinsicsAtomicCxchg,
+                        n if n.starts_with("atomic_load") => KnownNames::StdIntrinsicsAtomicLoad,
+                        n if n.starts_with("atomic_store") => KnownNames::StdIntrinsicsAtomicStore,
+                        n if n.starts_with("atomic_xchg") => KnownNames::StdIntrinsicsAtomicXchg,
                         _ => KnownNames::None,
                     })
                     .unwrap_or(KnownNames::None)
@@ -417,6 +571,8 @@ impl KnownNamesCache {
             get_path_data_elem_name(def_path_data_iter.next())
                 .map(|n| match n.as_str() {
                     "replace" => KnownNames::StdMemReplace,
+                    "swap" => KnownNames::StdMemSwap,
+                    "take" => KnownNames::StdMemTake,
                     _ => KnownNames::None,
                 })
                 .unwrap_or(KnownNames::None)
@@ -453,8 +609,12 @@ impl KnownNamesCache {
             get_path_data_elem_name(def_path_data_iter.next())
                 .map(|n| match n.as_str() {
                     "assert_failed" => KnownNames::StdPanickingAssertFailed,
-                    "begin_panic" | "panic" => KnownNames::StdPanickingBeginPanic,
+                    "begin_panic" | "panic" | "panic_display" | "panic_explicit" | "panic_str_2015"
+                    | "panic_nounwind" | "unreachable_display" => KnownNames::StdPanickingBeginPanic,
                     "begin_panic_fmt" | "panic_fmt" => KnownNames::StdPanickingBeginPanicFmt,
+                    "rust_panic_with_hook" => KnownNames::StdPanickingRustPanicWithHook,
+                    "set_hook" => KnownNames::StdPanickingSetHook,
+                    "take_hook" => KnownNames::StdPanickingTakeHook,
                     _ => KnownNames::None,
                 })
                 .unwrap_or(KnownNames::None)
@@ -467,6 +627,10 @@ impl KnownNamesCache {
                 Some(0) => get_path_data_elem_name(def_path_data_iter.next())
                     .map(|n| match n.as_str() {
                         "write_bytes" => KnownNames::StdIntrinsicsWriteBytes,
+                        "read" | "read_unaligned" | "read_volatile" => KnownNames::StdPtrRead,
+                        "write" | "write_unaligned" | "write_volatile" => KnownNames::StdPtrWrite,
+                        "replace" => KnownNames::StdPtrReplace,
+                        "swap" => KnownNames::StdPtrSwap,
                         "cast" => KnownNames::StdPtrMutPtrCast,
                         "add" => KnownNames::StdPtrMutPtrAdd,
                         "sub" => KnownNames::StdPtrMutPtrSub,
@@ -493,6 +657,7 @@ impl KnownNamesCache {
                 Some(0) => get_path_data_elem_name(def_path_data_iter.next())
                     .map(|n| match n.as_str() {
                         "write_bytes" => KnownNames::StdIntrinsicsWriteBytes,
+                        "read" | "read_unaligned" | "read_volatile" => KnownNames::StdPtrRead,
                         "cast" => KnownNames::StdPtrConstPtrCast,
                         "add" => KnownNames::StdPtrConstPtrAdd,
                         "sub" => KnownNames::StdPtrConstPtrSub,
@@ -538,7 +703,11 @@ impl KnownNamesCache {
         let get_known_name_for_ptr_namespace = |mut def_path_data_iter: Iter<'_>| {
             get_path_data_elem_name(def_path_data_iter.next())
                 .map(|n| match n.as_str() {
+                    "read" | "read_unaligned" | "read_volatile" => KnownNames::StdPtrRead,
+                    "replace" => KnownNames::StdPtrReplace,
+                    "swap" => KnownNames::StdPtrSwap,
                     "swap_nonoverlapping" => KnownNames::StdPtrSwapNonOverlapping,
+                    "write" | "write_unaligned" | "write_volatile" => KnownNames::StdPtrWrite,
                     "mut_ptr" => get_known_name_for_ptr_mut_ptr_namespace(def_path_data_iter),
                     "const_ptr" => get_known_name_for_ptr_const_ptr_namespace(def_path_data_iter),
                     "non_null" => get_known_name_for_ptr_non_null_namespace(def_path_data_iter),
@@ -614,30 +783,329 @@ impl KnownNamesCache {
             };
 
         let get_known_name_for_slice_namespace = |mut def_path_data_iter: Iter<'_>| {
+            let mut sub_namespace_iter = def_path_data_iter.clone();
+            get_path_data_elem_name(sub_namespace_iter.next())
+                .map(|n| match n.as_str() {
+                    "cmp" => get_known_name_for_slice_cmp_namespace(sub_namespace_iter),
+                    "index" => get_known_name_for_slice_index_namespace(sub_namespace_iter),
+                    _ => KnownNames::None,
+                })
+                // Methods of `[T]` defined in `alloc::slice::{impl}`
+                .unwrap_or_else(|| {
+                    def_path_data_iter.next();
+                    get_path_data_elem_name(def_path_data_iter.next())
+                        .map(|n| match n.as_str() {
+                            "into_vec" => KnownNames::StdSliceIntoVec,
+                            "to_vec" => KnownNames::StdCollectionFrom,
+                            _ => KnownNames::None,
+                        })
+                        .unwrap_or(KnownNames::None)
+                })
+        };
+
+        // helper to get the name of the self type of the impl block that defines the method
+        let get_impl_self_type_name = || {
+            tcx.impl_of_method(def_id).and_then(|impl_def_id| {
+                match tcx.type_of(impl_def_id).skip_binder().kind() {
+                    TyKind::Adt(def, _) => Some(tcx.item_name(def.did())),
+                    _ => None,
+                }
+            })
+        };
+
+        // The atomic types share the method names, therefore we check the self type of the
+        // impl block to only recognize the methods of `AtomicPtr<T>`.
+        let get_known_name_for_sync_atomic_namespace = |mut def_path_data_iter: Iter<'_>| {
+            if !get_impl_self_type_name().is_some_and(|n| n.as_str() == "AtomicPtr") {
+                return KnownNames::None;
+            }
+            def_path_data_iter.next();
             get_path_data_elem_name(def_path_data_iter.next())
                 .map(|n| match n.as_str() {
-                    "cmp" => get_known_name_for_slice_cmp_namespace(def_path_data_iter),
-                    "index" => get_known_name_for_slice_index_namespace(def_path_data_iter),
+                    "load" => KnownNames::StdSyncAtomicPtrLoad,
+                    "store" => KnownNames::StdSyncAtomicPtrStore,
+                    "swap" => KnownNames::StdSyncAtomicPtrSwap,
+                    "compare_exchange" | "compare_exchange_weak" => {
+                        KnownNames::StdSyncAtomicPtrCompareExchange
+                    }
                     _ => KnownNames::None,
                 })
                 .unwrap_or(KnownNames::None)
         };
 
-        //get_known_name_for_sync_namespace
-        let get_known_name_for_sync_namespace = |mut def_path_data_iter: Iter<'_>| {
+        // Methods of the smart pointer and interior mutability types, which are defined in impl
+        // blocks of `alloc::rc`, `alloc::sync`, `core::cell`, `std::sync::mutex` and `std::sync::rwlock`.
+        let get_known_name_for_smart_pointer_impl = |mut def_path_data_iter: Iter<'_>| {
+            let Some(self_type_name) = get_impl_self_type_name() else {
+                return KnownNames::None;
+            };
+            def_path_data_iter.next();
+            get_path_data_elem_name(def_path_data_iter.next())
+                .map(|n| match (self_type_name.as_str(), n.as_str()) {
+                    ("Rc", "new") => KnownNames::StdRcNew,
+                    ("Rc", "clone") => KnownNames::StdRcClone,
+                    ("Rc", "deref") => KnownNames::StdRcDeref,
+                    ("Rc", "into_inner") => KnownNames::StdRcIntoInner,
+                    ("Arc", "new") => KnownNames::StdArcNew,
+                    ("Arc", "clone") => KnownNames::StdArcClone,
+                    ("Arc", "deref") => KnownNames::StdArcDeref,
+                    ("Arc", "into_inner") => KnownNames::StdArcIntoInner,
+                    ("Cell", "new") => KnownNames::StdCellNew,
+                    ("Cell", "get") => KnownNames::StdCellGet,
+                    ("Cell", "set") => KnownNames::StdCellSet,
+                    ("Cell", "replace") => KnownNames::StdCellReplace,
+                    ("Cell", "into_inner") => KnownNames::StdCellIntoInner,
+                    ("RefCell", "new") => KnownNames::StdRefCellNew,
+                    ("RefCell", "borrow") => KnownNames::StdRefCellBorrow,
+                    ("RefCell", "borrow_mut") => KnownNames::StdRefCellBorrowMut,
+                    ("RefCell", "replace") => KnownNames::StdRefCellReplace,
+                    ("RefCell", "into_inner") => KnownNames::StdRefCellIntoInner,
+                    ("Mutex", "new") => KnownNames::StdMutexNew,
+                    ("Mutex", "lock") => KnownNames::StdMutexLock,
+                    ("Mutex", "into_inner") => KnownNames::StdMutexIntoInner,
+                    ("MutexGuard", "deref" | "deref_mut") => KnownNames::StdMutexGuardDeref,
+                    ("RwLock", "new") => KnownNames::StdRwLockNew,
+                    ("RwLock", "read") => KnownNames::StdRwLockRead,
+                    ("RwLock", "write") => KnownNames::StdRwLockWrite,
+                    ("RwLock", "into_inner") => KnownNames::StdRwLockIntoInner,
+                    ("RwLockWriteGuard", "deref" | "deref_mut") => KnownNames::StdRwLockWriteGuardDeref,
+                    _ => KnownNames::None,
+                })
+                .unwrap_or(KnownNames::None)
+        };
+
+        // Methods of the lazily initialized globals, which are defined in impl blocks of `core::cell::{once, lazy}`,
+        // `std::sync::{once_lock, lazy_lock}`, the thread local implementations in `std::sys` and `lazy_static::lazy`.
+        let get_known_name_for_lazy_global_impl = |mut def_path_data_iter: Iter<'_>| {
+            let Some(self_type_name) = get_impl_self_type_name() else {
+                return KnownNames::None;
+            };
+            def_path_data_iter.next();
+            get_path_data_elem_name(def_path_data_iter.next())
+                .map(|n| match (self_type_name.as_str(), n.as_str()) {
+                    ("OnceCell", "get" | "get_mut") => KnownNames::StdOnceCellGet,
+                    ("OnceCell", "set") => KnownNames::StdOnceCellSet,
+                    ("OnceCell", "get_or_init") => KnownNames::StdOnceCellGetOrInit,
+                    ("OnceCell", "into_inner" | "take") => KnownNames::StdOnceCellIntoInner,
+                    ("OnceLock", "get" | "get_mut") => KnownNames::StdOnceLockGet,
+                    ("OnceLock", "set") => KnownNames::StdOnceLockSet,
+                    ("OnceLock", "get_or_init") => KnownNames::StdOnceLockGetOrInit,
+                    ("OnceLock", "into_inner" | "take") => KnownNames::StdOnceLockIntoInner,
+                    ("LazyCell", "new") => KnownNames::StdLazyCellNew,
+                    ("LazyCell", "force" | "deref") => KnownNames::StdLazyCellForce,
+                    ("LazyLock", "new") => KnownNames::StdLazyLockNew,
+                    ("LazyLock", "force" | "deref") => KnownNames::StdLazyLockForce,
+                    ("Key", "get") => KnownNames::StdThreadLocalKeyGet,
+                    ("LocalKey", "new") => KnownNames::StdThreadLocalKeyNew,
+                    ("Lazy", "get") => KnownNames::LazyStaticLazyGet,
+                    _ => KnownNames::None,
+                })
+                .unwrap_or(KnownNames::None)
+        };
+
+        // The keys of `thread_local!` are defined in `std::sys::pal::common::thread_local::{fast_local, os_local, static_local}`.
+        let get_known_name_for_sys_namespace = |mut def_path_data_iter: Iter<'_>| {
+            while let Some(n) = get_path_data_elem_name(def_path_data_iter.next()) {
+                if n.as_str() == "thread_local" {
+                    def_path_data_iter.next();
+                    return get_known_name_for_lazy_global_impl(def_path_data_iter);
+                }
+            }
+            KnownNames::None
+        };
+
+        // Methods of the std collections, which are defined in impl blocks of `alloc::vec`,
+        // `alloc::collections::{vec_deque, btree::map, btree::set}` and `std::collections::hash::{map, set}`.
+        // `collection` is the name of the collection type that owns the namespace, which is used to
+        // recognize the `next` methods of its iterators.
+        let get_known_name_for_collection_impl = |collection: &str, mut def_path_data_iter: Iter<'_>| {
+            let Some(self_type_name) = get_impl_self_type_name() else {
+                return KnownNames::None;
+            };
+            def_path_data_iter.next();
+            get_path_data_elem_name(def_path_data_iter.next())
+                .map(|n| match (self_type_name.as_str(), n.as_str()) {
+                    ("Vec", "new" | "with_capacity" | "default") => KnownNames::StdVecNew,
+                    ("Vec", "push") => KnownNames::StdVecPush,
+                    ("Vec", "pop") => KnownNames::StdVecPop,
+                    ("Vec", "insert") => KnownNames::StdVecInsert,
+                    ("Vec", "remove" | "swap_remove") => KnownNames::StdVecRemove,
+                    ("Vec", "deref" | "deref_mut" | "as_slice" | "as_mut_slice") => KnownNames::StdVecDeref,
+                    ("Vec", "index" | "index_mut") => KnownNames::StdVecIndex,
+                    ("Vec", "as_ptr" | "as_mut_ptr") => KnownNames::StdVecAsPtr,
+                    ("Vec", "into_iter") => KnownNames::StdVecIntoIter,
+                    (
+                        "Vec",
+                        "len" | "is_empty" | "capacity" | "clear" | "truncate" | "reserve" | "reserve_exact"
+                        | "shrink_to_fit",
+                    ) => KnownNames::StdVecQuery,
+                    ("VecDeque", "new" | "with_capacity" | "default") => KnownNames::StdVecDequeNew,
+                    ("VecDeque", "push_back" | "push_front") => KnownNames::StdVecDequePush,
+                    ("VecDeque", "insert") => KnownNames::StdVecDequeInsert,
+                    ("VecDeque", "pop_back" | "pop_front" | "remove") => KnownNames::StdVecDequePop,
+                    ("VecDeque", "get" | "get_mut" | "front" | "front_mut" | "back" | "back_mut") => {
+                        KnownNames::StdVecDequeGet
+                    }
+                    ("VecDeque", "index" | "index_mut") => KnownNames::StdVecDequeIndex,
+                    ("VecDeque", "iter" | "iter_mut" | "into_iter") => KnownNames::StdVecDequeIter,
+                    (
+                        "VecDeque",
+                        "len" | "is_empty" | "capacity" | "clear" | "truncate" | "reserve" | "reserve_exact"
+                        | "shrink_to_fit",
+                    ) => KnownNames::StdVecDequeQuery,
+                    ("HashMap", "new" | "with_capacity" | "with_hasher" | "with_capacity_and_hasher" | "default") => {
+                        KnownNames::StdHashMapNew
+                    }
+                    ("HashMap", "insert") => KnownNames::StdHashMapInsert,
+                    ("HashMap", "get" | "get_mut") => KnownNames::StdHashMapGet,
+                    ("HashMap", "get_key_value") => KnownNames::StdHashMapGetKeyValue,
+                    ("HashMap", "remove") => KnownNames::StdHashMapRemove,
+                    ("HashMap", "index") => KnownNames::StdHashMapIndex,
+                    (
+                        "HashMap",
+                        "iter" | "iter_mut" | "keys" | "values" | "values_mut" | "into_iter" | "into_keys"
+                        | "into_values",
+                    ) => KnownNames::StdHashMapIter,
+                    ("HashMap", "contains_key" | "len" | "is_empty" | "capacity" | "clear" | "reserve" | "shrink_to_fit") => {
+                        KnownNames::StdHashMapQuery
+                    }
+                    ("HashSet", "new" | "with_capacity" | "with_hasher" | "with_capacity_and_hasher" | "default") => {
+                        KnownNames::StdHashSetNew
+                    }
+                    ("HashSet", "insert") => KnownNames::StdHashSetInsert,
+                    ("HashSet", "get") => KnownNames::StdHashSetGet,
+                    ("HashSet", "take") => KnownNames::StdHashSetTake,
+                    ("HashSet", "iter" | "into_iter") => KnownNames::StdHashSetIter,
+                    (
+                        "HashSet",
+                        "contains" | "remove" | "len" | "is_empty" | "capacity" | "clear" | "reserve" | "shrink_to_fit",
+                    ) => KnownNames::StdHashSetQuery,
+                    ("BTreeMap", "new" | "default") => KnownNames::StdBTreeMapNew,
+                    ("BTreeMap", "insert") => KnownNames::StdBTreeMapInsert,
+                    ("BTreeMap", "get" | "get_mut") => KnownNames::StdBTreeMapGet,
+                    ("BTreeMap", "get_key_value" | "first_key_value" | "last_key_value") => {
+                        KnownNames::StdBTreeMapGetKeyValue
+                    }
+                    ("BTreeMap", "remove") => KnownNames::StdBTreeMapRemove,
+                    ("BTreeMap", "index") => KnownNames::StdBTreeMapIndex,
+                    (
+                        "BTreeMap",
+                        "iter" | "iter_mut" | "keys" | "values" | "values_mut" | "into_iter" | "into_keys"
+                        | "into_values",
+                    ) => KnownNames::StdBTreeMapIter,
+                    ("BTreeMap", "contains_key" | "len" | "is_empty" | "clear") => KnownNames::StdBTreeMapQuery,
+                    ("BTreeSet", "new" | "default") => KnownNames::StdBTreeSetNew,
+                    ("BTreeSet", "insert") => KnownNames::StdBTreeSetInsert,
+                    ("BTreeSet", "get" | "first" | "last") => KnownNames::StdBTreeSetGet,
+                    ("BTreeSet", "take" | "pop_first" | "pop_last") => KnownNames::StdBTreeSetTake,
+                    ("BTreeSet", "iter" | "into_iter") => KnownNames::StdBTreeSetIter,
+                    ("BTreeSet", "contains" | "remove" | "len" | "is_empty" | "clear") => KnownNames::StdBTreeSetQuery,
+                    (
+                        "Vec" | "VecDeque" | "HashMap" | "HashSet" | "BTreeMap" | "BTreeSet",
+                        "from_iter" | "from" | "clone",
+                    ) => KnownNames::StdCollectionFrom,
+                    (
+                        "Vec" | "VecDeque" | "HashMap" | "HashSet" | "BTreeMap" | "BTreeSet",
+                        "extend" | "append" | "extend_from_slice",
+                    ) => KnownNames::StdCollectionExtend,
+                    ("HashMap" | "BTreeMap", "entry") => KnownNames::StdMapEntry,
+                    ("OccupiedEntry" | "VacantEntry" | "RustcOccupiedEntry" | "RustcVacantEntry", "key") => {
+                        KnownNames::StdMapEntryKey
+                    }
+                    ("VacantEntry" | "RustcVacantEntry", "into_key") => KnownNames::StdMapEntryIntoKey,
+                    ("OccupiedEntry" | "RustcOccupiedEntry", "get" | "get_mut" | "into_mut") => {
+                        KnownNames::StdMapEntryGet
+                    }
+                    ("VacantEntry" | "RustcVacantEntry", "insert") => KnownNames::StdMapEntryInsert,
+                    ("OccupiedEntry" | "RustcOccupiedEntry", "insert") => KnownNames::StdMapEntryReplace,
+                    ("OccupiedEntry" | "RustcOccupiedEntry", "remove") => KnownNames::StdMapEntryRemove,
+                    ("OccupiedEntry" | "RustcOccupiedEntry", "remove_entry") => KnownNames::StdMapEntryRemoveEntry,
+                    (
+                        "Iter" | "IterMut" | "IntoIter" | "Keys" | "Values" | "ValuesMut" | "IntoKeys" | "IntoValues",
+                        "next",
+                    ) => match collection {
+                        "HashMap" => KnownNames::StdHashMapIterNext,
+                        "HashSet" => KnownNames::StdHashSetIterNext,
+                        "BTreeMap" => KnownNames::StdBTreeMapIterNext,
+                        "BTreeSet" => KnownNames::StdBTreeSetIterNext,
+                        _ => KnownNames::None,
+                    },
+                    _ => KnownNames::None,
+                })
+                .unwrap_or(KnownNames::None)
+        };
+
+        let get_known_name_for_collections_namespace = |mut def_path_data_iter: Iter<'_>| {
             get_path_data_elem_name(def_path_data_iter.next())
                 .map(|n| match n.as_str() {
-                    "once" => get_known_name_for_sync_once_namespace(def_path_data_iter),
+                    "vec_deque" => get_known_name_for_collection_impl("VecDeque", def_path_data_iter),
+                    "btree" | "hash" => {
+                        let map_or_set = get_path_data_elem_name(def_path_data_iter.next());
+                        match (n.as_str(), map_or_set.as_ref().map(|n| n.as_str())) {
+                            ("btree", Some("map")) => {
+                                // The entries of `BTreeMap` are defined in `alloc::collections::btree::map::entry`
+                                let mut entry_iter = def_path_data_iter.clone();
+                                if get_path_data_elem_name(entry_iter.next()).is_some_and(|n| n.as_str() == "entry") {
+                                    def_path_data_iter = entry_iter;
+                                }
+                                get_known_name_for_collection_impl("BTreeMap", def_path_data_iter)
+                            }
+                            ("btree", Some("set")) => get_known_name_for_collection_impl("BTreeSet", def_path_data_iter),
+                            ("hash", Some("map")) => get_known_name_for_collection_impl("HashMap", def_path_data_iter),
+                            ("hash", Some("set")) => get_known_name_for_collection_impl("HashSet", def_path_data_iter),
+                            _ => KnownNames::None,
+                        }
+                    }
                     _ => KnownNames::None,
                 })
                 .unwrap_or(KnownNames::None)
         };
 
+        // The entries of `HashMap` wrap the entries defined in `hashbrown::rustc_entry`, whose methods
+        // are called directly by the inlined methods of the std entries.
+        let get_known_name_for_hashbrown_crate = |mut def_path_data_iter: Iter<'_>| {
+            match get_path_data_elem_name(def_path_data_iter.next()) {
+                Some(n) if n.as_str() == "rustc_entry" => {
+                    get_known_name_for_collection_impl("HashMap", def_path_data_iter)
+                }
+                _ => KnownNames::None,
+            }
+        };
+
+        let get_known_name_for_cell_namespace = |def_path_data_iter: Iter<'_>| {
+            let mut sub_namespace_iter = def_path_data_iter.clone();
+            get_path_data_elem_name(sub_namespace_iter.next())
+                .map(|n| match n.as_str() {
+                    "once" | "lazy" => get_known_name_for_lazy_global_impl(sub_namespace_iter),
+                    _ => KnownNames::None,
+                })
+                // `Cell` and `RefCell` are defined in `core::cell` directly
+                .unwrap_or_else(|| get_known_name_for_smart_pointer_impl(def_path_data_iter))
+        };
+
         //get_known_name_for_sync_namespace
-        let get_known_name_for_thread_namespace =
-            |mut def_path_data_iter: Iter<'_>| match path_data_elem_as_disambiguator(
-                def_path_data_iter.next(),
-            ) {
+        let get_known_name_for_sync_namespace = |def_path_data_iter: Iter<'_>| {
+            let mut sub_namespace_iter = def_path_data_iter.clone();
+            get_path_data_elem_name(sub_namespace_iter.next())
+                .map(|n| match n.as_str() {
+                    "atomic" => get_known_name_for_sync_atomic_namespace(sub_namespace_iter),
+                    "mutex" | "rwlock" => get_known_name_for_smart_pointer_impl(sub_namespace_iter),
+                    "once_lock" | "lazy_lock" => get_known_name_for_lazy_global_impl(sub_namespace_iter),
+                    "once" => get_known_name_for_sync_once_namespace(sub_namespace_iter),
+                    _ => KnownNames::None,
+                })
+                // `Arc` is defined in `alloc::sync` directly
+                .unwrap_or_else(|| get_known_name_for_smart_pointer_impl(def_path_data_iter))
+        };
+
+        //get_known_name_for_sync_namespace
+        let get_known_name_for_thread_namespace = |mut def_path_data_iter: Iter<'_>| {
+            let def_path_data = def_path_data_iter.next();
+            // `LocalKey` is defined in `std::thread::local`
+            if get_path_data_elem_name(def_path_data).is_some_and(|n| n.as_str() == "local") {
+                return get_known_name_for_lazy_global_impl(def_path_data_iter);
+            }
+            match path_data_elem_as_disambiguator(def_path_data) {
                 Some(0) => get_path_data_elem_name(def_path_data_iter.next())
                     .map(|n| match n.as_str() {
                         "spawn_unchecked" => KnownNames::StdThreadBuilderSpawnUnchecked,
@@ -645,8 +1113,16 @@ impl KnownNamesCache {
                         _ => KnownNames::None,
                     })
                     .unwrap_or(KnownNames::None),
+                Some(_) => get_impl_self_type_name()
+                    .zip(get_path_data_elem_name(def_path_data_iter.next()))
+                    .map(|(self_type_name, n)| match (self_type_name.as_str(), n.as_str()) {
+                        ("JoinHandle", "join") => KnownNames::StdThreadJoinHandleJoin,
+                        _ => KnownNames::None,
+                    })
+                    .unwrap_or(KnownNames::None),
                 _ => KnownNames::None,
-            };
+            }
+        };
 
         // get_known_name_for_result_namespace
         let get_known_name_for_result_namespace =
@@ -673,11 +1149,37 @@ impl KnownNamesCache {
                     .unwrap_or(KnownNames::None),
             };
 
+        // The inherent methods of `dyn Any` trait objects and of `Box<dyn Any>`.
+        let get_known_name_for_any_downcast = |mut def_path_data_iter: Iter<'_>| {
+            let Some(impl_def_id) = tcx.impl_of_method(def_id) else {
+                return KnownNames::None;
+            };
+            let self_ty = tcx.type_of(impl_def_id).skip_binder();
+            def_path_data_iter.next();
+            get_path_data_elem_name(def_path_data_iter.next())
+                .map(|n| match (self_ty.kind(), n.as_str()) {
+                    (TyKind::Dynamic(..), "is") => KnownNames::StdAnyIs,
+                    (TyKind::Dynamic(..), "downcast_ref" | "downcast_mut") => KnownNames::StdAnyDowncastRef,
+                    (TyKind::Dynamic(..), "downcast_ref_unchecked" | "downcast_mut_unchecked") => {
+                        KnownNames::StdAnyDowncastRefUnchecked
+                    }
+                    (TyKind::Adt(..), "downcast") if self_ty.is_box() => KnownNames::StdBoxDowncast,
+                    (TyKind::Adt(..), "downcast_unchecked") if self_ty.is_box() => {
+                        KnownNames::StdBoxDowncastUnchecked
+                    }
+                    _ => KnownNames::None,
+                })
+                .unwrap_or(KnownNames::None)
+        };
+
         let get_known_name_for_known_crate = |mut def_path_data_iter: Iter<'_>| {
             get_path_data_elem_name(def_path_data_iter.next())
                 .map(|n| match n.as_str() {
                     "alloc" => get_known_name_for_alloc_namespace(def_path_data_iter),
+                    "any" | "boxed" => get_known_name_for_any_downcast(def_path_data_iter),
+                    "cell" => get_known_name_for_cell_namespace(def_path_data_iter),
                     "clone" => get_known_name_for_clone_namespace(def_path_data_iter),
+                    "collections" => get_known_name_for_collections_namespace(def_path_data_iter),
                     "future" => get_known_name_for_future_namespace(def_path_data_iter),
                     "intrinsics" => get_known_name_for_intrinsics_namespace(def_path_data_iter),
                     "marker" => get_known_name_for_marker_namespace(def_path_data_iter),
@@ -686,20 +1188,52 @@ impl KnownNamesCache {
                     "panicking" => get_known_name_for_panicking_namespace(def_path_data_iter),
                     "ptr" => get_known_name_for_ptr_namespace(def_path_data_iter),
                     "raw_vec" => get_known_name_for_raw_vec_namespace(def_path_data_iter),
+                    "rc" => get_known_name_for_smart_pointer_impl(def_path_data_iter),
                     "result" => get_known_name_for_result_namespace(def_path_data_iter),
                     "rt" => get_known_name_for_panicking_namespace(def_path_data_iter),
                     "slice" => get_known_name_for_slice_namespace(def_path_data_iter),
                     "sync" => get_known_name_for_sync_namespace(def_path_data_iter),
+                    "sys" => get_known_name_for_sys_namespace(def_path_data_iter),
                     "thread" => get_known_name_for_thread_namespace(def_path_data_iter),
+                    "vec" => get_known_name_for_collection_impl("Vec", def_path_data_iter),
                     "convert" => get_known_name_for_convert_namespace(def_path_data_iter),
                     _ => KnownNames::None,
                 })
                 .unwrap_or(KnownNames::None)
         };
 
+        let get_known_name_for_lazy_static_crate = |mut def_path_data_iter: Iter<'_>| {
+            get_path_data_elem_name(def_path_data_iter.next())
+                .map(|n| match n.as_str() {
+                    "lazy" => get_known_name_for_lazy_global_impl(def_path_data_iter),
+                    _ => KnownNames::None,
+                })
+                .unwrap_or(KnownNames::None)
+        };
+
+        // The functions of the C library are recognized by their symbol names.
+        if tcx.is_foreign_item(def_id) {
+            let known_name = match tcx.item_name(def_id).as_str() {
+                "malloc" => KnownNames::LibcMalloc,
+                "calloc" => KnownNames::LibcCalloc,
+                "realloc" => KnownNames::LibcRealloc,
+                "free" => KnownNames::LibcFree,
+                "memcpy" => KnownNames::LibcMemcpy,
+                "memmove" => KnownNames::LibcMemmove,
+                "strdup" => KnownNames::LibcStrdup,
+                "mmap" => KnownNames::LibcMmap,
+                _ => KnownNames::None,
+            };
+            if known_name != KnownNames::None {
+                return known_name;
+            }
+        }
+
         let crate_name = tcx.crate_name(def_id.krate);
         match crate_name.as_str() {
             "alloc" | "core" | "std" => get_known_name_for_known_crate(def_path_data_iter),
+            "lazy_static" => get_known_name_for_lazy_static_crate(def_path_data_iter),
+            "hashbrown" => get_known_name_for_hashbrown_crate(def_path_data_iter),
             _ => KnownNames::None,
         }
     }
@@ -1,5 +1,6 @@
 pub mod call_site;
 pub mod context;
+pub mod entry_points;
 pub mod function;
 pub mod analysis_context;
 pub mod known_names;
@@ -123,6 +123,12 @@ pub enum PathEnum {
 
     /// A type instance uniquely identified by the type's index in type cache
     Type(usize),
+
+    /// An abstract object of the type identified by the type's index in type cache, which is
+    /// pointed to by the arguments of the entry functions, e.g. the public functions of a library.
+    AbstractObject {
+        type_index: usize,
+    },
 }
 
 impl Debug for PathEnum {
@@ -166,6 +172,9 @@ impl Debug for PathEnum {
             PathEnum::PromotedArgumentV1Array => f.write_fmt(format_args!("ArgumentV1Arr")),
             PathEnum::PromotedStrRefArray => f.write_fmt(format_args!("StrRefArr")),
             PathEnum::Type(type_id) => f.write_fmt(format_args!("Ty({:?})", type_id)),
+            PathEnum::AbstractObject { type_index } => {
+                f.write_fmt(format_args!("abstract_obj::Ty({:?})", type_index))
+            }
         }
     }
 }
@@ -193,6 +202,11 @@ pub enum PathSelector {
     /// Index into a slice/array
     Index,
 
+    /// Index into an array with a constant index. Only used for the arrays whose elements are
+    /// distinguished with `--index-sensitive-arrays`. The element selected by `Index` summarizes
+    /// all the constant-index elements of such an array.
+    ConstantIndex(usize),
+
     // These indices are generated by slice patterns. Easiest to explain
     // by example:
     //
@@ -238,6 +252,7 @@ impl Debug for PathSelector {
             PathSelector::UnionField(index) => f.write_fmt(format_args!("union_field#{:?}", index)),
             // PathSelector::UnionOffset(offset) => f.write_fmt(format_args!("union_offset#{:?}", offset)),
             PathSelector::Index => f.write_str("index"),
+            PathSelector::ConstantIndex(index) => f.write_fmt(format_args!("index#{}", index)),
             // PathSelector::ConstantIndex {
             //     offset,
             //     min_length,
@@ -387,6 +402,13 @@ impl Path {
         })
     }
 
+    /// Creates a path to the abstract object of the type with the given index.
+    pub fn new_abstract_object(type_index: usize) -> Rc<Path> {
+        Rc::new(Path {
+            value: PathEnum::AbstractObject { type_index },
+        })
+    }
+
     /// Creates a path to the target memory of a reference value.
     pub fn new_deref(address_path: Rc<Path>) -> Rc<Path> {
         assert!(!matches!(address_path.value, PathEnum::QualifiedPath { .. }));
@@ -588,6 +610,10 @@ impl PAGPath for Rc<Path> {
         acx.get_regularized_path(self.clone())
     }
 
+    fn index_variants(&self, acx: &mut AnalysisContext) -> Vec<Self> {
+        acx.get_index_variants(self)
+    }
+
     fn try_eval_path_type<'tcx>(&self, acx: &mut AnalysisContext<'tcx, '_>) -> Ty<'tcx> {
         if let Some(ty) = acx.get_path_rustc_type(self) {
             ty
@@ -620,7 +646,7 @@ impl PAGPath for Rc<Path> {
     fn flatten_fields<'tcx>(self, acx: &mut AnalysisContext<'tcx, '_>) -> Vec<(usize, Self, Ty<'tcx>)> {
         let param_env = rustc_middle::ty::ParamEnv::reveal_all();
         let path_ty = self.try_eval_path_type(acx);
-        type_util::flatten_fields(acx.tcx, param_env, self, path_ty)
+        type_util::flatten_fields(acx.tcx, param_env, self, path_ty, acx.analysis_options.variant_sensitive_enums)
     }
 
     fn get_containing_func(&self) -> Option<FuncId> {
@@ -638,10 +664,22 @@ impl PAGPath for Rc<Path> {
             | PathEnum::Function(..) 
             | PathEnum::PromotedArgumentV1Array 
             | PathEnum::PromotedStrRefArray 
-            | PathEnum::Type(..) => None,
+            | PathEnum::Type(..)
+            | PathEnum::AbstractObject { .. } => None,
+        }
+    }
+
+    fn base_path(&self) -> Rc<Path> {
+        match &self.value {
+            PathEnum::QualifiedPath { base, .. } | PathEnum::OffsetPath { base, .. } => base.base_path(),
+            _ => self.clone(),
         }
     }
 
+    fn ci_path(&self) -> Rc<Path> {
+        self.clone()
+    }
+
 }
 
 impl PAGPath for Rc<CSPath> {
@@ -727,6 +765,13 @@ impl PAGPath for Rc<CSPath> {
         )
     }
 
+    fn index_variants(&self, acx: &mut AnalysisContext) -> Vec<Self> {
+        acx.get_index_variants(&self.path)
+            .into_iter()
+            .map(|path| CSPath::new_cs_path(self.cid, path))
+            .collect()
+    }
+
     fn try_eval_path_type<'tcx>(&self, acx: &mut AnalysisContext<'tcx, '_>) -> Ty<'tcx> {
         self.path.try_eval_path_type(acx)
     }
@@ -758,4 +803,12 @@ impl PAGPath for Rc<CSPath> {
             None
         }
     }
+
+    fn base_path(&self) -> Rc<CSPath> {
+        CSPath::new_cs_path(self.cid, self.path.base_path())
+    }
+
+    fn ci_path(&self) -> Rc<Path> {
+        self.path.clone()
+    }
 }
@@ -8,15 +8,16 @@ use std::fmt::{Debug, Formatter, Result};
 use std::rc::Rc;
 use std::time::Duration;
 
-use rustc_middle::ty::TyCtxt;
 
 use super::propagator::propagator::Propagator;
 use super::PointerAnalysis;
 use super::strategies::stack_filtering::StackFilter;
+use super::flow_sensitive::FlowSensitivePointsTo;
 use crate::graph::call_graph::CallGraph;
 use crate::graph::func_pag::FuncPAG;
 use crate::mir::call_site::{CallSite, BaseCallSite, CallType, AssocCallGroup};
 use crate::mir::function::FuncId;
+use crate::api::AnalysisResult;
 use crate::mir::analysis_context::AnalysisContext;
 use crate::mir::path::Path;
 use crate::pta::*;
@@ -52,6 +53,9 @@ pub struct AndersenPTA<'pta, 'tcx, 'compilation> {
 
     pub stack_filter: Option<StackFilter<FuncId>>,
     pub pre_analysis_time: Duration,
+
+    /// The flow-sensitive points-to results of the functions selected with `--flow-sensitive-funcs`.
+    pub fs_pts: Vec<FlowSensitivePointsTo>,
 }
 
 impl<'pta, 'compilation, 'tcx> Debug for AndersenPTA<'pta, 'compilation, 'tcx> {
@@ -79,14 +83,10 @@ impl<'pta, 'tcx, 'compilation> AndersenPTA<'pta, 'tcx, 'compilation> {
             assoc_calls: AssocCallGroup::new(),
             stack_filter: None,
             pre_analysis_time: Duration::ZERO,
+            fs_pts: Vec::new(),
         }
     }
 
-    #[inline]
-    fn tcx(&self) -> TyCtxt<'tcx> {
-        self.acx.tcx
-    }
-
     /// Process statements in reachable functions.
     fn process_reach_funcs(&mut self) {
         while let Some(func_id) = self.rf_iter.next() {
@@ -223,6 +223,7 @@ impl<'pta, 'tcx, 'compilation> PointerAnalysis<'tcx, 'compilation> for AndersenP
         rta.analyze();
         self.pre_analysis_time += rta.analysis_time;
         self.stack_filter = Some(StackFilter::new(rta.call_graph));
+        self.stack_filter.as_mut().unwrap().collect_filtered_pts = self.acx.analysis_options.dump_stats;
         self.pre_analysis_time += self.stack_filter.as_ref().unwrap().fra_time();
         println!("Pre-analysis time {}", 
             humantime::format_duration(self.pre_analysis_time).to_string()
@@ -231,10 +232,10 @@ impl<'pta, 'tcx, 'compilation> PointerAnalysis<'tcx, 'compilation> for AndersenP
 
     /// Initialize the analysis.
     fn initialize(&mut self) {
-        // add the entry point to the call graph
-        let entry_point = self.acx.entry_point;
-        let entry_func_id = self.acx.get_func_id(entry_point, self.tcx().mk_args(&[]));
-        self.call_graph.add_node(entry_func_id);
+        // add the entry points to the call graph
+        for entry_func_id in self.acx.entry_points.clone() {
+            self.call_graph.add_node(entry_func_id);
+        }
 
         // process statements of reachable functions
         self.process_reach_funcs();
@@ -270,13 +271,35 @@ impl<'pta, 'tcx, 'compilation> PointerAnalysis<'tcx, 'compilation> for AndersenP
     }
 
     /// Finalize the analysis.
-    fn finalize(&self) {
+    fn finalize(&mut self) {
         // dump call graph, points-to results
         results_dumper::dump_results(self.acx, &self.call_graph, &self.pt_data, &self.pag);
 
+        // refine the points-to results of the selected functions flow-sensitively
+        self.fs_pts = flow_sensitive::analyze_selected_functions(self.acx, self.call_graph.reach_funcs_iter(), &self.pt_data, &self.pag);
+
+        // compute the mod/ref summaries of the reachable functions
+        mod_ref::compute_mod_ref(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // classify the stack and heap objects by where they escape to
+        escape_analysis::analyze_escapes(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // report the unprotected conflicting accesses of the objects shared between threads
+        data_race::check_races(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // report the accesses and the frees of the heap objects that may have been freed before
+        free_errors::detect_free_errors(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // report the cycles in the lock acquisition order and the double locking
+        deadlock::check_deadlocks(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
         // dump pta statistics
         let pta_stat = AndersenStat::new(self);
         pta_stat.dump_stats();
     }
 
+    fn analysis_result(&self) -> AnalysisResult {
+        AnalysisResult::new(self.acx, &self.call_graph, &self.pt_data, &self.pag, &self.fs_pts)
+    }
+
 }
@@ -15,6 +15,7 @@ use rustc_middle::ty::TyCtxt;
 use super::*;
 use super::strategies::context_strategy::{ContextStrategy, KObjectSensitive};
 use super::strategies::stack_filtering::StackFilter;
+use super::flow_sensitive::FlowSensitivePointsTo;
 use super::propagator::propagator::Propagator;
 use super::PointerAnalysis;
 use crate::graph::func_pag::FuncPAG;
@@ -22,6 +23,7 @@ use crate::graph::call_graph::CSCallGraph;
 use crate::mir::call_site::{AssocCallGroup, CSCallSite, CallSite, CallType};
 use crate::mir::context::{Context, ContextId};
 use crate::mir::function::{FuncId, CSFuncId};
+use crate::api::AnalysisResult;
 use crate::mir::analysis_context::AnalysisContext;
 use crate::mir::path::{Path, CSPath, PathEnum};
 use crate::rta::rta::RapidTypeAnalysis;
@@ -61,6 +63,9 @@ pub struct ContextSensitivePTA<'pta, 'tcx, 'compilation, S: ContextStrategy> {
 
     pub stack_filter: Option<StackFilter<CSFuncId>>,
     pub pre_analysis_time: Duration,
+
+    /// The flow-sensitive points-to results of the functions selected with `--flow-sensitive-funcs`.
+    pub fs_pts: Vec<FlowSensitivePointsTo>,
 }
 
 impl<'pta, 'tcx, 'compilation, S: ContextStrategy> Debug for ContextSensitivePTA<'pta, 'tcx, 'compilation, S> {
@@ -89,6 +94,7 @@ impl<'pta, 'tcx, 'compilation, S: ContextStrategy> ContextSensitivePTA<'pta, 'tc
             ctx_strategy,
             stack_filter: None,
             pre_analysis_time: Duration::ZERO,
+            fs_pts: Vec::new(),
         }
     }
 
@@ -305,7 +311,8 @@ impl<'pta, 'tcx, 'compilation, S: ContextStrategy> ContextSensitivePTA<'pta, 'tc
             | PathEnum::Function(..) 
             | PathEnum::PromotedStrRefArray
             | PathEnum::PromotedArgumentV1Array
-            | PathEnum::Type(..) => {
+            | PathEnum::Type(..)
+            | PathEnum::AbstractObject { .. } => {
                 // Context insensitive for these kinds of path
                 let empty_cid = self.get_empty_context_id();
                 CSPath::new_cs_path(empty_cid, path.clone())
@@ -355,6 +362,7 @@ impl<'pta, 'tcx, 'compilation, S: ContextStrategy> PointerAnalysis<'tcx, 'compil
         rta.analyze();
         self.pre_analysis_time += rta.analysis_time;
         self.stack_filter = Some(StackFilter::new(rta.call_graph));
+        self.stack_filter.as_mut().unwrap().collect_filtered_pts = self.acx.analysis_options.dump_stats;
         self.ctx_strategy.with_stack_filter(self.stack_filter.as_mut().unwrap());
         self.pre_analysis_time += self.stack_filter.as_ref().unwrap().fra_time();
         println!("Pre-analysis time {}", 
@@ -364,11 +372,11 @@ impl<'pta, 'tcx, 'compilation, S: ContextStrategy> PointerAnalysis<'tcx, 'compil
 
     /// Initialize the analysis.
     fn initialize(&mut self) {
-        // add the entry point to the call graph
-        let entry_point = self.acx.entry_point;
+        // add the entry points to the call graph
         let empty_context_id = self.get_empty_context_id();
-        let entry_func_id = self.acx.get_func_id(entry_point, self.tcx().mk_args(&[]));
-        self.call_graph.add_node(CSFuncId::new(empty_context_id, entry_func_id));
+        for entry_func_id in self.acx.entry_points.clone() {
+            self.call_graph.add_node(CSFuncId::new(empty_context_id, entry_func_id));
+        }
 
         // process statements of reachable functions
         self.process_reach_funcs();
@@ -404,12 +412,34 @@ impl<'pta, 'tcx, 'compilation, S: ContextStrategy> PointerAnalysis<'tcx, 'compil
     }
 
     /// Finalize the analysis.
-    fn finalize(&self) {
+    fn finalize(&mut self) {
         // dump call graph, points-to results
         results_dumper::dump_results(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // refine the points-to results of the selected functions flow-sensitively
+        self.fs_pts = flow_sensitive::analyze_selected_functions(self.acx, self.call_graph.reach_funcs_iter().map(|cs_func| cs_func.func_id), &self.pt_data, &self.pag);
         
+        // compute the mod/ref summaries of the reachable functions
+        mod_ref::compute_mod_ref(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // classify the stack and heap objects by where they escape to
+        escape_analysis::analyze_escapes(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // report the unprotected conflicting accesses of the objects shared between threads
+        data_race::check_races(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // report the accesses and the frees of the heap objects that may have been freed before
+        free_errors::detect_free_errors(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
+        // report the cycles in the lock acquisition order and the double locking
+        deadlock::check_deadlocks(self.acx, &self.call_graph, &self.pt_data, &self.pag);
+
         // dump pta statistics
         let pta_stat = ContextSensitiveStat::new(self);
         pta_stat.dump_stats();
     }
+
+    fn analysis_result(&self) -> AnalysisResult {
+        AnalysisResult::new(self.acx, &self.call_graph, &self.pt_data, &self.pag, &self.fs_pts)
+    }
 }
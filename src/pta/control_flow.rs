@@ -0,0 +1,397 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! An interprocedural control flow graph of the reachable functions, given by the intraprocedural
+//! CFGs of their MIR bodies connected by the callsites of the call graph. It is used by the clients
+//! that need to know which code may be executed after a callsite, e.g. the data race detector and
+//! the use-after-free detector.
+
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::visit::{NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir;
+
+use crate::graph::call_graph::{CGCallSite, CGFunction, CallGraph};
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::call_site::BaseCallSite;
+use crate::mir::function::FuncId;
+use crate::mir::known_names::{KnownNames, KnownNamesCache};
+
+/// The code of a program given by whole functions and by basic blocks of other functions.
+#[derive(Default)]
+pub(crate) struct Region {
+    pub(crate) funcs: HashSet<FuncId>,
+    pub(crate) blocks: HashSet<(FuncId, mir::BasicBlock)>,
+}
+
+impl Region {
+    pub(crate) fn contains(&self, func: FuncId, block: mir::BasicBlock) -> bool {
+        self.funcs.contains(&func) || self.blocks.contains(&(func, block))
+    }
+
+    pub(crate) fn extend(&mut self, other: &Region) -> bool {
+        let (num_funcs, num_blocks) = (self.funcs.len(), self.blocks.len());
+        self.funcs.extend(other.funcs.iter().copied());
+        self.blocks.extend(other.blocks.iter().copied());
+        num_funcs != self.funcs.len() || num_blocks != self.blocks.len()
+    }
+}
+
+/// The locals definitely moved out and the boolean locals, e.g. the drop flags, definitely
+/// assigned `false` at a program point. A local dropped before is also moved out.
+#[derive(Clone, Default)]
+pub(crate) struct MoveState {
+    moved: HashSet<mir::Local>,
+    false_locals: HashSet<mir::Local>,
+}
+
+impl MoveState {
+    pub(crate) fn is_moved(&self, local: mir::Local) -> bool {
+        self.moved.contains(&local)
+    }
+
+    /// Intersects the state with the other one, and returns true if the state is changed.
+    fn meet(&mut self, other: &MoveState) -> bool {
+        let len = (self.moved.len(), self.false_locals.len());
+        self.moved.retain(|local| other.moved.contains(local));
+        self.false_locals.retain(|local| other.false_locals.contains(local));
+        len != (self.moved.len(), self.false_locals.len())
+    }
+
+    fn transfer_statement(&mut self, statement: &mir::Statement) {
+        match &statement.kind {
+            mir::StatementKind::Assign(box (place, rvalue)) => {
+                self.moved.extend(moved_locals(|collector| collector.visit_rvalue(rvalue, mir::Location::START)));
+                if place.is_indirect() {
+                    return;
+                }
+                self.moved.remove(&place.local);
+                match rvalue {
+                    mir::Rvalue::Use(mir::Operand::Constant(constant))
+                        if place.projection.is_empty() && constant.const_.try_to_bool() == Some(false) =>
+                    {
+                        self.false_locals.insert(place.local);
+                    }
+                    _ => {
+                        self.false_locals.remove(&place.local);
+                    }
+                }
+            }
+            mir::StatementKind::StorageLive(local) | mir::StatementKind::StorageDead(local) => {
+                self.moved.remove(local);
+                self.false_locals.remove(local);
+            }
+            mir::StatementKind::SetDiscriminant { place, .. } | mir::StatementKind::Deinit(place) => {
+                if !place.is_indirect() {
+                    self.moved.remove(&place.local);
+                    self.false_locals.remove(&place.local);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn transfer_terminator(&mut self, terminator: &mir::Terminator) {
+        self.moved.extend(moved_locals(|collector| collector.visit_terminator(terminator, mir::Location::START)));
+        match &terminator.kind {
+            mir::TerminatorKind::Drop { place, .. } if place.projection.is_empty() => {
+                self.moved.insert(place.local);
+            }
+            mir::TerminatorKind::Call { destination, .. } if !destination.is_indirect() => {
+                self.moved.remove(&destination.local);
+                self.false_locals.remove(&destination.local);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the successors of the terminator that may be executed in this state, i.e. a switch
+    /// on a local assigned `false` only branches to the target of `0`.
+    fn successors(&self, terminator: &mir::Terminator) -> Vec<mir::BasicBlock> {
+        if let mir::TerminatorKind::SwitchInt {
+            discr: mir::Operand::Copy(place) | mir::Operand::Move(place),
+            targets,
+        } = &terminator.kind
+        {
+            if place.projection.is_empty() && self.false_locals.contains(&place.local) {
+                return vec![targets.target_for_value(0)];
+            }
+        }
+        terminator.successors().collect()
+    }
+}
+
+/// Returns the locals moved as a whole by the MIR visited by `visit`.
+fn moved_locals(visit: impl FnOnce(&mut MovedLocalCollector)) -> Vec<mir::Local> {
+    let mut collector = MovedLocalCollector::default();
+    visit(&mut collector);
+    collector.locals
+}
+
+#[derive(Default)]
+struct MovedLocalCollector {
+    locals: Vec<mir::Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for MovedLocalCollector {
+    fn visit_local(&mut self, local: mir::Local, context: PlaceContext, _location: mir::Location) {
+        // The context of the local of a projected place is `Projection` instead.
+        if context == PlaceContext::NonMutatingUse(NonMutatingUseContext::Move) {
+            self.locals.push(local);
+        }
+    }
+}
+
+pub(crate) struct InterproceduralCFG<'a, 'tcx, 'compilation> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    known_names_cache: RefCell<KnownNamesCache>,
+    move_states_cache: RefCell<HashMap<FuncId, Rc<HashMap<mir::BasicBlock, MoveState>>>>,
+    pub(crate) callees: HashMap<BaseCallSite, HashSet<FuncId>>,
+    pub(crate) func_callees: HashMap<FuncId, HashSet<FuncId>>,
+    pub(crate) callers: HashMap<FuncId, HashSet<BaseCallSite>>,
+}
+
+impl<'a, 'tcx, 'compilation> InterproceduralCFG<'a, 'tcx, 'compilation> {
+    pub(crate) fn new<F, S>(acx: &'a AnalysisContext<'tcx, 'compilation>, call_graph: &CallGraph<F, S>) -> Self
+    where
+        F: CGFunction + Into<FuncId>,
+        S: CGCallSite + Into<BaseCallSite>,
+    {
+        let mut callees: HashMap<BaseCallSite, HashSet<FuncId>> = HashMap::new();
+        for edge in call_graph.graph.edge_indices() {
+            let (_, callee) = call_graph.graph.edge_endpoints(edge).unwrap();
+            let callsite: BaseCallSite = call_graph.graph[edge].callsite.into();
+            callees.entry(callsite).or_default().insert(call_graph.graph[callee].func.into());
+        }
+        let mut cfg = InterproceduralCFG {
+            acx,
+            known_names_cache: RefCell::new(KnownNamesCache::create_cache_from_language_items()),
+            move_states_cache: RefCell::new(HashMap::new()),
+            callees,
+            func_callees: HashMap::new(),
+            callers: HashMap::new(),
+        };
+        cfg.index_callees();
+        cfg
+    }
+
+    fn index_callees(&mut self) {
+        self.func_callees.clear();
+        self.callers.clear();
+        for (callsite, callsite_callees) in &self.callees {
+            self.func_callees
+                .entry(callsite.func)
+                .or_default()
+                .extend(callsite_callees.iter().copied());
+            for callee in callsite_callees {
+                self.callers.entry(*callee).or_default().insert(*callsite);
+            }
+        }
+    }
+
+    /// Removes the callsites from the graph, and returns their callees.
+    fn remove_callsites(&mut self, callsites: &[BaseCallSite]) -> Vec<HashSet<FuncId>> {
+        let removed = callsites
+            .iter()
+            .map(|callsite| self.callees.remove(callsite).unwrap_or_default())
+            .collect();
+        self.index_callees();
+        removed
+    }
+
+    /// Removes the calls of the spawned closures from the graph, and returns the functions called
+    /// by the spawned closures at each `spawn_unchecked` callsite.
+    pub(crate) fn remove_spawned_closure_calls<P: PAGPath>(
+        &mut self,
+        pag: &PAG<P>,
+        reach_funcs: &[FuncId],
+    ) -> Vec<(BaseCallSite, HashSet<FuncId>)> {
+        // The spawned closure is called right after the `spawn_unchecked` callsite,
+        // see `special_function_handler::handle_thread_builder_spawn_unchecked`.
+        let mut spawn_sites = Vec::new();
+        let mut closure_callsites = Vec::new();
+        for func in reach_funcs {
+            let Some(fpag) = pag.get_func_pag(func) else {
+                continue;
+            };
+            for (callsite, callee) in &fpag.special_callsites {
+                let callee_def_id = self.acx.get_function_reference(*callee).def_id;
+                if self.known_name(callee_def_id) != KnownNames::StdThreadBuilderSpawnUnchecked {
+                    continue;
+                }
+                let mut closure_location = callsite.location;
+                closure_location.statement_index += 1;
+                closure_callsites.push(BaseCallSite::new(*func, closure_location));
+                spawn_sites.push(BaseCallSite::new(*func, callsite.location));
+            }
+        }
+        let entries = self.remove_callsites(&closure_callsites);
+        spawn_sites.into_iter().zip(entries).collect()
+    }
+
+    pub(crate) fn known_name(&self, def_id: DefId) -> KnownNames {
+        self.known_names_cache.borrow_mut().get(self.acx.tcx, def_id)
+    }
+
+    pub(crate) fn is_known_call(&self, callee: &mir::Operand<'tcx>, pred: impl Fn(KnownNames) -> bool) -> bool {
+        match callee.const_fn_def() {
+            Some((def_id, _)) => pred(self.known_name(def_id)),
+            None => false,
+        }
+    }
+
+    /// Returns the functions reachable from the given functions.
+    pub(crate) fn reachable_funcs(&self, roots: impl Iterator<Item = FuncId>) -> HashSet<FuncId> {
+        let mut reachable: HashSet<FuncId> = HashSet::new();
+        let mut worklist: Vec<FuncId> = Vec::new();
+        for root in roots {
+            if reachable.insert(root) {
+                worklist.push(root);
+            }
+        }
+        while let Some(func) = worklist.pop() {
+            for callee in self.func_callees.get(&func).into_iter().flatten() {
+                if reachable.insert(*callee) {
+                    worklist.push(*callee);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Returns the code that may be executed after the callsite, including the code executed
+    /// after returning to the callers. The paths stop at the call terminators satisfying `stops_at`.
+    /// The branches guarded by the drop flags of the values moved out on the paths are skipped,
+    /// e.g. the drop of a `Box` after it is moved into the callee.
+    pub(crate) fn code_after(
+        &self,
+        callsite: BaseCallSite,
+        stops_at: impl Fn(FuncId, &'tcx mir::Body<'tcx>, &mir::Terminator<'tcx>) -> bool,
+    ) -> Region {
+        let mut region = Region::default();
+        let mut called_funcs: HashSet<FuncId> = HashSet::new();
+        let mut returned_funcs: HashSet<FuncId> = HashSet::new();
+        let mut block_states: HashMap<(FuncId, mir::BasicBlock), MoveState> = HashMap::new();
+        let mut worklist: Vec<(FuncId, mir::BasicBlock, MoveState)> = self.successors(callsite);
+        while let Some((func, block, state)) = worklist.pop() {
+            let mut state = match block_states.entry((func, block)) {
+                Entry::Vacant(entry) => entry.insert(state).clone(),
+                Entry::Occupied(mut entry) => {
+                    if !entry.get_mut().meet(&state) {
+                        continue;
+                    }
+                    entry.get().clone()
+                }
+            };
+            region.blocks.insert((func, block));
+            let Some(mir) = self.func_mir(func) else {
+                continue;
+            };
+            let data = &mir.basic_blocks[block];
+            let terminator = data.terminator();
+            match &terminator.kind {
+                mir::TerminatorKind::Call { .. } => {
+                    if stops_at(func, mir, terminator) {
+                        continue;
+                    }
+                    let callsite = BaseCallSite::new(func, mir.terminator_loc(block));
+                    called_funcs.extend(self.callees.get(&callsite).into_iter().flatten());
+                }
+                mir::TerminatorKind::Return => {
+                    if returned_funcs.insert(func) {
+                        for caller_callsite in self.callers.get(&func).into_iter().flatten() {
+                            worklist.extend(self.successors(*caller_callsite));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            for statement in &data.statements {
+                state.transfer_statement(statement);
+            }
+            state.transfer_terminator(terminator);
+            worklist.extend(state.successors(terminator).into_iter().map(|succ| (func, succ, state.clone())));
+        }
+        region.funcs = self.reachable_funcs(called_funcs.into_iter());
+        region
+    }
+
+    /// Returns the successors of the callsite's terminator with the move states on entering them.
+    fn successors(&self, callsite: BaseCallSite) -> Vec<(FuncId, mir::BasicBlock, MoveState)> {
+        let Some(mir) = self.func_mir(callsite.func) else {
+            return Vec::new();
+        };
+        let terminator = mir.basic_blocks[callsite.location.block].terminator();
+        let mut state = self.move_state_at(callsite.func, mir, callsite.location);
+        state.transfer_terminator(terminator);
+        state
+            .successors(terminator)
+            .into_iter()
+            .map(|succ| (callsite.func, succ, state.clone()))
+            .collect()
+    }
+
+    /// Returns the move state before the statement or the terminator at the location, on all the
+    /// paths from the entry of the function.
+    pub(crate) fn move_state_at(&self, func: FuncId, mir: &mir::Body, location: mir::Location) -> MoveState {
+        let block_states = self
+            .move_states_cache
+            .borrow_mut()
+            .entry(func)
+            .or_insert_with(|| Rc::new(analyze_moves(mir)))
+            .clone();
+        // The blocks unreachable from the entry are given the empty state.
+        let mut state = block_states.get(&location.block).cloned().unwrap_or_default();
+        for statement in &mir.basic_blocks[location.block].statements[..location.statement_index] {
+            state.transfer_statement(statement);
+        }
+        state
+    }
+
+    /// Returns the MIR of the function, excluding the promoted constants and the static variables.
+    pub(crate) fn func_mir(&self, func_id: FuncId) -> Option<&'tcx mir::Body<'tcx>> {
+        let func_ref = self.acx.get_function_reference(func_id);
+        let tcx = self.acx.tcx;
+        if func_ref.promoted.is_some() || tcx.is_static(func_ref.def_id) || !tcx.is_mir_available(func_ref.def_id) {
+            return None;
+        }
+        Some(tcx.optimized_mir(func_ref.def_id))
+    }
+}
+
+/// Runs the intraprocedural must-move analysis on the function, and returns the move states at
+/// the beginning of the basic blocks reachable from the entry.
+fn analyze_moves(mir: &mir::Body) -> HashMap<mir::BasicBlock, MoveState> {
+    let mut block_states: HashMap<mir::BasicBlock, MoveState> = HashMap::new();
+    block_states.insert(mir::START_BLOCK, MoveState::default());
+    let mut worklist = vec![mir::START_BLOCK];
+    while let Some(block) = worklist.pop() {
+        let data = &mir.basic_blocks[block];
+        let mut state = block_states[&block].clone();
+        for statement in &data.statements {
+            state.transfer_statement(statement);
+        }
+        let terminator = data.terminator();
+        state.transfer_terminator(terminator);
+        for succ in state.successors(terminator) {
+            let changed = match block_states.entry(succ) {
+                Entry::Vacant(entry) => {
+                    entry.insert(state.clone());
+                    true
+                }
+                Entry::Occupied(mut entry) => entry.get_mut().meet(&state),
+            };
+            if changed {
+                worklist.push(succ);
+            }
+        }
+    }
+    block_states
+}
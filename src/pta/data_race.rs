@@ -0,0 +1,418 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! A static data race detector based on the points-to results.
+//!
+//! The threads are started by the closures passed to `Builder::spawn_unchecked`, besides the main
+//! thread running the entry functions. A spawned thread may run concurrently with the code its
+//! parent executes after spawning it until a call to `JoinHandle::join` on its handle, which is
+//! identified by the packet allocated at the spawn site, with the threads spawned by that code,
+//! and with itself if it may be spawned again. Only the objects that may be shared between threads
+//! are considered, i.e. the static variables and the objects reachable from them or from the
+//! environments of the spawned closures.
+//!
+//! The locks held at each access are computed by a must-lockset analysis. A lock is acquired by
+//! `Mutex::lock`, `RwLock::read` or `RwLock::write` on a receiver pointing to a single lock object,
+//! and released when a guard pointing to it is dropped or moved into a call. The locksets at the
+//! callsites are intersected to give the locks held on entry to the callees. A pair of accesses to
+//! overlapping objects, at least one of which is a write, is reported as a possible data race if
+//! the accesses may run concurrently without holding a common lock.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use log::*;
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir;
+
+use crate::api::{MemoryObject, ObjectBase};
+use crate::graph::call_graph::{CGCallSite, CGFunction, CallGraph};
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::call_site::BaseCallSite;
+use crate::mir::function::FuncId;
+use crate::mir::known_names::KnownNames;
+use crate::mir::path::Path;
+use crate::pta::control_flow::{InterproceduralCFG, Region};
+use crate::pta::escape_analysis;
+use crate::pta::lockset::{LockSet, LockSetAnalysis};
+use crate::pta::mod_ref;
+use crate::pta::points_to_view::{is_write_access, PointsToView};
+use crate::pta::DiffPTDataTy;
+
+/// An access of a shared object at a MIR location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RaceAccess {
+    pub func: FuncId,
+    pub location: mir::Location,
+    pub is_write: bool,
+}
+
+/// A pair of conflicting accesses that may run concurrently without holding a common lock.
+#[derive(Debug)]
+pub struct DataRace {
+    pub object: Rc<Path>,
+    pub accesses: (RaceAccess, RaceAccess),
+}
+
+/// The indices of the accesses of the objects with a common base, with the accessed objects.
+type BaseAccesses<'a> = Vec<(usize, MemoryObject, &'a Rc<Path>)>;
+
+struct SpawnedThread {
+    /// The callsite of `spawn_unchecked` starting the thread.
+    spawn_site: BaseCallSite,
+    /// The functions called by the spawned closure.
+    entries: HashSet<FuncId>,
+    /// The functions executed by the thread.
+    funcs: HashSet<FuncId>,
+    /// The code of the other threads that may run concurrently with the thread.
+    concurrent: Region,
+}
+
+impl SpawnedThread {
+    /// Returns true if the thread may be spawned again while it is running.
+    fn has_multiple_instances(&self) -> bool {
+        self.concurrent.contains(self.spawn_site.func, self.spawn_site.location.block)
+    }
+}
+
+struct Access {
+    race_access: RaceAccess,
+    objects: HashSet<Rc<Path>>,
+    locks: LockSet<Rc<Path>>,
+}
+
+pub struct RaceDetector<'a, 'tcx, 'compilation> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    points_to: PointsToView<Rc<Path>>,
+    /// The interprocedural CFG, excluding the calls of the spawned closures.
+    cfg: InterproceduralCFG<'a, 'tcx, 'compilation>,
+    threads: Vec<SpawnedThread>,
+    /// The locksets at the beginning of the basic blocks of each function.
+    block_locksets: HashMap<FuncId, HashMap<mir::BasicBlock, LockSet<Rc<Path>>>>,
+}
+
+/// Checks the data races on the shared objects and reports them if `--check-races` is set.
+pub fn check_races<P, F, S>(
+    acx: &AnalysisContext,
+    call_graph: &CallGraph<F, S>,
+    pt_data: &DiffPTDataTy,
+    pag: &PAG<P>,
+) -> Vec<DataRace>
+where
+    P: PAGPath,
+    F: CGFunction + Into<FuncId>,
+    S: CGCallSite + Into<BaseCallSite>,
+{
+    if !acx.analysis_options.check_races {
+        return Vec::new();
+    }
+    let mut detector = RaceDetector {
+        acx,
+        points_to: PointsToView::new(pt_data, pag, |path| path.ci_path()),
+        cfg: InterproceduralCFG::new(acx, call_graph),
+        threads: Vec::new(),
+        block_locksets: HashMap::new(),
+    };
+
+    let mut reach_funcs: Vec<FuncId> = call_graph.reach_funcs_iter().map(|func| func.into()).collect();
+    reach_funcs.sort();
+    reach_funcs.dedup();
+    let thread_entries = detector.cfg.remove_spawned_closure_calls(pag, &reach_funcs);
+    detector.collect_threads(thread_entries);
+
+    // The spawned closures start without holding locks, even if they are also called elsewhere.
+    let mut roots: Vec<FuncId> = acx.entry_points.clone();
+    for thread in &detector.threads {
+        roots.extend(thread.entries.iter().copied());
+    }
+    detector.compute_locksets(roots);
+
+    let shared_objects = escape_analysis::shared_objects(acx, pt_data, pag);
+    let accesses = detector.collect_accesses(&reach_funcs, &shared_objects);
+    let races = detector.find_races(&accesses);
+    info!("Reporting data races...");
+    detector.report_races(&races);
+    races
+}
+
+impl<'a, 'tcx, 'compilation> RaceDetector<'a, 'tcx, 'compilation> {
+    fn collect_threads(&mut self, thread_entries: Vec<(BaseCallSite, HashSet<FuncId>)>) {
+        for (spawn_site, entries) in thread_entries {
+            let funcs = self.cfg.reachable_funcs(entries.iter().copied());
+            let concurrent = self
+                .cfg
+                .code_after(spawn_site, |func, mir, terminator| self.joins(spawn_site, func, mir, terminator));
+            self.threads.push(SpawnedThread { spawn_site, entries, funcs, concurrent });
+        }
+        // A thread spawned by another thread may also run concurrently with the code
+        // running concurrently with its parent.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in 0..self.threads.len() {
+                for j in 0..self.threads.len() {
+                    if i != j && self.threads[j].funcs.contains(&self.threads[i].spawn_site.func) {
+                        let parent_concurrent = std::mem::take(&mut self.threads[j].concurrent);
+                        changed |= self.threads[i].concurrent.extend(&parent_concurrent);
+                        self.threads[j].concurrent = parent_concurrent;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns true if the terminator calls `JoinHandle::join` on the handle of the thread spawned at
+    /// `spawn_site`, i.e. the handle only points to the packet allocated at the spawn site. A handle
+    /// that may also be the handle of another thread does not end the thread.
+    fn joins(&self, spawn_site: BaseCallSite, func: FuncId, mir: &'tcx mir::Body<'tcx>, terminator: &mir::Terminator<'tcx>) -> bool {
+        let mir::TerminatorKind::Call { func: callee, args, .. } = &terminator.kind else {
+            return false;
+        };
+        if !self.cfg.is_known_call(callee, |name| name == KnownNames::StdThreadJoinHandleJoin) {
+            return false;
+        }
+        let packet = Path::new_heap_obj(spawn_site.func, spawn_site.location);
+        // fn join(self) -> Result<T>
+        match args.first().map(|arg| &arg.node) {
+            Some(mir::Operand::Copy(handle) | mir::Operand::Move(handle)) => {
+                let mut pointees = self.points_to.local_pointees(func, mir, handle.local).peekable();
+                pointees.peek().is_some() && pointees.all(|pointee| pointee.base_path() == packet)
+            }
+            _ => false,
+        }
+    }
+
+    /// Computes the locksets at the beginning of the basic blocks of the functions called from
+    /// the roots, which are entered without holding locks.
+    fn compute_locksets(&mut self, roots: Vec<FuncId>) {
+        let analysis = LockSetAnalysis::new(self.acx, &self.cfg, &self.points_to);
+        let mut entry_locksets: HashMap<FuncId, LockSet<Rc<Path>>> = HashMap::new();
+        let mut worklist: VecDeque<FuncId> = VecDeque::new();
+        for root in roots {
+            entry_locksets.insert(root, LockSet::new());
+            worklist.push_back(root);
+        }
+        let mut in_worklist: HashSet<FuncId> = worklist.iter().copied().collect();
+        while let Some(func) = worklist.pop_front() {
+            in_worklist.remove(&func);
+            let Some(mir) = self.cfg.func_mir(func) else {
+                continue;
+            };
+            let block_locksets = analysis.analyze(func, mir, entry_locksets[&func].clone());
+            for (block, lockset) in &block_locksets {
+                if !matches!(mir.basic_blocks[*block].terminator().kind, mir::TerminatorKind::Call { .. }) {
+                    continue;
+                }
+                let callsite = BaseCallSite::new(func, mir.terminator_loc(*block));
+                for callee in self.cfg.callees.get(&callsite).into_iter().flatten() {
+                    let changed = match entry_locksets.entry(*callee) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(lockset.clone());
+                            true
+                        }
+                        Entry::Occupied(mut entry) => {
+                            let len = entry.get().len();
+                            entry.get_mut().retain(|lock| lockset.contains(lock));
+                            len != entry.get().len()
+                        }
+                    };
+                    if changed && in_worklist.insert(*callee) {
+                        worklist.push_back(*callee);
+                    }
+                }
+            }
+            self.block_locksets.insert(func, block_locksets);
+        }
+    }
+
+    /// Collects the accesses of the shared objects through the dereferenced places of the functions.
+    fn collect_accesses(&self, reach_funcs: &[FuncId], shared_objects: &HashSet<Rc<Path>>) -> Vec<Access> {
+        let mut accesses = Vec::new();
+        for func in reach_funcs {
+            let Some(mir) = self.cfg.func_mir(*func) else {
+                continue;
+            };
+            let mut collector = AccessCollector {
+                detector: self,
+                func: *func,
+                mir,
+                shared_objects,
+                accesses: &mut accesses,
+            };
+            collector.visit_body(mir);
+        }
+        accesses
+    }
+
+    /// Returns the threads executing the function, where `None` stands for the main thread.
+    fn threads_of(&self, func: FuncId, main_funcs: &HashSet<FuncId>) -> Vec<Option<usize>> {
+        let mut threads: Vec<Option<usize>> = self
+            .threads
+            .iter()
+            .enumerate()
+            .filter(|(_, thread)| thread.funcs.contains(&func))
+            .map(|(i, _)| Some(i))
+            .collect();
+        if main_funcs.contains(&func) {
+            threads.push(None);
+        }
+        threads
+    }
+
+    fn may_run_concurrently(&self, a: &RaceAccess, thread_a: Option<usize>, b: &RaceAccess, thread_b: Option<usize>) -> bool {
+        match (thread_a, thread_b) {
+            (None, None) => false,
+            (Some(i), Some(j)) if i == j => self.threads[i].has_multiple_instances(),
+            _ => {
+                let runs_concurrently_with = |access: &RaceAccess, thread: Option<usize>| match thread {
+                    Some(i) => self.threads[i].concurrent.contains(access.func, access.location.block),
+                    None => false,
+                };
+                let spawned_concurrently_with = |spawned: Option<usize>, thread: Option<usize>| match (spawned, thread) {
+                    (Some(i), Some(j)) => {
+                        let spawn_site = self.threads[i].spawn_site;
+                        self.threads[j].concurrent.contains(spawn_site.func, spawn_site.location.block)
+                    }
+                    _ => false,
+                };
+                runs_concurrently_with(a, thread_b)
+                    || runs_concurrently_with(b, thread_a)
+                    || spawned_concurrently_with(thread_a, thread_b)
+                    || spawned_concurrently_with(thread_b, thread_a)
+            }
+        }
+    }
+
+    /// Finds the pairs of conflicting accesses that may run concurrently without a common lock.
+    fn find_races(&self, accesses: &[Access]) -> Vec<DataRace> {
+        let main_funcs = self.cfg.reachable_funcs(self.acx.entry_points.iter().copied());
+        let mut accesses_by_base: HashMap<ObjectBase, BaseAccesses> = HashMap::new();
+        for (i, access) in accesses.iter().enumerate() {
+            for object in &access.objects {
+                if let Some(memory_object) = MemoryObject::from_path(object, true) {
+                    accesses_by_base
+                        .entry(memory_object.base)
+                        .or_default()
+                        .push((i, memory_object, object));
+                }
+            }
+        }
+
+        // The conflicting accesses with the object they race on, which is the first object in
+        // the order of the descriptions if they race on several objects.
+        let mut racing_accesses: HashMap<(usize, usize), &Rc<Path>> = HashMap::new();
+        for base_accesses in accesses_by_base.values() {
+            for (k, (i, object_i, path)) in base_accesses.iter().enumerate() {
+                for (j, object_j, _) in &base_accesses[k..] {
+                    let (a, b) = (&accesses[*i], &accesses[*j]);
+                    if !(a.race_access.is_write || b.race_access.is_write)
+                        || !object_i.may_overlap(object_j)
+                        || !a.locks.is_disjoint(&b.locks)
+                    {
+                        continue;
+                    }
+                    if let Some(reported_path) = racing_accesses.get_mut(&(*i, *j)) {
+                        if format!("{:?}", path) < format!("{:?}", reported_path) {
+                            *reported_path = path;
+                        }
+                        continue;
+                    }
+                    let threads_a = self.threads_of(a.race_access.func, &main_funcs);
+                    let threads_b = self.threads_of(b.race_access.func, &main_funcs);
+                    let concurrent = threads_a.iter().any(|thread_a| {
+                        threads_b.iter().any(|thread_b| {
+                            self.may_run_concurrently(&a.race_access, *thread_a, &b.race_access, *thread_b)
+                        })
+                    });
+                    if concurrent {
+                        racing_accesses.insert((*i, *j), path);
+                    }
+                }
+            }
+        }
+        racing_accesses
+            .into_iter()
+            .map(|((i, j), path)| DataRace {
+                object: path.clone(),
+                accesses: (accesses[i].race_access, accesses[j].race_access),
+            })
+            .collect()
+    }
+
+    fn access_description(&self, access: &RaceAccess) -> String {
+        let func_ref = self.acx.get_function_reference(access.func);
+        let span = self.acx.tcx.optimized_mir(func_ref.def_id).source_info(access.location).span;
+        format!(
+            "{} in {} at {}",
+            if access.is_write { "write" } else { "read" },
+            func_ref.to_string(),
+            self.acx.tcx.sess.source_map().span_to_embeddable_string(span),
+        )
+    }
+
+    /// Prints the data races sorted by the accesses.
+    fn report_races(&self, races: &[DataRace]) {
+        let mut reports: Vec<(String, String, String)> = races
+            .iter()
+            .map(|race| {
+                let mut first = self.access_description(&race.accesses.0);
+                let mut second = self.access_description(&race.accesses.1);
+                if first > second {
+                    std::mem::swap(&mut first, &mut second);
+                }
+                (mod_ref::object_description(self.acx, &race.object), first, second)
+            })
+            .collect();
+        reports.sort_by(|a, b| (&a.1, &a.2, &a.0).cmp(&(&b.1, &b.2, &b.0)));
+        reports.dedup();
+        println!("Found {} possible data race(s)", reports.len());
+        for (object, first, second) in reports {
+            println!("Possible data race on {}:", object);
+            println!("    {}", first);
+            println!("    {}", second);
+        }
+    }
+}
+
+struct AccessCollector<'a, 'b, 'tcx, 'compilation> {
+    detector: &'b RaceDetector<'a, 'tcx, 'compilation>,
+    func: FuncId,
+    mir: &'tcx mir::Body<'tcx>,
+    shared_objects: &'b HashSet<Rc<Path>>,
+    accesses: &'b mut Vec<Access>,
+}
+
+impl<'a, 'b, 'tcx, 'compilation> Visitor<'tcx> for AccessCollector<'a, 'b, 'tcx, 'compilation> {
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, location: mir::Location) {
+        if !place.is_indirect() {
+            return;
+        }
+        let Some(is_write) = is_write_access(context) else {
+            return;
+        };
+        let detector = self.detector;
+        let mut objects = detector.points_to.place_objects(detector.acx, self.func, self.mir, place);
+        objects.retain(|object| {
+            let base = object.base_path();
+            base.is_static_variable() || self.shared_objects.contains(&base)
+        });
+        if objects.is_empty() {
+            return;
+        }
+        let locks = detector
+            .block_locksets
+            .get(&self.func)
+            .and_then(|block_locksets| block_locksets.get(&location.block))
+            .cloned()
+            .unwrap_or_default();
+        self.accesses.push(Access {
+            race_access: RaceAccess { func: self.func, location, is_write },
+            objects,
+            locks,
+        });
+    }
+}
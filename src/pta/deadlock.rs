@@ -0,0 +1,398 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! A static deadlock detector based on the lock acquisition order.
+//!
+//! The `Mutex` and `RwLock` objects acquired by `lock`, `read` and `write` are resolved by the
+//! points-to results, qualified by the contexts of the analysis. The locks held at each callsite
+//! are computed by the must-lockset analysis of its function, and the locks that may be acquired
+//! by a function are summarized with the call chains leading to their acquisitions. A lock held
+//! while acquiring another lock, directly or by a called function, is ordered before it. The
+//! cycles of this order are reported as possible deadlocks, and a lock acquired again while it is
+//! held as a possible double locking. The calls of the spawned closures are not followed, as the
+//! spawned threads do not hold the locks of their parents.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use log::*;
+use rustc_middle::mir;
+
+use crate::graph::call_graph::{CGCallSite, CGFunction, CallGraph};
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::call_site::BaseCallSite;
+use crate::mir::function::FuncId;
+use crate::mir::path::Path;
+use crate::pta::control_flow::InterproceduralCFG;
+use crate::pta::lockset::{LockSet, LockSetAnalysis};
+use crate::pta::mod_ref;
+use crate::pta::points_to_view::PointsToView;
+use crate::pta::DiffPTDataTy;
+
+/// A lock held while acquiring another lock.
+#[derive(Clone, Debug)]
+pub struct LockOrderEdge {
+    /// The callsite acquiring the held lock.
+    pub holder: BaseCallSite,
+    /// The callsites from the function holding the lock to the acquisition of the other lock.
+    pub chain: Vec<BaseCallSite>,
+}
+
+/// A cycle in the lock acquisition order, or a double locking if it has a single lock.
+#[derive(Debug)]
+pub struct Deadlock {
+    pub locks: Vec<Rc<Path>>,
+    /// The edge from each lock to the next lock of the cycle.
+    pub edges: Vec<LockOrderEdge>,
+}
+
+/// A lock that may be acquired by a function.
+#[derive(Clone)]
+struct Acquired {
+    /// The callsites leading to the acquisition of the lock.
+    chain: Vec<BaseCallSite>,
+    /// Whether the receiver of the acquisition points to the lock only.
+    is_single: bool,
+}
+
+pub struct DeadlockDetector<'a, 'tcx, 'compilation, P> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    points_to: PointsToView<P>,
+    /// The interprocedural CFG, excluding the calls of the spawned closures.
+    cfg: InterproceduralCFG<'a, 'tcx, 'compilation>,
+    /// The callsites acquiring locks in each function, with the locks they may acquire.
+    acquisitions: HashMap<FuncId, Vec<(BaseCallSite, HashSet<P>)>>,
+    /// The non-empty locksets at the callsites.
+    held_locks: HashMap<BaseCallSite, LockSet<P>>,
+    /// The locks that may be acquired by each function and the functions it calls.
+    acquired: HashMap<FuncId, HashMap<P, Acquired>>,
+    /// The lock order edges from the held locks to the acquired locks.
+    edges: HashMap<(P, P), LockOrderEdge>,
+    /// The names of the locks in the reports.
+    names: HashMap<P, String>,
+}
+
+/// Checks the lock acquisition order and reports the possible deadlocks if `--check-deadlocks` is set.
+pub fn check_deadlocks<P, F, S>(
+    acx: &AnalysisContext,
+    call_graph: &CallGraph<F, S>,
+    pt_data: &DiffPTDataTy,
+    pag: &PAG<P>,
+) -> Vec<Deadlock>
+where
+    P: PAGPath,
+    F: CGFunction + Into<FuncId>,
+    S: CGCallSite + Into<BaseCallSite>,
+{
+    if !acx.analysis_options.check_deadlocks {
+        return Vec::new();
+    }
+    let mut detector = DeadlockDetector {
+        acx,
+        points_to: PointsToView::new(pt_data, pag, |path| path.clone()),
+        cfg: InterproceduralCFG::new(acx, call_graph),
+        acquisitions: HashMap::new(),
+        held_locks: HashMap::new(),
+        acquired: HashMap::new(),
+        edges: HashMap::new(),
+        names: HashMap::new(),
+    };
+
+    let mut reach_funcs: Vec<FuncId> = call_graph.reach_funcs_iter().map(|func| func.into()).collect();
+    reach_funcs.sort();
+    reach_funcs.dedup();
+    detector.cfg.remove_spawned_closure_calls(pag, &reach_funcs);
+    detector.collect_acquisitions(&reach_funcs);
+    detector.summarize_acquisitions();
+    detector.collect_edges();
+    detector.name_locks();
+
+    let cycles = detector.find_cycles();
+    info!("Reporting deadlocks...");
+    detector.report_deadlocks(&cycles);
+    cycles.iter().map(|cycle| detector.deadlock(cycle)).collect()
+}
+
+impl<'a, 'tcx, 'compilation, P: PAGPath> DeadlockDetector<'a, 'tcx, 'compilation, P> {
+    /// Collects the lock acquisitions of the functions and the locks held at their callsites,
+    /// starting from the empty lockset at the entry of each function.
+    fn collect_acquisitions(&mut self, reach_funcs: &[FuncId]) {
+        let analysis = LockSetAnalysis::new(self.acx, &self.cfg, &self.points_to);
+        for func in reach_funcs {
+            let Some(mir) = self.cfg.func_mir(*func) else {
+                continue;
+            };
+            let block_locksets = analysis.analyze(*func, mir, LockSet::new());
+            for (block, lockset) in block_locksets {
+                let terminator = mir.basic_blocks[block].terminator();
+                if !matches!(terminator.kind, mir::TerminatorKind::Call { .. }) {
+                    continue;
+                }
+                let callsite = BaseCallSite::new(*func, mir.terminator_loc(block));
+                let locks = analysis.acquired_locks(*func, mir, terminator);
+                if !locks.is_empty() {
+                    self.acquisitions.entry(*func).or_default().push((callsite, locks));
+                }
+                if !lockset.is_empty() {
+                    self.held_locks.insert(callsite, lockset);
+                }
+            }
+        }
+    }
+
+    /// Computes the locks that may be acquired by each function, propagating the acquisitions
+    /// of the callees to their callers until a fixed point is reached.
+    fn summarize_acquisitions(&mut self) {
+        let mut worklist: VecDeque<FuncId> = VecDeque::new();
+        for (func, acquisitions) in &self.acquisitions {
+            let acquired = self.acquired.entry(*func).or_default();
+            for (callsite, locks) in acquisitions {
+                for lock in locks {
+                    let acquisition = Acquired { chain: vec![*callsite], is_single: locks.len() == 1 };
+                    merge_acquired(acquired, lock, acquisition);
+                }
+            }
+            worklist.push_back(*func);
+        }
+        let mut in_worklist: HashSet<FuncId> = worklist.iter().copied().collect();
+        while let Some(callee) = worklist.pop_front() {
+            in_worklist.remove(&callee);
+            let callee_acquired: Vec<(P, Acquired)> = self.acquired[&callee]
+                .iter()
+                .map(|(lock, acquisition)| (lock.clone(), acquisition.clone()))
+                .collect();
+            for callsite in self.cfg.callers.get(&callee).into_iter().flatten() {
+                let acquired = self.acquired.entry(callsite.func).or_default();
+                let mut changed = false;
+                for (lock, acquisition) in &callee_acquired {
+                    let mut chain = vec![*callsite];
+                    chain.extend(acquisition.chain.iter().copied());
+                    changed |= merge_acquired(acquired, lock, Acquired { chain, is_single: acquisition.is_single });
+                }
+                if changed && in_worklist.insert(callsite.func) {
+                    worklist.push_back(callsite.func);
+                }
+            }
+        }
+    }
+
+    /// Collects the edges from the locks held at the callsites to the locks acquired by them.
+    /// A lock ordered before itself is only kept if it is the single lock of the acquisition.
+    fn collect_edges(&mut self) {
+        for (callsite, lockset) in &self.held_locks {
+            let mut acquired: Vec<(P, Acquired)> = Vec::new();
+            for (acquisition_site, locks) in self.acquisitions.get(&callsite.func).into_iter().flatten() {
+                if acquisition_site == callsite {
+                    let is_single = locks.len() == 1;
+                    acquired.extend(locks.iter().map(|lock| (lock.clone(), Acquired { chain: vec![*callsite], is_single })));
+                }
+            }
+            for callee in self.cfg.callees.get(callsite).into_iter().flatten() {
+                for (lock, acquisition) in self.acquired.get(callee).into_iter().flatten() {
+                    let mut chain = vec![*callsite];
+                    chain.extend(acquisition.chain.iter().copied());
+                    acquired.push((lock.clone(), Acquired { chain, is_single: acquisition.is_single }));
+                }
+            }
+            for held in lockset {
+                let Some(holder) = self.holder(callsite.func, held) else {
+                    continue;
+                };
+                for (lock, acquisition) in &acquired {
+                    if held == lock && !acquisition.is_single {
+                        continue;
+                    }
+                    let edge = LockOrderEdge { holder, chain: acquisition.chain.clone() };
+                    let key = (held.clone(), lock.clone());
+                    let is_better = match self.edges.get(&key) {
+                        Some(existing) => self.edge_order_key(&edge) < self.edge_order_key(existing),
+                        None => true,
+                    };
+                    if is_better {
+                        self.edges.insert(key, edge);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the first callsite of the function acquiring the lock.
+    fn holder(&self, func: FuncId, lock: &P) -> Option<BaseCallSite> {
+        self.acquisitions
+            .get(&func)?
+            .iter()
+            .filter(|(_, locks)| locks.contains(lock))
+            .map(|(callsite, _)| *callsite)
+            .min_by_key(|callsite| callsite.location)
+    }
+
+    /// Orders the witnesses of an edge to report the same one in every run.
+    fn edge_order_key(&self, edge: &LockOrderEdge) -> (usize, String, Vec<String>) {
+        (
+            edge.chain.len(),
+            self.site_description(edge.holder),
+            edge.chain.iter().map(|callsite| self.site_description(*callsite)).collect(),
+        )
+    }
+
+    /// Names the locks by their context-insensitive objects, numbering the locks sharing the same
+    /// object in the order of their first acquisitions.
+    fn name_locks(&mut self) {
+        let mut first_acquisitions: HashMap<P, String> = HashMap::new();
+        for acquisitions in self.acquisitions.values() {
+            for (callsite, locks) in acquisitions {
+                let description = self.site_description(*callsite);
+                for lock in locks {
+                    let first = first_acquisitions.entry(lock.clone()).or_insert_with(|| description.clone());
+                    if description < *first {
+                        *first = description.clone();
+                    }
+                }
+            }
+        }
+        let mut locks_by_object: HashMap<String, Vec<P>> = HashMap::new();
+        for (held, lock) in self.edges.keys() {
+            for lock in [held, lock] {
+                let object = mod_ref::object_description(self.acx, &lock.ci_path());
+                let locks = locks_by_object.entry(object).or_default();
+                if !locks.contains(lock) {
+                    locks.push(lock.clone());
+                }
+            }
+        }
+        for (object, mut locks) in locks_by_object {
+            if locks.len() == 1 {
+                self.names.insert(locks.pop().unwrap(), object);
+                continue;
+            }
+            locks.sort_by_cached_key(|lock| (first_acquisitions.get(lock).cloned(), format!("{:?}", lock)));
+            for (i, lock) in locks.into_iter().enumerate() {
+                self.names.insert(lock, format!("{} (instance {})", object, i + 1));
+            }
+        }
+    }
+
+    /// Finds the double lockings and the shortest cycle through each lock order edge.
+    fn find_cycles(&self) -> Vec<Vec<P>> {
+        let mut successors: HashMap<&P, Vec<&P>> = HashMap::new();
+        for (held, lock) in self.edges.keys() {
+            if held != lock {
+                successors.entry(held).or_default().push(lock);
+            }
+        }
+        for locks in successors.values_mut() {
+            locks.sort_by_key(|lock| &self.names[*lock]);
+        }
+        let mut edges: Vec<&(P, P)> = self.edges.keys().collect();
+        edges.sort_by_key(|(held, lock)| (&self.names[held], &self.names[lock]));
+
+        let mut cycles: Vec<Vec<P>> = Vec::new();
+        let mut found: HashSet<Vec<&String>> = HashSet::new();
+        for (held, lock) in edges {
+            if held == lock {
+                cycles.push(vec![held.clone()]);
+                continue;
+            }
+            let Some(path) = shortest_path(&successors, lock, held) else {
+                continue;
+            };
+            // The cycle held -> lock -> ... -> held, starting at the lock with the smallest name.
+            let mut cycle: Vec<P> = std::iter::once(held.clone()).chain(path.into_iter().take_while(|p| p != held)).collect();
+            let start = (0..cycle.len()).min_by_key(|i| &self.names[&cycle[*i]]).unwrap();
+            cycle.rotate_left(start);
+            if found.insert(cycle.iter().map(|lock| &self.names[lock]).collect()) {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+
+    /// Returns the edge from the i-th lock of the cycle to the next one.
+    fn cycle_edge(&self, cycle: &[P], i: usize) -> &LockOrderEdge {
+        &self.edges[&(cycle[i].clone(), cycle[(i + 1) % cycle.len()].clone())]
+    }
+
+    fn deadlock(&self, cycle: &[P]) -> Deadlock {
+        Deadlock {
+            locks: cycle.iter().map(|lock| lock.ci_path()).collect(),
+            edges: (0..cycle.len()).map(|i| self.cycle_edge(cycle, i).clone()).collect(),
+        }
+    }
+
+    fn site_description(&self, callsite: BaseCallSite) -> String {
+        let func_ref = self.acx.get_function_reference(callsite.func);
+        let span = self.acx.tcx.optimized_mir(func_ref.def_id).source_info(callsite.location).span;
+        format!(
+            "{} at {}",
+            func_ref.to_string(),
+            self.acx.tcx.sess.source_map().span_to_embeddable_string(span),
+        )
+    }
+
+    /// Prints the double lockings and the lock order cycles with the call chains producing them.
+    fn report_deadlocks(&self, cycles: &[Vec<P>]) {
+        println!("Found {} possible deadlock(s)", cycles.len());
+        for cycle in cycles {
+            let names: Vec<&String> = cycle.iter().map(|lock| &self.names[lock]).collect();
+            if names.len() == 1 {
+                println!("Possible double locking of {}:", names[0]);
+            } else {
+                let cycle_names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+                println!("Possible deadlock in the lock order cycle {} -> {}:", cycle_names.join(" -> "), names[0]);
+            }
+            for i in 0..cycle.len() {
+                let edge = self.cycle_edge(cycle, i);
+                println!("    {} locked in {}", names[i], self.site_description(edge.holder));
+                let (acquisition, calls) = edge.chain.split_last().unwrap();
+                for callsite in calls {
+                    println!("        call in {}", self.site_description(*callsite));
+                }
+                println!(
+                    "        {} locked in {}",
+                    names[(i + 1) % names.len()],
+                    self.site_description(*acquisition)
+                );
+            }
+        }
+    }
+}
+
+/// Records that the lock may be acquired, preferring the acquisitions of a single lock.
+/// Returns true if the acquisitions are changed.
+fn merge_acquired<P: PAGPath>(acquired: &mut HashMap<P, Acquired>, lock: &P, acquisition: Acquired) -> bool {
+    match acquired.get(lock) {
+        Some(existing) if existing.is_single || !acquisition.is_single => false,
+        _ => {
+            acquired.insert(lock.clone(), acquisition);
+            true
+        }
+    }
+}
+
+/// Returns the shortest path from `from` to `to` in the graph.
+fn shortest_path<'p, P: PAGPath>(successors: &HashMap<&'p P, Vec<&'p P>>, from: &'p P, to: &'p P) -> Option<Vec<P>> {
+    let mut predecessors: HashMap<&P, &P> = HashMap::new();
+    let mut queue: VecDeque<&P> = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut path = vec![to.clone()];
+            let mut node = to;
+            while let Some(pred) = predecessors.get(node) {
+                path.push((*pred).clone());
+                node = pred;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for succ in successors.get(node).into_iter().flatten() {
+            if *succ != from && !predecessors.contains_key(succ) {
+                predecessors.insert(succ, node);
+                queue.push_back(succ);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,411 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! An escape analysis of the stack and heap objects based on the points-to results.
+//!
+//! The objects form a graph, where an object points to another object if a pointer contained in
+//! the former points to the latter. Each local variable whose address is taken and each heap object
+//! is classified by the objects it is reachable from:
+//! - it escapes to another thread if it is reachable from the environment of a spawned closure;
+//! - it escapes to a global if it is reachable from a static variable;
+//! - it escapes to the caller if it is reachable from the return value of the function it belongs
+//!   to, or from the parameters of the function, i.e. it is stored into the objects of the callers;
+//! - otherwise it is local to the function.
+//!
+//! The objects are qualified by the contexts of the points-to analysis, so that the heap objects
+//! allocated by a function such as `Box::new` are distinguished by its callers in the
+//! callsite-sensitive analysis. For an object escaping to the caller, the callers it escapes from
+//! are followed over the call graph to find the functions it is confined to, e.g. the `Box`
+//! allocations that could live on the stack of the functions calling `Box::new`.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use log::*;
+use rustc_middle::mir;
+use rustc_middle::ty::InstanceDef;
+use rustc_span::Span;
+
+use crate::graph::call_graph::{CGCallSite, CGFunction, CallGraph};
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::call_site::BaseCallSite;
+use crate::mir::function::FuncId;
+use crate::mir::known_names::{KnownNames, KnownNamesCache};
+use crate::mir::path::{Path, PathEnum};
+use crate::pta::mod_ref::object_description;
+use crate::pta::DiffPTDataTy;
+use crate::pts_set::points_to::PointsToSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EscapeKind {
+    FunctionLocal,
+    EscapesToCaller,
+    EscapesToGlobal,
+    EscapesToThread,
+}
+
+impl EscapeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EscapeKind::FunctionLocal => "function-local",
+            EscapeKind::EscapesToCaller => "caller",
+            EscapeKind::EscapesToGlobal => "global",
+            EscapeKind::EscapesToThread => "thread",
+        }
+    }
+}
+
+/// The escape result of a stack or heap object.
+pub struct EscapeInfo {
+    /// The function the object belongs to.
+    pub func_id: FuncId,
+    pub kind: EscapeKind,
+    /// For an object escaping to the caller, the callsites in the functions it is confined to.
+    pub confined_to: Vec<BaseCallSite>,
+    /// For a heap object, the callsites calling the instance of the function that allocates it,
+    /// which distinguish the heap objects allocated under different contexts.
+    pub allocated_at: Vec<BaseCallSite>,
+}
+
+/// The object graph, in which an object points to another object if a pointer rooted at the
+/// former points to the latter. The objects are represented by the bases of their paths.
+pub(crate) struct ObjectGraph<P: PAGPath> {
+    succs: HashMap<P, HashSet<P>>,
+}
+
+impl<P: PAGPath> ObjectGraph<P> {
+    pub(crate) fn new(pt_data: &DiffPTDataTy, pag: &PAG<P>) -> Self {
+        let mut succs: HashMap<P, HashSet<P>> = HashMap::new();
+        for (node, pts) in &pt_data.propa_pts_map {
+            if pts.is_empty() {
+                continue;
+            }
+            let ptr_succs = succs.entry(pag.node_path(*node).base_path()).or_default();
+            for pointee in pts {
+                ptr_succs.insert(pag.node_path(pointee).base_path());
+            }
+        }
+        ObjectGraph { succs }
+    }
+
+    /// Returns the objects pointed to by the pointers rooted at the object.
+    pub(crate) fn successors(&self, object: &P) -> impl Iterator<Item = &P> {
+        self.succs.get(object).into_iter().flatten()
+    }
+
+    /// Returns the objects reachable from the environments of the spawned closures, and the
+    /// objects reachable from the static variables. The static variables and the closures are
+    /// matched regardless of their contexts.
+    fn thread_and_global_objects(&self, acx: &AnalysisContext, pag: &PAG<P>) -> (HashSet<P>, HashSet<P>) {
+        let thread_closures = thread_closures(acx, pag);
+        let thread_roots = self.succs.keys().filter(|ptr| thread_closures.contains(&ptr.ci_path()));
+        let statics = self
+            .succs
+            .keys()
+            .filter(|ptr| matches!(ptr.value(), PathEnum::StaticVariable { .. }));
+        (self.reachable_from(thread_roots), self.reachable_from(statics))
+    }
+
+    /// Returns the objects reachable from the roots through at least one pointer.
+    pub(crate) fn reachable_from<'a>(&self, roots: impl Iterator<Item = &'a P>) -> HashSet<P>
+    where
+        P: 'a,
+    {
+        let mut reachable: HashSet<P> = HashSet::new();
+        let mut worklist: VecDeque<P> = roots.cloned().collect();
+        while let Some(object) = worklist.pop_front() {
+            if let Some(succs) = self.succs.get(&object) {
+                for succ in succs {
+                    if reachable.insert(succ.clone()) {
+                        worklist.push_back(succ.clone());
+                    }
+                }
+            }
+        }
+        reachable
+    }
+}
+
+pub struct EscapeAnalysis<'a, 'tcx, 'compilation, P: PAGPath, F: CGFunction> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    object_graph: ObjectGraph<P>,
+    /// The pointers rooted at the locals, the parameters and the return value of each function.
+    func_ptrs: HashMap<F, HashSet<P>>,
+    /// The callers and the callsites calling each function.
+    callers: HashMap<F, HashSet<(F, BaseCallSite)>>,
+    /// The objects reachable from the parameters and the return value of each function.
+    escaping_cache: HashMap<F, HashSet<P>>,
+    /// The objects reachable from the pointers of each function.
+    visible_cache: HashMap<F, HashSet<P>>,
+}
+
+/// Runs the escape analysis on the stack and heap objects and dumps the results if required.
+pub fn analyze_escapes<P, F, S>(
+    acx: &AnalysisContext,
+    call_graph: &CallGraph<F, S>,
+    pt_data: &DiffPTDataTy,
+    pag: &PAG<P>,
+) -> HashMap<P, EscapeInfo>
+where
+    P: PAGPath<FuncTy = F>,
+    F: CGFunction + Into<FuncId>,
+    S: CGCallSite + Into<BaseCallSite>,
+{
+    let Some(escape_output) = &acx.analysis_options.escape_output else {
+        return HashMap::new();
+    };
+
+    let object_graph = ObjectGraph::new(pt_data, pag);
+    let mut objects: HashSet<P> = HashSet::new();
+    let mut func_ptrs: HashMap<F, HashSet<P>> = HashMap::new();
+    for (ptr_base, pointee_bases) in &object_graph.succs {
+        if let Some(func) = ptr_base.get_containing_func() {
+            func_ptrs.entry(func).or_default().insert(ptr_base.clone());
+        }
+        objects.extend(pointee_bases.iter().filter(|pointee_base| {
+            matches!(
+                pointee_base.value(),
+                PathEnum::LocalVariable { .. } | PathEnum::Parameter { .. } | PathEnum::HeapObj { .. }
+            )
+        }).cloned());
+    }
+    let mut callers: HashMap<F, HashSet<(F, BaseCallSite)>> = HashMap::new();
+    for edge in call_graph.graph.edge_indices() {
+        let (caller, callee) = call_graph.graph.edge_endpoints(edge).unwrap();
+        let callsite: BaseCallSite = call_graph.graph[edge].callsite.into();
+        callers
+            .entry(call_graph.graph[callee].func)
+            .or_default()
+            .insert((call_graph.graph[caller].func, callsite));
+    }
+
+    let (thread_objects, global_objects) = object_graph.thread_and_global_objects(acx, pag);
+
+    let mut analysis = EscapeAnalysis {
+        acx,
+        object_graph,
+        func_ptrs,
+        callers,
+        escaping_cache: HashMap::new(),
+        visible_cache: HashMap::new(),
+    };
+    let mut results = HashMap::new();
+    for object in objects {
+        let Some(func) = object.get_containing_func() else {
+            continue;
+        };
+        let (kind, confined_to) = if thread_objects.contains(&object) {
+            (EscapeKind::EscapesToThread, vec![])
+        } else if global_objects.contains(&object) {
+            (EscapeKind::EscapesToGlobal, vec![])
+        } else if analysis.escapes_from(func, &object) {
+            (EscapeKind::EscapesToCaller, analysis.confining_callsites(func, &object))
+        } else {
+            (EscapeKind::FunctionLocal, vec![])
+        };
+        let allocated_at = if matches!(object.value(), PathEnum::HeapObj { .. }) {
+            analysis.callsites_calling(func)
+        } else {
+            vec![]
+        };
+        let func_id = func.into();
+        results.insert(object, EscapeInfo { func_id, kind, confined_to, allocated_at });
+    }
+
+    info!("Dumping escape analysis results...");
+    analysis.dump_escapes(&results, escape_output);
+    results
+}
+
+/// Returns the objects that may be shared between threads, i.e. the objects reachable from the
+/// environments of the spawned closures or from the static variables, without contexts.
+pub fn shared_objects<P: PAGPath>(acx: &AnalysisContext, pt_data: &DiffPTDataTy, pag: &PAG<P>) -> HashSet<Rc<Path>> {
+    let object_graph = ObjectGraph::new(pt_data, pag);
+    let (thread_objects, global_objects) = object_graph.thread_and_global_objects(acx, pag);
+    thread_objects.union(&global_objects).map(|object| object.ci_path()).collect()
+}
+
+impl<'a, 'tcx, 'compilation, P, F> EscapeAnalysis<'a, 'tcx, 'compilation, P, F>
+where
+    P: PAGPath<FuncTy = F>,
+    F: CGFunction,
+{
+    /// Returns true if the object is reachable from the parameters or the return value of the function.
+    fn escapes_from(&mut self, func: F, object: &P) -> bool {
+        let object_graph = &self.object_graph;
+        let func_ptrs = &self.func_ptrs;
+        self.escaping_cache
+            .entry(func)
+            .or_insert_with(|| {
+                let roots = func_ptrs.get(&func).into_iter().flatten().filter(|ptr| {
+                    matches!(ptr.value(), PathEnum::Parameter { .. } | PathEnum::ReturnValue { .. })
+                });
+                object_graph.reachable_from(roots)
+            })
+            .contains(object)
+    }
+
+    /// Returns true if the object is reachable from the pointers of the function.
+    fn is_visible_in(&mut self, func: F, object: &P) -> bool {
+        let object_graph = &self.object_graph;
+        let func_ptrs = &self.func_ptrs;
+        self.visible_cache
+            .entry(func)
+            .or_insert_with(|| object_graph.reachable_from(func_ptrs.get(&func).into_iter().flatten()))
+            .contains(object)
+    }
+
+    /// Follows the callers the object escapes to, and returns the callsites in the callers
+    /// the object does not escape from.
+    fn confining_callsites(&mut self, func: F, object: &P) -> Vec<BaseCallSite> {
+        let mut confined_to = HashSet::new();
+        let mut visited = HashSet::from([func]);
+        let mut worklist = VecDeque::from([func]);
+        while let Some(callee) = worklist.pop_front() {
+            let Some(callers) = self.callers.get(&callee).cloned() else {
+                continue;
+            };
+            for (caller, callsite) in callers {
+                if !self.is_visible_in(caller, object) {
+                    continue;
+                }
+                if self.escapes_from(caller, object) {
+                    if visited.insert(caller) {
+                        worklist.push_back(caller);
+                    }
+                } else {
+                    confined_to.insert(callsite);
+                }
+            }
+        }
+        confined_to.into_iter().collect()
+    }
+
+    /// Returns the callsites calling the function.
+    fn callsites_calling(&self, func: F) -> Vec<BaseCallSite> {
+        let callsites: HashSet<BaseCallSite> = self
+            .callers
+            .get(&func)
+            .into_iter()
+            .flatten()
+            .map(|(_, callsite)| *callsite)
+            .collect();
+        callsites.into_iter().collect()
+    }
+
+    /// Returns the MIR of the function, or of the initializer of a static variable.
+    fn func_mir(&self, func_id: FuncId) -> Option<&'tcx mir::Body<'tcx>> {
+        let func_ref = self.acx.get_function_reference(func_id);
+        if func_ref.promoted.is_some() || !self.acx.tcx.is_mir_available(func_ref.def_id) {
+            return None;
+        }
+        if self.acx.tcx.is_static(func_ref.def_id) {
+            Some(self.acx.tcx.instance_mir(InstanceDef::Item(func_ref.def_id)))
+        } else {
+            Some(self.acx.tcx.optimized_mir(func_ref.def_id))
+        }
+    }
+
+    /// Returns the source span of the object's declaration or allocation site.
+    fn object_span(&self, object: &Rc<Path>, func_id: FuncId) -> Option<Span> {
+        let mir = self.func_mir(func_id)?;
+        match &object.value {
+            PathEnum::LocalVariable { ordinal, .. } | PathEnum::Parameter { ordinal, .. } => mir
+                .local_decls
+                .get(mir::Local::from_usize(*ordinal))
+                .map(|decl| decl.source_info.span),
+            PathEnum::HeapObj { location, .. } => Some(mir.source_info(*location).span),
+            _ => None,
+        }
+    }
+
+    fn callsite_span(&self, callsite: &BaseCallSite) -> Option<Span> {
+        Some(self.func_mir(callsite.func)?.source_info(callsite.location).span)
+    }
+
+    fn span_string(&self, span: Option<Span>) -> String {
+        match span {
+            Some(span) => self.acx.tcx.sess.source_map().span_to_embeddable_string(span),
+            None => String::new(),
+        }
+    }
+
+    /// Describes the callsites with the functions containing them and their source spans.
+    fn callsites_json(&self, callsites: &[BaseCallSite]) -> Vec<serde_json::Value> {
+        let mut callsites = callsites.to_vec();
+        callsites.sort_by_key(|callsite| (callsite.func, callsite.location));
+        callsites
+            .iter()
+            .map(|callsite| {
+                serde_json::json!({
+                    "function": self.acx.get_function_reference(callsite.func).to_string(),
+                    "span": self.span_string(self.callsite_span(callsite)),
+                })
+            })
+            .collect()
+    }
+
+    /// Dumps the escape results in JSON format, sorted by the functions the objects belong to.
+    /// An object is dumped once for each distinct result of it under different contexts, and a
+    /// heap object is given the callsites allocating it under each context.
+    fn dump_escapes(&self, results: &HashMap<P, EscapeInfo>, escape_path: &String) {
+        let mut output: Vec<serde_json::Value> = Vec::new();
+        for (object, info) in results {
+            let object = object.ci_path();
+            let mut entry = serde_json::json!({
+                "function": self.acx.get_function_reference(info.func_id).to_string(),
+                "object": object_description(self.acx, &object),
+                "escape": info.kind.as_str(),
+                "span": self.span_string(self.object_span(&object, info.func_id)),
+            });
+            if !info.allocated_at.is_empty() {
+                entry["allocated_at"] = serde_json::Value::Array(self.callsites_json(&info.allocated_at));
+            }
+            if !info.confined_to.is_empty() {
+                entry["confined_to"] = serde_json::Value::Array(self.callsites_json(&info.confined_to));
+            }
+            output.push(entry);
+        }
+        output.sort_by_key(|entry| {
+            (
+                entry["function"].to_string(),
+                entry["object"].to_string(),
+                entry["allocated_at"].to_string(),
+                entry["escape"].to_string(),
+                entry["confined_to"].to_string(),
+            )
+        });
+        output.dedup();
+        let output = serde_json::to_string_pretty(&output).expect("Unable to serialize data");
+        match &escape_path[..] {
+            "stdout" => println!("{}", output),
+            _ => std::fs::write(escape_path, output).expect("Unable to write data"),
+        }
+    }
+}
+
+/// Returns the closures passed to the thread spawning functions, whose environments are shared
+/// with the spawned threads.
+fn thread_closures<P: PAGPath>(acx: &AnalysisContext, pag: &PAG<P>) -> HashSet<Rc<Path>> {
+    let mut known_names_cache = KnownNamesCache::create_cache_from_language_items();
+    let mut closures = HashSet::new();
+    for fpag in pag.func_pags.values() {
+        let callsites = fpag.static_dispatch_callsites.iter().chain(fpag.special_callsites.iter());
+        for (callsite, callee) in callsites {
+            let callee_def_id = acx.get_function_reference(*callee).def_id;
+            if matches!(
+                known_names_cache.get(acx.tcx, callee_def_id),
+                KnownNames::StdThreadBuilderSpawnUnchecked | KnownNames::StdThreadBuilderSpawnUnchecked_
+            ) {
+                // fn spawn_unchecked<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+                if let Some(closure) = callsite.args.get(1) {
+                    closures.insert(closure.base_path());
+                }
+            }
+        }
+    }
+    closures
+}
@@ -0,0 +1,711 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! A flow-sensitive intraprocedural refinement of the points-to results for selected functions.
+//!
+//! The pointer analyses are flow-insensitive, a local that is reassigned holds the union of all
+//! its values. For the functions selected with `--flow-sensitive-funcs`, this pass propagates the
+//! points-to sets of the pointers rooted at the function's locals over the MIR basic blocks, and
+//! computes the points-to sets holding before each MIR `Location`.
+//!
+//! The flow-insensitive (and interprocedural) result seeds the pass: the parameters start with
+//! their flow-insensitive points-to sets, the values loaded from the objects that are not locals of
+//! the function, the results of calls and the values that cannot be evaluated precisely are
+//! given by the flow-insensitive result. Assignments to locals, and stores through pointers that
+//! point to a single local object, are strong updates. After a call, the locals whose addresses
+//! are taken may have been modified by the callee, hence their flow-insensitive points-to sets
+//! are merged.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::rc::Rc;
+
+use log::*;
+use rustc_middle::mir::{self, tcx::PlaceTy};
+use rustc_middle::ty::{Ty, TyCtxt};
+
+use crate::builder::fpag_builder::find_constant_index_locals;
+use crate::builder::substs_specializer::SubstsSpecializer;
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::function::FuncId;
+use crate::mir::path::{Path, PathEnum, PathSelector, ProjectionElems};
+use crate::pta::DiffPTDataTy;
+use crate::pts_set::points_to::PointsToSet;
+use crate::util::type_util;
+
+/// The points-to sets of the pointers at a program point.
+pub type PointsToState = HashMap<Rc<Path>, HashSet<Rc<Path>>>;
+
+/// The flow-sensitive points-to results of a function.
+pub struct FlowSensitivePointsTo {
+    pub func_id: FuncId,
+    /// The points-to sets of the pointers rooted at the function's locals before each location.
+    pub pts_before: HashMap<mir::Location, PointsToState>,
+}
+
+impl FlowSensitivePointsTo {
+    /// Returns the points-to set of `ptr` before the given location.
+    pub fn pts_at(&self, location: mir::Location, ptr: &Rc<Path>) -> Option<&HashSet<Rc<Path>>> {
+        self.pts_before.get(&location).and_then(|state| state.get(ptr))
+    }
+}
+
+/// Collects the context-insensitive points-to sets from the results of a pointer analysis.
+pub fn ci_points_to<P: PAGPath>(pt_data: &DiffPTDataTy, pag: &PAG<P>) -> PointsToState {
+    let mut ci_pts: PointsToState = HashMap::new();
+    for (node, pts) in &pt_data.propa_pts_map {
+        if pts.is_empty() {
+            continue;
+        }
+        let ptr = pag.node_path(*node).ci_path();
+        let ci_pointees = ci_pts.entry(ptr).or_default();
+        for pointee in pts {
+            ci_pointees.insert(pag.node_path(pointee).ci_path());
+        }
+    }
+    ci_pts
+}
+
+/// Runs the flow-sensitive refinement on the reachable functions whose names match the
+/// `--flow-sensitive-funcs` pattern, dumps the results if required and returns them.
+pub fn analyze_selected_functions<P: PAGPath>(
+    acx: &AnalysisContext,
+    reach_funcs: impl Iterator<Item = FuncId>,
+    pt_data: &DiffPTDataTy,
+    pag: &PAG<P>,
+) -> Vec<FlowSensitivePointsTo> {
+    let Some(regex) = &acx.analysis_options.flow_sensitive_funcs else {
+        return Vec::new();
+    };
+    let mut selected_funcs: Vec<FuncId> = reach_funcs
+        .filter(|func_id| {
+            let func_ref = acx.get_function_reference(*func_id);
+            func_ref.promoted.is_none()
+                && acx.tcx.is_mir_available(func_ref.def_id)
+                && regex.is_match(&func_ref.to_string())
+        })
+        .collect();
+    selected_funcs.sort();
+    selected_funcs.dedup();
+
+    let fi_pts = ci_points_to(pt_data, pag);
+    let results: Vec<FlowSensitivePointsTo> = selected_funcs
+        .into_iter()
+        .map(|func_id| FlowSensitiveAnalysis::new(acx, func_id, &fi_pts).analyze())
+        .collect();
+    if let Some(fs_pts_output) = &acx.analysis_options.fs_pts_output {
+        info!("Dumping flow-sensitive points-to results...");
+        dump_flow_sensitive_pts(acx, &results, fs_pts_output);
+    }
+    results
+}
+
+pub struct FlowSensitiveAnalysis<'a, 'tcx, 'compilation> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    func_id: FuncId,
+    mir: &'tcx mir::Body<'tcx>,
+    substs_specializer: SubstsSpecializer<'tcx>,
+    /// The flow-insensitive points-to sets, keyed by the context-insensitive pointers.
+    fi_pts: &'a PointsToState,
+    /// The locals that may be pointed to, which can be modified by the callees.
+    address_taken_locals: HashSet<usize>,
+    /// The locals accessed through cast or offset paths, which are not tracked flow-sensitively.
+    untracked_locals: HashSet<usize>,
+    constant_index_locals: HashMap<mir::Local, usize>,
+}
+
+impl<'a, 'tcx, 'compilation> FlowSensitiveAnalysis<'a, 'tcx, 'compilation> {
+    pub fn new(acx: &'a AnalysisContext<'tcx, 'compilation>, func_id: FuncId, fi_pts: &'a PointsToState) -> Self {
+        let func_ref = acx.get_function_reference(func_id);
+        let mir = acx.tcx.optimized_mir(func_ref.def_id);
+        let substs_specializer = SubstsSpecializer::new(acx.tcx, func_ref.generic_args.clone());
+        let mut address_taken_locals = HashSet::new();
+        let mut untracked_locals = HashSet::new();
+        for pointee in fi_pts.values().flatten() {
+            if let Some((pointee_func, ordinal)) = local_of(pointee) {
+                if pointee_func != func_id {
+                    continue;
+                }
+                address_taken_locals.insert(ordinal);
+                if let PathEnum::QualifiedPath { projection, .. } = &pointee.value {
+                    if !projection.iter().all(is_tracked_selector) {
+                        untracked_locals.insert(ordinal);
+                    }
+                }
+            }
+        }
+        let constant_index_locals = if acx.analysis_options.index_sensitive_arrays.is_some() {
+            find_constant_index_locals(acx.tcx, mir)
+        } else {
+            HashMap::new()
+        };
+        FlowSensitiveAnalysis {
+            acx,
+            func_id,
+            mir,
+            substs_specializer,
+            fi_pts,
+            address_taken_locals,
+            untracked_locals,
+            constant_index_locals,
+        }
+    }
+
+    #[inline]
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.acx.tcx
+    }
+
+    /// Propagates the points-to states over the basic blocks until a fixed point is reached,
+    /// then records the state before each location.
+    pub fn analyze(&self) -> FlowSensitivePointsTo {
+        let mut entry_states: HashMap<mir::BasicBlock, PointsToState> = HashMap::new();
+        entry_states.insert(mir::START_BLOCK, self.initial_state());
+        let mut worklist = VecDeque::from([mir::START_BLOCK]);
+        while let Some(bb) = worklist.pop_front() {
+            let mut state = entry_states[&bb].clone();
+            for (stmt_idx, statement) in self.mir.basic_blocks[bb].statements.iter().enumerate() {
+                self.transfer_statement(&mut state, statement, mir::Location { block: bb, statement_index: stmt_idx });
+            }
+            for (succ, succ_state) in self.transfer_terminator(state, bb) {
+                let visited = entry_states.contains_key(&succ);
+                let changed = join(entry_states.entry(succ).or_default(), succ_state);
+                if (changed || !visited) && !worklist.contains(&succ) {
+                    worklist.push_back(succ);
+                }
+            }
+        }
+
+        let mut pts_before = HashMap::new();
+        for (bb, entry_state) in entry_states {
+            let mut state = entry_state;
+            let block_data = &self.mir.basic_blocks[bb];
+            for (stmt_idx, statement) in block_data.statements.iter().enumerate() {
+                let location = mir::Location { block: bb, statement_index: stmt_idx };
+                pts_before.insert(location, non_empty(&state));
+                self.transfer_statement(&mut state, statement, location);
+            }
+            let location = mir::Location { block: bb, statement_index: block_data.statements.len() };
+            pts_before.insert(location, non_empty(&state));
+        }
+        FlowSensitivePointsTo { func_id: self.func_id, pts_before }
+    }
+
+    /// The parameters hold their flow-insensitive points-to sets at the function entry.
+    fn initial_state(&self) -> PointsToState {
+        let mut state = PointsToState::new();
+        for (ptr, pts) in self.fi_pts {
+            if let PathEnum::Parameter { .. } = self.base_value(ptr) {
+                if self.is_tracked(ptr) {
+                    state.insert(ptr.clone(), pts.clone());
+                }
+            }
+        }
+        state
+    }
+
+    fn transfer_statement(&self, state: &mut PointsToState, statement: &mir::Statement<'tcx>, _location: mir::Location) {
+        match &statement.kind {
+            mir::StatementKind::Assign(box (place, rvalue)) => {
+                self.transfer_assign(state, place, rvalue);
+            }
+            mir::StatementKind::StorageLive(local) | mir::StatementKind::StorageDead(local) => {
+                let ordinal = local.as_usize();
+                state.retain(|ptr, _| local_of(ptr) != Some((self.func_id, ordinal)));
+            }
+            mir::StatementKind::Intrinsic(box mir::NonDivergingIntrinsic::CopyNonOverlapping(..)) => {
+                self.merge_address_taken_locals(state);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the states flowing to the successors of the basic block.
+    fn transfer_terminator(&self, mut state: PointsToState, bb: mir::BasicBlock) -> Vec<(mir::BasicBlock, PointsToState)> {
+        let terminator = self.mir.basic_blocks[bb].terminator();
+        match &terminator.kind {
+            mir::TerminatorKind::Call { destination, target, .. } => {
+                self.merge_address_taken_locals(&mut state);
+                let unwind_state = state.clone();
+                let (dests, strong) = self.eval_assigned_place(&state, destination);
+                let values = self
+                    .pointer_projections(self.place_ty(destination))
+                    .into_iter()
+                    .map(|proj| {
+                        let pts = self.fi_value(&dests, &proj);
+                        (proj, pts)
+                    })
+                    .collect();
+                self.assign(&mut state, &dests, strong, values);
+                terminator
+                    .successors()
+                    .map(|succ| {
+                        if Some(succ) == *target {
+                            (succ, state.clone())
+                        } else {
+                            (succ, unwind_state.clone())
+                        }
+                    })
+                    .collect()
+            }
+            mir::TerminatorKind::Drop { .. } => {
+                self.merge_address_taken_locals(&mut state);
+                terminator.successors().map(|succ| (succ, state.clone())).collect()
+            }
+            mir::TerminatorKind::InlineAsm { .. } => {
+                // The inline assembly may write any place of the function.
+                for (ptr, pts) in self.fi_pts {
+                    if self.is_tracked(ptr) {
+                        state.entry(ptr.clone()).or_default().extend(pts.iter().cloned());
+                    }
+                }
+                terminator.successors().map(|succ| (succ, state.clone())).collect()
+            }
+            _ => terminator.successors().map(|succ| (succ, state.clone())).collect(),
+        }
+    }
+
+    fn transfer_assign(&self, state: &mut PointsToState, place: &mir::Place<'tcx>, rvalue: &mir::Rvalue<'tcx>) {
+        let place_ty = self.place_ty(place);
+        let projs = self.pointer_projections(place_ty);
+        if projs.is_empty() {
+            return;
+        }
+        let (dests, strong) = self.eval_assigned_place(state, place);
+        let values = projs
+            .into_iter()
+            .map(|proj| {
+                let pts = self.eval_rvalue(state, rvalue, place_ty, &dests, &proj);
+                (proj, pts)
+            })
+            .collect();
+        self.assign(state, &dests, strong, values);
+    }
+
+    /// Returns the points-to set of the pointer at `proj` of the value of the rvalue, which is
+    /// assigned to a place of type `ty`.
+    fn eval_rvalue(
+        &self,
+        state: &PointsToState,
+        rvalue: &mir::Rvalue<'tcx>,
+        ty: Ty<'tcx>,
+        dests: &HashSet<Rc<Path>>,
+        proj: &ProjectionElems,
+    ) -> HashSet<Rc<Path>> {
+        match rvalue {
+            mir::Rvalue::Use(operand) | mir::Rvalue::ShallowInitBox(operand, _) => {
+                self.eval_operand(state, operand, dests, proj)
+            }
+            mir::Rvalue::CopyForDeref(place) => self.eval_operand(state, &mir::Operand::Copy(*place), dests, proj),
+            mir::Rvalue::Cast(_, operand, _) if proj.is_empty() => self.eval_operand(state, operand, dests, proj),
+            mir::Rvalue::Ref(_, _, place) | mir::Rvalue::AddressOf(_, place) if proj.is_empty() => {
+                self.eval_place(state, place).0
+            }
+            mir::Rvalue::Aggregate(box kind, operands) => {
+                let mut pts = HashSet::new();
+                for (i, operand) in operands.iter_enumerated() {
+                    let prefix = self.aggregate_field_projection(kind, ty, i.as_usize());
+                    if proj.starts_with(&prefix) {
+                        let rest = proj[prefix.len()..].to_vec();
+                        pts.extend(self.eval_operand(state, operand, dests, &rest));
+                    }
+                }
+                pts
+            }
+            mir::Rvalue::Repeat(operand, _) => match proj.first() {
+                Some(PathSelector::Index) | Some(PathSelector::ConstantIndex(..)) => {
+                    self.eval_operand(state, operand, dests, &proj[1..].to_vec())
+                }
+                _ => HashSet::new(),
+            },
+            _ => self.fi_value(dests, proj),
+        }
+    }
+
+    fn eval_operand(
+        &self,
+        state: &PointsToState,
+        operand: &mir::Operand<'tcx>,
+        dests: &HashSet<Rc<Path>>,
+        proj: &ProjectionElems,
+    ) -> HashSet<Rc<Path>> {
+        match operand {
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                let (srcs, _) = self.eval_place(state, place);
+                let mut pts = HashSet::new();
+                for src in srcs {
+                    pts.extend(self.pts_of(state, &Path::append_projection(&src, proj)));
+                }
+                pts
+            }
+            // The pointers in constants are given by the flow-insensitive result.
+            mir::Operand::Constant(..) => self.fi_value(dests, proj),
+        }
+    }
+
+    /// Returns the memory locations denoted by the place, and whether the place is accessed
+    /// through a dereference.
+    fn eval_place(&self, state: &PointsToState, place: &mir::Place<'tcx>) -> (HashSet<Rc<Path>>, bool) {
+        let base = Path::new_local_parameter_or_result(self.func_id, place.local.as_usize(), self.mir.arg_count);
+        let mut locations = HashSet::from([base]);
+        let mut has_deref = false;
+        let mut place_ty = PlaceTy::from_ty(self.mir.local_decls[place.local].ty);
+        for elem in place.projection.iter() {
+            match elem {
+                mir::ProjectionElem::Deref => {
+                    has_deref = true;
+                    let box_ptr_proj = vec![PathSelector::Field(0), PathSelector::Field(0), PathSelector::Field(0)];
+                    let mut pointees = HashSet::new();
+                    for location in &locations {
+                        let ptr = if place_ty.ty.is_box() {
+                            Path::append_projection(location, &box_ptr_proj)
+                        } else {
+                            location.clone()
+                        };
+                        pointees.extend(self.pts_of(state, &ptr));
+                    }
+                    locations = pointees;
+                }
+                _ => {
+                    if let Some(selector) = self.projection_selector(place_ty.ty, &elem) {
+                        locations = locations
+                            .iter()
+                            .map(|location| Path::append_projection_elem(location, selector))
+                            .collect();
+                    }
+                }
+            }
+            place_ty = place_ty.projection_ty(self.tcx(), elem);
+        }
+        (locations, has_deref)
+    }
+
+    /// Returns the locations assigned by the place, and whether they can be strongly updated.
+    fn eval_assigned_place(&self, state: &PointsToState, place: &mir::Place<'tcx>) -> (HashSet<Rc<Path>>, bool) {
+        let (dests, has_deref) = self.eval_place(state, place);
+        // A local object pointed to by a single pointer is a singleton in the function's activation.
+        let strong = !has_deref || (dests.len() == 1 && dests.iter().all(|dest| self.is_tracked(dest)));
+        (dests, strong)
+    }
+
+    fn assign(
+        &self,
+        state: &mut PointsToState,
+        dests: &HashSet<Rc<Path>>,
+        strong: bool,
+        values: Vec<(ProjectionElems, HashSet<Rc<Path>>)>,
+    ) {
+        for dest in dests {
+            for (proj, pts) in &values {
+                let ptr = Path::append_projection(dest, proj);
+                if !self.is_tracked(&ptr) {
+                    continue;
+                }
+                // The paths referring to the same array elements are weakly updated.
+                for variant in self.index_variants(&ptr) {
+                    if self.is_tracked(&variant) {
+                        state.entry(variant).or_default().extend(pts.iter().cloned());
+                    }
+                }
+                if strong && !is_summary(&ptr) {
+                    state.insert(ptr, pts.clone());
+                } else {
+                    state.entry(ptr).or_default().extend(pts.iter().cloned());
+                }
+            }
+        }
+    }
+
+    /// Merges the flow-insensitive points-to sets of the locals whose addresses are taken,
+    /// as they may be modified through the pointers to them.
+    fn merge_address_taken_locals(&self, state: &mut PointsToState) {
+        for (ptr, pts) in self.fi_pts {
+            match local_of(ptr) {
+                Some((func_id, ordinal)) if func_id == self.func_id && self.address_taken_locals.contains(&ordinal) => {
+                    if self.is_tracked(ptr) {
+                        state.entry(ptr.clone()).or_default().extend(pts.iter().cloned());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the points-to set of the pointer at `proj` of the destinations given by the
+    /// flow-insensitive result.
+    fn fi_value(&self, dests: &HashSet<Rc<Path>>, proj: &ProjectionElems) -> HashSet<Rc<Path>> {
+        let mut pts = HashSet::new();
+        for dest in dests {
+            if let Some(fi_pts) = self.fi_pts.get(&Path::append_projection(dest, proj)) {
+                pts.extend(fi_pts.iter().cloned());
+            }
+        }
+        pts
+    }
+
+    /// Returns the points-to set of the pointer. The pointers rooted at the locals of the function
+    /// are tracked flow-sensitively, the others are given by the flow-insensitive result.
+    fn pts_of(&self, state: &PointsToState, ptr: &Rc<Path>) -> HashSet<Rc<Path>> {
+        if !self.is_tracked(ptr) {
+            return self.fi_pts.get(ptr).cloned().unwrap_or_default();
+        }
+        let mut pts = state.get(ptr).cloned().unwrap_or_default();
+        // An element accessed with a non-constant index may be any of the constant-index elements.
+        if is_summary(ptr) {
+            for variant in self.index_variants(ptr) {
+                if let Some(variant_pts) = state.get(&variant) {
+                    pts.extend(variant_pts.iter().cloned());
+                }
+            }
+        }
+        pts
+    }
+
+    fn is_tracked(&self, path: &Rc<Path>) -> bool {
+        match local_of(path) {
+            Some((func_id, ordinal)) if func_id == self.func_id && !self.untracked_locals.contains(&ordinal) => {
+                match &path.value {
+                    PathEnum::QualifiedPath { projection, .. } => projection.iter().all(is_tracked_selector),
+                    _ => true,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the paths that refer to the same array elements as the tracked path, the same as
+    /// `AnalysisContext::get_index_variants`, i.e. the summarizing element of a constant-index
+    /// element and the constant-index elements of a summarizing element.
+    fn index_variants(&self, path: &Rc<Path>) -> Vec<Rc<Path>> {
+        if self.acx.analysis_options.index_sensitive_arrays.is_none() {
+            return Vec::new();
+        }
+        let (PathEnum::QualifiedPath { base, projection }, Some((_, ordinal))) = (&path.value, local_of(path)) else {
+            return Vec::new();
+        };
+        if !projection
+            .iter()
+            .any(|selector| matches!(selector, PathSelector::Index | PathSelector::ConstantIndex(..)))
+        {
+            return Vec::new();
+        }
+
+        let tcx = self.tcx();
+        let mut ty = self
+            .substs_specializer
+            .specialize_generic_argument_type(self.mir.local_decls[mir::Local::from_usize(ordinal)].ty);
+        let mut variants: Vec<ProjectionElems> = vec![Vec::new()];
+        for selector in projection {
+            let alternatives = match selector {
+                PathSelector::ConstantIndex(..) => vec![*selector, PathSelector::Index],
+                PathSelector::Index => {
+                    let mut alternatives = vec![PathSelector::Index];
+                    if let Some(len) = self.acx.index_sensitive_array_len(ty) {
+                        alternatives.extend((0..len).map(PathSelector::ConstantIndex));
+                    }
+                    alternatives
+                }
+                _ => vec![*selector],
+            };
+            ty = match selector {
+                PathSelector::Field(ordinal) | PathSelector::UnionField(ordinal) => {
+                    type_util::get_field_type(tcx, ty, *ordinal)
+                }
+                PathSelector::Downcast(variant_idx) => type_util::get_downcast_type(tcx, ty, (*variant_idx).into()),
+                PathSelector::Index | PathSelector::ConstantIndex(..) => type_util::get_element_type(tcx, ty),
+                _ => return Vec::new(),
+            };
+            variants = variants
+                .into_iter()
+                .flat_map(|variant| {
+                    alternatives.iter().map(move |selector| {
+                        let mut variant = variant.clone();
+                        variant.push(*selector);
+                        variant
+                    })
+                })
+                .collect();
+        }
+        variants
+            .into_iter()
+            .filter(|variant| variant != projection)
+            .map(|variant| Path::new_qualified(base.clone(), variant))
+            .collect()
+    }
+
+    fn base_value<'p>(&self, path: &'p Rc<Path>) -> &'p PathEnum {
+        match &path.value {
+            PathEnum::QualifiedPath { base, .. } => &base.value,
+            value => value,
+        }
+    }
+
+    fn place_ty(&self, place: &mir::Place<'tcx>) -> Ty<'tcx> {
+        self.substs_specializer
+            .specialize_generic_argument_type(place.ty(self.mir, self.tcx()).ty)
+    }
+
+    /// Returns the projections to the pointers contained in a value of the type.
+    fn pointer_projections(&self, ty: Ty<'tcx>) -> Vec<ProjectionElems> {
+        if ty.is_any_ptr() {
+            return vec![vec![]];
+        }
+        type_util::get_pointer_projections(self.tcx(), ty, self.acx.analysis_options.index_sensitive_arrays)
+            .into_iter()
+            .map(|(proj, _)| proj)
+            .collect()
+    }
+
+    /// Returns the selector of the projection element, the same as the one used by the PAG builder.
+    fn projection_selector(&self, base_ty: Ty<'tcx>, elem: &mir::PlaceElem<'tcx>) -> Option<PathSelector> {
+        let base_ty = self.substs_specializer.specialize_generic_argument_type(base_ty);
+        match elem {
+            mir::ProjectionElem::Field(field, _) => {
+                if base_ty.is_union() {
+                    Some(PathSelector::UnionField(field.index()))
+                } else {
+                    Some(PathSelector::Field(field.index()))
+                }
+            }
+            mir::ProjectionElem::Index(local) => {
+                match (self.acx.index_sensitive_array_len(base_ty), self.constant_index_locals.get(local)) {
+                    (Some(len), Some(index)) if *index < len => Some(PathSelector::ConstantIndex(*index)),
+                    _ => Some(PathSelector::Index),
+                }
+            }
+            mir::ProjectionElem::ConstantIndex { offset, from_end, .. } => {
+                match self.acx.index_sensitive_array_len(base_ty) {
+                    Some(len) if !*from_end && (*offset as usize) < len => {
+                        Some(PathSelector::ConstantIndex(*offset as usize))
+                    }
+                    Some(len) if *from_end && (*offset as usize) <= len && *offset > 0 => {
+                        Some(PathSelector::ConstantIndex(len - *offset as usize))
+                    }
+                    _ => Some(PathSelector::Index),
+                }
+            }
+            mir::ProjectionElem::Downcast(_, variant_idx) => Some(PathSelector::Downcast(variant_idx.as_usize())),
+            mir::ProjectionElem::Deref
+            | mir::ProjectionElem::Subslice { .. }
+            | mir::ProjectionElem::OpaqueCast(..)
+            | mir::ProjectionElem::Subtype(..) => None,
+        }
+    }
+
+    /// Returns the projection to the i-th operand of an aggregate of type `ty`.
+    fn aggregate_field_projection(&self, kind: &mir::AggregateKind<'tcx>, ty: Ty<'tcx>, i: usize) -> ProjectionElems {
+        match kind {
+            mir::AggregateKind::Adt(def_id, variant_idx, _, _, active_field) => {
+                let adt_def = self.tcx().adt_def(*def_id);
+                if adt_def.is_enum() {
+                    vec![PathSelector::Downcast(variant_idx.as_usize()), PathSelector::Field(i)]
+                } else if adt_def.is_union() {
+                    vec![PathSelector::UnionField(active_field.map_or(i, |field| field.index()))]
+                } else {
+                    vec![PathSelector::Field(i)]
+                }
+            }
+            mir::AggregateKind::Array(_) => {
+                // The same as the PAG builder, which decides with the length of the assigned array.
+                if self.acx.index_sensitive_array_len(ty).is_some() {
+                    vec![PathSelector::ConstantIndex(i)]
+                } else {
+                    vec![PathSelector::Index]
+                }
+            }
+            _ => vec![PathSelector::Field(i)],
+        }
+    }
+}
+
+/// Joins `other` into `state`, returns true if `state` is changed.
+fn join(state: &mut PointsToState, other: PointsToState) -> bool {
+    let mut changed = false;
+    for (ptr, pts) in other {
+        let entry = state.entry(ptr).or_default();
+        for pointee in pts {
+            changed |= entry.insert(pointee);
+        }
+    }
+    changed
+}
+
+fn non_empty(state: &PointsToState) -> PointsToState {
+    state.iter().filter(|(_, pts)| !pts.is_empty()).map(|(ptr, pts)| (ptr.clone(), pts.clone())).collect()
+}
+
+/// Returns the function and the ordinal of the MIR local that the path is rooted at.
+fn local_of(path: &Rc<Path>) -> Option<(FuncId, usize)> {
+    match &path.value {
+        PathEnum::LocalVariable { func_id, ordinal } | PathEnum::Parameter { func_id, ordinal } => {
+            Some((*func_id, *ordinal))
+        }
+        PathEnum::ReturnValue { func_id } => Some((*func_id, 0)),
+        PathEnum::QualifiedPath { base, .. } => local_of(base),
+        _ => None,
+    }
+}
+
+fn is_tracked_selector(selector: &PathSelector) -> bool {
+    matches!(
+        selector,
+        PathSelector::Field(..)
+            | PathSelector::UnionField(..)
+            | PathSelector::Downcast(..)
+            | PathSelector::Index
+            | PathSelector::ConstantIndex(..)
+    )
+}
+
+/// An element accessed with a non-constant index summarizes all the elements.
+fn is_summary(path: &Rc<Path>) -> bool {
+    match &path.value {
+        PathEnum::QualifiedPath { projection, .. } => projection.contains(&PathSelector::Index),
+        _ => false,
+    }
+}
+
+pub fn dump_flow_sensitive_pts(acx: &AnalysisContext, results: &[FlowSensitivePointsTo], fs_pts_path: &String) {
+    let mut pts_writer = BufWriter::new(match &fs_pts_path[..] {
+        "stdout" => Box::new(std::io::stdout()) as Box<dyn Write>,
+        _ => Box::new(File::create(fs_pts_path).expect("Unable to create file")) as Box<dyn Write>,
+    });
+    for result in results {
+        pts_writer
+            .write_all(
+                format!("{:?} - {:?}\n", result.func_id, acx.get_function_reference(result.func_id).to_string())
+                    .as_bytes(),
+            )
+            .expect("Unable to write data");
+        let locations: BTreeMap<mir::Location, &PointsToState> =
+            result.pts_before.iter().map(|(location, state)| (*location, state)).collect();
+        for (location, state) in locations {
+            pts_writer
+                .write_all(format!("\t{:?}:\n", location).as_bytes())
+                .expect("Unable to write data");
+            let ptrs: BTreeMap<String, BTreeMap<String, ()>> = state
+                .iter()
+                .map(|(ptr, pts)| {
+                    (format!("{:?}", ptr), pts.iter().map(|pointee| (format!("{:?}", pointee), ())).collect())
+                })
+                .collect();
+            for (ptr, pts) in ptrs {
+                pts_writer
+                    .write_all(format!("\t\t{} ({:?}) ==> {{ ", ptr, pts.len()).as_bytes())
+                    .expect("Unable to write data");
+                for pointee in pts.keys() {
+                    pts_writer
+                        .write_all(format!("{} ", pointee).as_bytes())
+                        .expect("Unable to write data");
+                }
+                pts_writer
+                    .write_all("}\n".as_bytes())
+                    .expect("Unable to write data");
+            }
+        }
+    }
+}
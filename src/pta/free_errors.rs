@@ -0,0 +1,623 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! A use-after-free and double-free detector based on the points-to results.
+//!
+//! The heap objects are freed by the deallocation functions handled by `special_function_handler`,
+//! i.e. `__rust_dealloc`, `std::alloc::dealloc`, `box_free`, `Allocator::deallocate` and `free`,
+//! and by the drops of `Box` values, which are not calls in the call graph. A free in a function of
+//! another crate is attributed to the callsites of the local crate reaching it over the call graph,
+//! e.g. a call of `std::mem::drop`, and the objects it frees are narrowed down to the objects
+//! reachable from the arguments of these callsites.
+//!
+//! A load or store through a pointer in the local crate whose pointees contain a freed object is
+//! reported as a possible use after free if it may be executed after the free, and a free of an
+//! object freed before as a possible double free. The code executed after a free is given by the
+//! intraprocedural CFG of the function freeing the object and the call graph. Within that function,
+//! the pointer must be held by a local that may be assigned before the free, so that the objects
+//! allocated again in a loop are not reported. The drops of the boxes moved out before, either on
+//! all the paths from the entry or on the paths from the free guarded by their drop flags, are not
+//! frees.
+
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use log::*;
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir;
+use rustc_span::Span;
+
+use crate::builder::substs_specializer::SubstsSpecializer;
+use crate::graph::call_graph::{CGCallSite, CGFunction, CallGraph};
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::call_site::BaseCallSite;
+use crate::mir::function::FuncId;
+use crate::mir::known_names::KnownNames;
+use crate::mir::path::{Path, PathEnum};
+use crate::pta::control_flow::InterproceduralCFG;
+use crate::pta::escape_analysis::ObjectGraph;
+use crate::pta::mod_ref;
+use crate::pta::points_to_view::{is_write_access, PointsToView};
+use crate::pta::DiffPTDataTy;
+use crate::util::type_util::PointerProjectionsCache;
+
+/// The format of the free errors dumped by `--dump-free-errors`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FreeErrorsFormat {
+    Json,
+    Sarif,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FreeErrorKind {
+    UseAfterFree,
+    DoubleFree,
+}
+
+impl FreeErrorKind {
+    fn rule_id(&self) -> &'static str {
+        match self {
+            FreeErrorKind::UseAfterFree => "use-after-free",
+            FreeErrorKind::DoubleFree => "double-free",
+        }
+    }
+}
+
+/// A use or a free of a heap object that may have been freed before.
+#[derive(Debug)]
+pub struct FreeError {
+    pub kind: FreeErrorKind,
+    pub object: Rc<Path>,
+    /// The function and the location of the use or of the second free.
+    pub func: FuncId,
+    pub location: mir::Location,
+    /// The callsite freeing the object before.
+    pub free_site: BaseCallSite,
+}
+
+/// A callsite or a drop in the local crate that may free the heap objects.
+struct FreeSite<P> {
+    callsite: BaseCallSite,
+    /// The arguments of the callsite the freed objects are reachable from.
+    ptr_locals: Vec<mir::Local>,
+    objects: HashSet<P>,
+}
+
+/// An access through a pointer in the local crate to the heap objects.
+struct HeapAccess<P> {
+    func: FuncId,
+    location: mir::Location,
+    local: mir::Local,
+    objects: HashSet<P>,
+}
+
+pub struct FreeErrorDetector<'a, 'tcx, 'compilation, P: PAGPath> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    cfg: InterproceduralCFG<'a, 'tcx, 'compilation>,
+    object_graph: ObjectGraph<P>,
+    points_to: PointsToView<P>,
+    ptr_projs_cache: RefCell<PointerProjectionsCache<'tcx>>,
+}
+
+/// Detects the uses after free and the double frees of the heap objects and dumps them if
+/// `--dump-free-errors` is set.
+pub fn detect_free_errors<P, F, S>(
+    acx: &AnalysisContext,
+    call_graph: &CallGraph<F, S>,
+    pt_data: &DiffPTDataTy,
+    pag: &PAG<P>,
+) -> Vec<FreeError>
+where
+    P: PAGPath,
+    F: CGFunction + Into<FuncId>,
+    S: CGCallSite + Into<BaseCallSite>,
+{
+    let Some(free_errors_output) = &acx.analysis_options.free_errors_output else {
+        return Vec::new();
+    };
+    let detector = FreeErrorDetector {
+        acx,
+        cfg: InterproceduralCFG::new(acx, call_graph),
+        object_graph: ObjectGraph::new(pt_data, pag),
+        points_to: PointsToView::new(pt_data, pag, |path| path.clone()),
+        ptr_projs_cache: RefCell::new(PointerProjectionsCache::new()),
+    };
+
+    let mut reach_funcs: Vec<FuncId> = call_graph.reach_funcs_iter().map(|func| func.into()).collect();
+    reach_funcs.sort();
+    reach_funcs.dedup();
+    let free_sites = detector.collect_free_sites(pag, &reach_funcs);
+    let accesses = detector.collect_heap_accesses(&reach_funcs);
+    let errors = detector.find_free_errors(&free_sites, &accesses);
+    info!("Dumping free errors...");
+    detector.dump_free_errors(&errors, free_errors_output);
+    errors
+}
+
+impl<'a, 'tcx, 'compilation, P: PAGPath> FreeErrorDetector<'a, 'tcx, 'compilation, P> {
+    /// Collects the frees of the heap objects, and attributes them to the callsites of the local crate.
+    fn collect_free_sites(&self, pag: &PAG<P>, reach_funcs: &[FuncId]) -> Vec<FreeSite<P>> {
+        let mut frees: Vec<(BaseCallSite, HashSet<P>)> = Vec::new();
+        for func in reach_funcs {
+            if let Some(fpag) = pag.get_func_pag(func) {
+                for (callsite, callee) in &fpag.special_callsites {
+                    let callee_def_id = self.acx.get_function_reference(*callee).def_id;
+                    let ptr_index = match self.cfg.known_name(callee_def_id) {
+                        KnownNames::RustDealloc
+                        | KnownNames::StdAllocDealloc
+                        | KnownNames::StdAllocBoxFree
+                        | KnownNames::LibcFree => 0,
+                        // fn deallocate(&self, ptr: NonNull<u8>, layout: Layout)
+                        KnownNames::StdAllocAllocatorDeallocate => 1,
+                        _ => continue,
+                    };
+                    if let Some(ptr) = callsite.args.get(ptr_index) {
+                        frees.push((BaseCallSite::new(*func, callsite.location), self.heap_pointees(ptr)));
+                    }
+                }
+            }
+            let Some(mir) = self.cfg.func_mir(*func) else {
+                continue;
+            };
+            let func_ref = self.acx.get_function_reference(*func);
+            let substs_specializer = SubstsSpecializer::new(self.acx.tcx, func_ref.generic_args.clone());
+            for (block, data) in mir.basic_blocks.iter_enumerated() {
+                // The drops on the unwinding paths are skipped, which are mostly guarded by the
+                // drop flags of the values moved before.
+                if data.is_cleanup {
+                    continue;
+                }
+                let mir::TerminatorKind::Drop { place, .. } = &data.terminator().kind else {
+                    continue;
+                };
+                let ty = substs_specializer.specialize_generic_argument_type(mir.local_decls[place.local].ty);
+                let location = mir.terminator_loc(block);
+                if place.projection.is_empty()
+                    && ty.is_box()
+                    && !self.cfg.move_state_at(*func, mir, location).is_moved(place.local)
+                {
+                    let local = Path::new_local_parameter_or_result(*func, place.local.as_usize(), mir.arg_count);
+                    frees.push((BaseCallSite::new(*func, location), self.heap_pointees(&local)));
+                }
+            }
+        }
+
+        let mut free_sites: HashMap<BaseCallSite, FreeSite<P>> = HashMap::new();
+        for (callsite, objects) in frees {
+            if objects.is_empty() {
+                continue;
+            }
+            for local_callsite in self.local_callsites(callsite) {
+                let Some(mir) = self.cfg.func_mir(local_callsite.func) else {
+                    continue;
+                };
+                for local in callsite_arg_locals(mir, local_callsite.location) {
+                    let reachable = self
+                        .object_graph
+                        .reachable_from(self.points_to.local_objects(local_callsite.func, mir, local));
+                    let freed: Vec<&P> = objects.iter().filter(|object| reachable.contains(*object)).collect();
+                    if freed.is_empty() {
+                        continue;
+                    }
+                    let free_site = free_sites.entry(local_callsite).or_insert_with(|| FreeSite {
+                        callsite: local_callsite,
+                        ptr_locals: Vec::new(),
+                        objects: HashSet::new(),
+                    });
+                    if !free_site.ptr_locals.contains(&local) {
+                        free_site.ptr_locals.push(local);
+                    }
+                    free_site.objects.extend(freed.into_iter().cloned());
+                }
+            }
+        }
+        let mut free_sites: Vec<FreeSite<P>> = free_sites.into_values().collect();
+        free_sites.sort_by_key(|free_site| (free_site.callsite.func, free_site.callsite.location));
+        free_sites
+    }
+
+    /// Returns the heap objects pointed to by the pointers rooted at the path's base.
+    fn heap_pointees(&self, path: &Rc<Path>) -> HashSet<P> {
+        self.points_to
+            .variants(&path.base_path())
+            .flat_map(|variant| self.object_graph.successors(variant))
+            .filter(|object| matches!(object.value(), PathEnum::HeapObj { .. }))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the locals of the function whose types may contain pointers.
+    fn pointer_locals(&self, func: FuncId, mir: &mir::Body<'tcx>) -> HashSet<mir::Local> {
+        let tcx = self.acx.tcx;
+        let func_ref = self.acx.get_function_reference(func);
+        let substs_specializer = SubstsSpecializer::new(tcx, func_ref.generic_args.clone());
+        let mut ptr_projs_cache = self.ptr_projs_cache.borrow_mut();
+        mir.local_decls
+            .iter_enumerated()
+            .filter(|(_, decl)| {
+                let ty = substs_specializer.specialize_generic_argument_type(decl.ty);
+                ty.is_any_ptr()
+                    || !ptr_projs_cache
+                        .get_pointer_projections(tcx, ty, self.acx.analysis_options.index_sensitive_arrays)
+                        .is_empty()
+            })
+            .map(|(local, _)| local)
+            .collect()
+    }
+
+    fn is_local_func(&self, func: FuncId) -> bool {
+        self.acx.get_function_reference(func).def_id.is_local()
+    }
+
+    /// Returns the callsites in the functions of the local crate from which the callsite is
+    /// reached, which is the callsite itself if it is in the local crate.
+    fn local_callsites(&self, callsite: BaseCallSite) -> Vec<BaseCallSite> {
+        let mut local_callsites = Vec::new();
+        let mut visited: HashSet<BaseCallSite> = HashSet::new();
+        let mut worklist = vec![callsite];
+        while let Some(callsite) = worklist.pop() {
+            if !visited.insert(callsite) {
+                continue;
+            }
+            if self.is_local_func(callsite.func) {
+                local_callsites.push(callsite);
+            } else {
+                worklist.extend(self.cfg.callers.get(&callsite.func).into_iter().flatten());
+            }
+        }
+        local_callsites
+    }
+
+    /// Collects the loads and stores through the pointers of the local crate that may access
+    /// the heap objects.
+    fn collect_heap_accesses(&self, reach_funcs: &[FuncId]) -> Vec<HeapAccess<P>> {
+        let mut accesses = Vec::new();
+        for func in reach_funcs {
+            if !self.is_local_func(*func) {
+                continue;
+            }
+            let Some(mir) = self.cfg.func_mir(*func) else {
+                continue;
+            };
+            let mut collector = AccessCollector {
+                detector: self,
+                func: *func,
+                mir,
+                accesses: &mut accesses,
+            };
+            collector.visit_body(mir);
+        }
+        accesses
+    }
+
+    /// Returns the heap objects that may be denoted by the place of the function's MIR.
+    fn place_heap_objects(&self, func: FuncId, mir: &mir::Body<'tcx>, place: &mir::Place<'tcx>) -> HashSet<P> {
+        self.points_to
+            .place_objects(self.acx, func, mir, place)
+            .iter()
+            .map(|object| object.base_path())
+            .filter(|object| matches!(object.value(), PathEnum::HeapObj { .. }))
+            .collect()
+    }
+
+    /// Finds the accesses and the frees of the objects that may be executed after the free sites
+    /// freeing them.
+    fn find_free_errors(&self, free_sites: &[FreeSite<P>], accesses: &[HeapAccess<P>]) -> Vec<FreeError> {
+        let mut errors = Vec::new();
+        for free_site in free_sites {
+            let Some(mir) = self.cfg.func_mir(free_site.callsite.func) else {
+                continue;
+            };
+            let region = self.cfg.code_after(free_site.callsite, |_, _, _| false);
+            let pointer_locals = self.pointer_locals(free_site.callsite.func, mir);
+            let stale_locals = StaleLocals::new(mir, free_site.callsite.location, pointer_locals);
+            let is_after_free = |func: FuncId, location: mir::Location, locals: &[mir::Local]| {
+                if func != free_site.callsite.func || region.funcs.contains(&func) {
+                    return region.contains(func, location.block);
+                }
+                region.contains(func, location.block)
+                    && locals.iter().any(|local| stale_locals.is_stale_at(location, *local))
+            };
+            for access in accesses {
+                if access.objects.is_disjoint(&free_site.objects)
+                    || !is_after_free(access.func, access.location, &[access.local])
+                {
+                    continue;
+                }
+                errors.push(self.free_error(FreeErrorKind::UseAfterFree, &access.objects, free_site, access.func, access.location));
+            }
+            for other in free_sites {
+                let callsite = other.callsite;
+                if other.objects.is_disjoint(&free_site.objects)
+                    || !is_after_free(callsite.func, callsite.location, &other.ptr_locals)
+                {
+                    continue;
+                }
+                errors.push(self.free_error(FreeErrorKind::DoubleFree, &other.objects, free_site, callsite.func, callsite.location));
+            }
+        }
+        errors
+    }
+
+    /// Creates the error on the object freed by the free site, which is the first object in the
+    /// order of the descriptions if several objects are freed.
+    fn free_error(
+        &self,
+        kind: FreeErrorKind,
+        objects: &HashSet<P>,
+        free_site: &FreeSite<P>,
+        func: FuncId,
+        location: mir::Location,
+    ) -> FreeError {
+        let object = objects
+            .intersection(&free_site.objects)
+            .map(|object| object.ci_path())
+            .min_by_key(|object| format!("{:?}", object))
+            .unwrap();
+        FreeError { kind, object, func, location, free_site: free_site.callsite }
+    }
+
+    fn span(&self, func: FuncId, location: mir::Location) -> Span {
+        let def_id = self.acx.get_function_reference(func).def_id;
+        self.acx.tcx.optimized_mir(def_id).source_info(location).span
+    }
+
+    fn json_location(&self, func: FuncId, location: mir::Location) -> serde_json::Value {
+        serde_json::json!({
+            "function": self.acx.get_function_reference(func).to_string(),
+            "span": self.acx.tcx.sess.source_map().span_to_embeddable_string(self.span(func, location)),
+        })
+    }
+
+    fn sarif_location(&self, func: FuncId, location: mir::Location) -> serde_json::Value {
+        let source_map = self.acx.tcx.sess.source_map();
+        let span = self.span(func, location);
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+        serde_json::json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": lo.file.name.prefer_local().to_string() },
+                "region": {
+                    "startLine": lo.line,
+                    "startColumn": lo.col.0 + 1,
+                    "endLine": hi.line,
+                    "endColumn": hi.col.0 + 1,
+                },
+            },
+            "logicalLocations": [{ "fullyQualifiedName": self.acx.get_function_reference(func).to_string() }],
+        })
+    }
+
+    /// Dumps the free errors in JSON or SARIF format, sorted by their locations.
+    fn dump_free_errors(&self, errors: &[FreeError], free_errors_path: &String) {
+        let mut results: Vec<(serde_json::Value, serde_json::Value)> = Vec::new();
+        for error in errors {
+            let object = mod_ref::object_description(self.acx, &error.object);
+            let free_site = error.free_site;
+            let entry = serde_json::json!({
+                "kind": error.kind.rule_id(),
+                "object": object,
+                "location": self.json_location(error.func, error.location),
+                "free_location": self.json_location(free_site.func, free_site.location),
+            });
+            let message = match error.kind {
+                FreeErrorKind::UseAfterFree => format!("Possible use after free of {}, which may be [freed](1) before.", object),
+                FreeErrorKind::DoubleFree => format!("Possible double free of {}, which may be [freed](1) before.", object),
+            };
+            let mut related_location = self.sarif_location(free_site.func, free_site.location);
+            related_location["id"] = serde_json::json!(1);
+            related_location["message"] = serde_json::json!({ "text": "freed here" });
+            let result = serde_json::json!({
+                "ruleId": error.kind.rule_id(),
+                "level": "warning",
+                "message": { "text": message },
+                "locations": [self.sarif_location(error.func, error.location)],
+                "relatedLocations": [related_location],
+            });
+            results.push((entry, result));
+        }
+        results.sort_by_key(|(entry, _)| {
+            (
+                entry["location"]["span"].to_string(),
+                entry["kind"].to_string(),
+                entry["free_location"]["span"].to_string(),
+                entry["object"].to_string(),
+            )
+        });
+        results.dedup_by(|(a, _), (b, _)| a == b);
+
+        let output = match self.acx.analysis_options.free_errors_format {
+            FreeErrorsFormat::Json => {
+                serde_json::Value::Array(results.into_iter().map(|(entry, _)| entry).collect())
+            }
+            FreeErrorsFormat::Sarif => {
+                let rules: Vec<serde_json::Value> = [FreeErrorKind::UseAfterFree, FreeErrorKind::DoubleFree]
+                    .iter()
+                    .map(|kind| {
+                        serde_json::json!({
+                            "id": kind.rule_id(),
+                            "shortDescription": { "text": match kind {
+                                FreeErrorKind::UseAfterFree => "A load or store through a pointer to a freed heap object.",
+                                FreeErrorKind::DoubleFree => "A deallocation of a freed heap object.",
+                            } },
+                        })
+                    })
+                    .collect();
+                serde_json::json!({
+                    "version": "2.1.0",
+                    "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+                    "runs": [{
+                        "tool": { "driver": { "name": "rupta", "rules": rules } },
+                        "results": results.into_iter().map(|(_, result)| result).collect::<Vec<_>>(),
+                    }],
+                })
+            }
+        };
+        let output = serde_json::to_string_pretty(&output).expect("Unable to serialize data");
+        match &free_errors_path[..] {
+            "stdout" => println!("{}", output),
+            _ => std::fs::write(free_errors_path, output).expect("Unable to write data"),
+        }
+    }
+}
+
+/// Returns the locals passed to the call or dropped at the location.
+fn callsite_arg_locals(mir: &mir::Body, location: mir::Location) -> Vec<mir::Local> {
+    match &mir.basic_blocks[location.block].terminator().kind {
+        mir::TerminatorKind::Call { args, .. } => args
+            .iter()
+            .filter_map(|arg| match &arg.node {
+                mir::Operand::Copy(place) | mir::Operand::Move(place) => Some(place.local),
+                mir::Operand::Constant(..) => None,
+            })
+            .collect(),
+        mir::TerminatorKind::Drop { place, .. } => vec![place.local],
+        _ => Vec::new(),
+    }
+}
+
+/// The locals that may hold the values computed before a call or a drop, at the beginning of
+/// the basic blocks executed after it in the function. A local assigned after the call holds such
+/// a value only if it is computed from the locals holding such values. Only the locals whose types
+/// may contain pointers are considered, e.g. the integers computed from an iterator created before
+/// the call are not.
+struct StaleLocals<'a, 'tcx> {
+    mir: &'a mir::Body<'tcx>,
+    pointer_locals: HashSet<mir::Local>,
+    block_stale_locals: HashMap<mir::BasicBlock, HashSet<mir::Local>>,
+}
+
+impl<'a, 'tcx> StaleLocals<'a, 'tcx> {
+    fn new(mir: &'a mir::Body<'tcx>, location: mir::Location, pointer_locals: HashSet<mir::Local>) -> Self {
+        let mut stale_locals = StaleLocals {
+            mir,
+            pointer_locals,
+            block_stale_locals: HashMap::new(),
+        };
+        let mut worklist = Vec::new();
+        for succ in mir.basic_blocks[location.block].terminator().successors() {
+            stale_locals.block_stale_locals.insert(succ, stale_locals.pointer_locals.clone());
+            worklist.push(succ);
+        }
+        while let Some(block) = worklist.pop() {
+            let data = &mir.basic_blocks[block];
+            let mut stale = stale_locals.block_stale_locals[&block].clone();
+            for statement in &data.statements {
+                stale_locals.transfer(statement, &mut stale);
+            }
+            let terminator = data.terminator();
+            if let mir::TerminatorKind::Call { args, destination, .. } = &terminator.kind {
+                let mut collector = LocalCollector::default();
+                for arg in args {
+                    collector.visit_operand(&arg.node, mir::Location::START);
+                }
+                let from_stale = collector.is_any_stale(&stale);
+                stale_locals.assign(destination, from_stale, &mut stale);
+            }
+            for succ in terminator.successors() {
+                let changed = match stale_locals.block_stale_locals.entry(succ) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(stale.clone());
+                        true
+                    }
+                    Entry::Occupied(mut entry) => {
+                        let len = entry.get().len();
+                        entry.get_mut().extend(stale.iter().copied());
+                        len != entry.get().len()
+                    }
+                };
+                if changed {
+                    worklist.push(succ);
+                }
+            }
+        }
+        stale_locals
+    }
+
+    fn transfer(&self, statement: &mir::Statement<'tcx>, stale: &mut HashSet<mir::Local>) {
+        match &statement.kind {
+            mir::StatementKind::Assign(box (place, rvalue)) => {
+                let mut collector = LocalCollector::default();
+                collector.visit_rvalue(rvalue, mir::Location::START);
+                self.assign(place, collector.is_any_stale(stale), stale);
+            }
+            mir::StatementKind::StorageLive(local) | mir::StatementKind::StorageDead(local) => {
+                stale.remove(local);
+            }
+            _ => {}
+        }
+    }
+
+    fn assign(&self, place: &mir::Place<'tcx>, from_stale: bool, stale: &mut HashSet<mir::Local>) {
+        if place.is_indirect() {
+            return;
+        }
+        if from_stale && self.pointer_locals.contains(&place.local) {
+            stale.insert(place.local);
+        } else if place.projection.is_empty() {
+            stale.remove(&place.local);
+        }
+    }
+
+    /// Returns true if the local may hold a value computed before the call at the given location.
+    fn is_stale_at(&self, location: mir::Location, local: mir::Local) -> bool {
+        let Some(stale) = self.block_stale_locals.get(&location.block) else {
+            return false;
+        };
+        let mut stale = stale.clone();
+        for statement in &self.mir.basic_blocks[location.block].statements[..location.statement_index] {
+            self.transfer(statement, &mut stale);
+        }
+        stale.contains(&local)
+    }
+}
+
+#[derive(Default)]
+struct LocalCollector {
+    locals: Vec<mir::Local>,
+}
+
+impl LocalCollector {
+    fn is_any_stale(&self, stale: &HashSet<mir::Local>) -> bool {
+        self.locals.iter().any(|local| stale.contains(local))
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for LocalCollector {
+    fn visit_local(&mut self, local: mir::Local, _context: PlaceContext, _location: mir::Location) {
+        self.locals.push(local);
+    }
+}
+
+struct AccessCollector<'a, 'b, 'tcx, 'compilation, P: PAGPath> {
+    detector: &'b FreeErrorDetector<'a, 'tcx, 'compilation, P>,
+    func: FuncId,
+    mir: &'tcx mir::Body<'tcx>,
+    accesses: &'b mut Vec<HeapAccess<P>>,
+}
+
+impl<'a, 'b, 'tcx, 'compilation, P: PAGPath> Visitor<'tcx> for AccessCollector<'a, 'b, 'tcx, 'compilation, P> {
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, location: mir::Location) {
+        if !place.is_indirect() {
+            return;
+        }
+        if is_write_access(context).is_none() {
+            return;
+        }
+        let objects = self.detector.place_heap_objects(self.func, self.mir, place);
+        if objects.is_empty() {
+            return;
+        }
+        self.accesses.push(HeapAccess {
+            func: self.func,
+            location,
+            local: place.local,
+            objects,
+        });
+    }
+}
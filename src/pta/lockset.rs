@@ -0,0 +1,217 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! A must-lockset analysis of the `Mutex` and `RwLock` locks held at the basic blocks of a function.
+//!
+//! A lock is acquired by `Mutex::lock`, `RwLock::read` or `RwLock::write` on a receiver pointing to
+//! a single lock object, and released when a guard pointing to it is dropped or moved into a call.
+//! The locks are the objects of a `PointsToView`, i.e. either context-insensitive paths or paths
+//! qualified by contexts.
+//!
+//! An abstract object may stand for many concrete locks, e.g. the mutexes allocated by a function
+//! called twice, and holding one of them does not protect the accesses guarded by another one.
+//! Therefore only the singleton objects are added to the locksets, i.e. the static variables and
+//! the locals and heap objects allocated at most once: outside loops, in functions that are only
+//! called once from the entry points.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use rustc_middle::mir;
+use rustc_middle::ty::{Ty, TyKind};
+
+use crate::api::MemoryObject;
+use crate::graph::pag::PAGPath;
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::function::FuncId;
+use crate::mir::known_names::KnownNames;
+use crate::mir::path::PathEnum;
+use crate::pta::control_flow::InterproceduralCFG;
+use crate::pta::points_to_view::PointsToView;
+
+pub(crate) type LockSet<O> = HashSet<O>;
+
+pub(crate) struct LockSetAnalysis<'a, 'tcx, 'compilation, O> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    cfg: &'a InterproceduralCFG<'a, 'tcx, 'compilation>,
+    points_to: &'a PointsToView<O>,
+}
+
+impl<'a, 'tcx, 'compilation, O: PAGPath> LockSetAnalysis<'a, 'tcx, 'compilation, O> {
+    pub(crate) fn new(
+        acx: &'a AnalysisContext<'tcx, 'compilation>,
+        cfg: &'a InterproceduralCFG<'a, 'tcx, 'compilation>,
+        points_to: &'a PointsToView<O>,
+    ) -> Self {
+        LockSetAnalysis { acx, cfg, points_to }
+    }
+
+    /// Runs the intraprocedural must-lockset analysis on the function, and returns the locksets
+    /// at the beginning of the basic blocks reachable from the entry.
+    pub(crate) fn analyze(
+        &self,
+        func: FuncId,
+        mir: &'tcx mir::Body<'tcx>,
+        entry_lockset: LockSet<O>,
+    ) -> HashMap<mir::BasicBlock, LockSet<O>> {
+        let mut block_locksets: HashMap<mir::BasicBlock, LockSet<O>> = HashMap::new();
+        block_locksets.insert(mir::START_BLOCK, entry_lockset);
+        let mut worklist = vec![mir::START_BLOCK];
+        while let Some(block) = worklist.pop() {
+            let terminator = mir.basic_blocks[block].terminator();
+            let mut lockset = block_locksets[&block].clone();
+            self.transfer(func, mir, terminator, &mut lockset);
+            for succ in terminator.successors() {
+                let changed = match block_locksets.entry(succ) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(lockset.clone());
+                        true
+                    }
+                    Entry::Occupied(mut entry) => {
+                        let len = entry.get().len();
+                        entry.get_mut().retain(|lock| lockset.contains(lock));
+                        len != entry.get().len()
+                    }
+                };
+                if changed {
+                    worklist.push(succ);
+                }
+            }
+        }
+        block_locksets
+    }
+
+    /// Updates the lockset with the locks acquired and released by the terminator.
+    fn transfer(&self, func: FuncId, mir: &'tcx mir::Body<'tcx>, terminator: &mir::Terminator<'tcx>, lockset: &mut LockSet<O>) {
+        match &terminator.kind {
+            mir::TerminatorKind::Call { args, .. } => {
+                for arg in args {
+                    if let mir::Operand::Move(place) = &arg.node {
+                        if place.projection.is_empty() && self.is_lock_guard(mir.local_decls[place.local].ty) {
+                            self.release(func, mir, place.local, lockset);
+                        }
+                    }
+                }
+                let locks = self.acquired_locks(func, mir, terminator);
+                // A lock must be held only if the receiver points to a single singleton lock object.
+                if locks.len() == 1 && locks.iter().all(|lock| self.is_singleton(lock)) {
+                    lockset.extend(locks);
+                }
+            }
+            mir::TerminatorKind::Drop { place, .. } => {
+                if place.projection.is_empty() && self.is_lock_guard(mir.local_decls[place.local].ty) {
+                    self.release(func, mir, place.local, lockset);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the locks that may be acquired by the terminator if it calls `Mutex::lock`,
+    /// `RwLock::read` or `RwLock::write`.
+    pub(crate) fn acquired_locks(
+        &self,
+        func: FuncId,
+        mir: &'tcx mir::Body<'tcx>,
+        terminator: &mir::Terminator<'tcx>,
+    ) -> HashSet<O> {
+        let mir::TerminatorKind::Call { func: callee, args, .. } = &terminator.kind else {
+            return HashSet::new();
+        };
+        let is_lock = |name| matches!(name, KnownNames::StdMutexLock | KnownNames::StdRwLockRead | KnownNames::StdRwLockWrite);
+        if !self.cfg.is_known_call(callee, is_lock) {
+            return HashSet::new();
+        }
+        // fn lock(&self) -> LockResult<MutexGuard<'_, T>>
+        match args.first().map(|arg| &arg.node) {
+            Some(mir::Operand::Copy(receiver) | mir::Operand::Move(receiver)) => {
+                let receiver = receiver.project_deeper(&[mir::ProjectionElem::Deref], self.acx.tcx);
+                self.points_to.place_objects(self.acx, func, mir, &receiver)
+            }
+            _ => HashSet::new(),
+        }
+    }
+
+    /// Returns true if the lock object stands for a single concrete lock.
+    fn is_singleton(&self, lock: &O) -> bool {
+        match lock.base_path().value() {
+            PathEnum::StaticVariable { .. } => true,
+            PathEnum::LocalVariable { func_id, .. } | PathEnum::Parameter { func_id, .. } => {
+                self.runs_at_most_once(*func_id, None, &mut HashSet::new())
+            }
+            PathEnum::HeapObj { func_id, location } => {
+                self.runs_at_most_once(*func_id, Some(*location), &mut HashSet::new())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the function is only called once from the entry points, and the location
+    /// in it, if any, is not in a loop.
+    fn runs_at_most_once(&self, func: FuncId, location: Option<mir::Location>, visited: &mut HashSet<FuncId>) -> bool {
+        if !visited.insert(func) {
+            return false;
+        }
+        if let Some(location) = location {
+            let Some(mir) = self.cfg.func_mir(func) else {
+                return false;
+            };
+            if is_in_loop(mir, location.block) {
+                return false;
+            }
+        }
+        match self.cfg.callers.get(&func) {
+            Some(callsites) if callsites.len() == 1 => {
+                let callsite = callsites.iter().next().unwrap();
+                self.runs_at_most_once(callsite.func, Some(callsite.location), visited)
+            }
+            Some(callsites) if !callsites.is_empty() => false,
+            _ => self.acx.entry_points.contains(&func),
+        }
+    }
+
+    /// Removes the locks pointed to by the guard from the lockset.
+    fn release(&self, func: FuncId, mir: &mir::Body<'tcx>, guard: mir::Local, lockset: &mut LockSet<O>) {
+        // A `RwLock` guard points to the inner lock of the `RwLock`.
+        let pointees: Vec<(O, MemoryObject)> = self
+            .points_to
+            .local_pointees(func, mir, guard)
+            .filter_map(|pointee| Some((pointee.base_path(), MemoryObject::from_path(&pointee.ci_path(), true)?)))
+            .collect();
+        lockset.retain(|lock| match MemoryObject::from_path(&lock.ci_path(), true) {
+            Some(lock_object) => !pointees
+                .iter()
+                .any(|(base, pointee)| *base == lock.base_path() && pointee.may_overlap(&lock_object)),
+            None => true,
+        });
+    }
+
+    fn is_lock_guard(&self, ty: Ty<'tcx>) -> bool {
+        let TyKind::Adt(adt_def, _) = ty.kind() else {
+            return false;
+        };
+        let tcx = self.acx.tcx;
+        tcx.crate_name(adt_def.did().krate).as_str() == "std"
+            && matches!(
+                tcx.item_name(adt_def.did()).as_str(),
+                "MutexGuard" | "RwLockReadGuard" | "RwLockWriteGuard"
+            )
+    }
+}
+
+/// Returns true if the basic block can be reached from itself.
+fn is_in_loop(mir: &mir::Body, block: mir::BasicBlock) -> bool {
+    let mut visited: HashSet<mir::BasicBlock> = HashSet::new();
+    let mut worklist: Vec<mir::BasicBlock> = mir.basic_blocks[block].terminator().successors().collect();
+    while let Some(succ) = worklist.pop() {
+        if succ == block {
+            return true;
+        }
+        if visited.insert(succ) {
+            worklist.extend(mir.basic_blocks[succ].terminator().successors());
+        }
+    }
+    false
+}
@@ -12,10 +12,13 @@ use rustc_middle::ty::TyCtxt;
 
 use self::andersen::AndersenPTA;
 use self::context_sensitive::ContextSensitivePTA;
+use self::strategies::borrowck_filtering;
 use self::strategies::context_strategy::KCallSiteSensitive;
+use crate::api::AnalysisResult;
 use crate::graph::pag::*;
 use crate::mir::function::FuncId;
 use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::entry_points;
 use crate::pts_set::points_to::HybridPointsToSet;
 use crate::pts_set::pt_data::DiffPTData;
 use crate::util::mem_watcher::MemoryWatcher;
@@ -23,6 +26,15 @@ use crate::util::options::AnalysisOptions;
 
 pub mod andersen;
 pub mod context_sensitive;
+pub mod control_flow;
+pub mod data_race;
+pub mod deadlock;
+pub mod escape_analysis;
+pub mod flow_sensitive;
+pub mod free_errors;
+pub mod lockset;
+pub mod mod_ref;
+pub mod points_to_view;
 pub mod propagator;
 pub mod strategies;
 
@@ -44,7 +56,9 @@ pub trait PointerAnalysis<'tcx, 'compilation> {
     // Solve the worklist problem.
     fn propagate(&mut self);
     // Finalize the analysis.
-    fn finalize(&self);
+    fn finalize(&mut self);
+    // Collect the results of the analysis for the library-level API.
+    fn analysis_result(&self) -> AnalysisResult;
 
     fn analyze(&mut self) {
         self.pre_analysis();
@@ -66,11 +80,18 @@ pub trait PointerAnalysis<'tcx, 'compilation> {
     }
 }
 
+/// A hook called with the results of the analysis while the type context is still alive.
+pub type AnalysisResultHook = Box<dyn for<'tcx> FnMut(TyCtxt<'tcx>, &AnalysisResult) + Send>;
+
 pub struct PTACallbacks {
     /// Options provided to the analysis.
     pub options: AnalysisOptions,
     /// The relative path of the file being compiled.
     file_name: String,
+    /// The hook to be called with the results of the analysis.
+    result_hook: Option<AnalysisResultHook>,
+    /// The results of the analysis, which are only collected if a result hook is set.
+    analysis_result: Option<AnalysisResult>,
 }
 
 /// Constructor
@@ -79,10 +100,29 @@ impl PTACallbacks {
         PTACallbacks {
             options,
             file_name: String::new(),
+            result_hook: None,
+            analysis_result: None,
         }
     }
 
+    /// Sets the hook to be called with the results of the analysis. The results are kept
+    /// afterwards and can be taken with `take_analysis_result` once the compilation is done.
+    pub fn with_result_hook(mut self, hook: AnalysisResultHook) -> PTACallbacks {
+        self.result_hook = Some(hook);
+        self
+    }
+
+    /// Takes the results of the analysis, if a result hook is set and the analysis has been run.
+    pub fn take_analysis_result(&mut self) -> Option<AnalysisResult> {
+        self.analysis_result.take()
+    }
+
     fn run_pointer_analysis(&mut self, compiler: &interface::Compiler, tcx: TyCtxt<'_>) {
+        if self.options.list_entries {
+            entry_points::list_entry_candidates(tcx);
+            return;
+        }
+
         let mut mem_watcher = MemoryWatcher::new();
         mem_watcher.start();
 
@@ -99,6 +139,11 @@ impl PTACallbacks {
                 PTAType::Andersen => Box::new(AndersenPTA::new(&mut acx)),
             };
             pta.analyze();
+            if let Some(hook) = &mut self.result_hook {
+                let result = pta.analysis_result();
+                hook(tcx, &result);
+                self.analysis_result = Some(result);
+            }
         } else {
             error!("AnalysisContext Initialization Failed");
         }
@@ -113,6 +158,9 @@ impl rustc_driver::Callbacks for PTACallbacks {
     fn config(&mut self, config: &mut interface::Config) {
         self.file_name = config.input.source_name().prefer_remapped_unconditionaly().to_string();
         debug!("Processing input file: {}", self.file_name);
+        if self.options.borrowck_filtering {
+            config.override_queries = Some(borrowck_filtering::override_queries);
+        }
     }
 
     /// Called after the compiler has completed all analysis passes and before it lowers MIR to LLVM IR.
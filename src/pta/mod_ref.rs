@@ -0,0 +1,261 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Mod/ref side-effect summaries of the reachable functions.
+//!
+//! The objects a function may read (ref) and write (mod) are given by the pointees of the bases of
+//! its load and store edges, qualified by the projections of the edges. As the PAG only keeps the
+//! loads and stores of values containing pointers, the dereferenced places of the other values are
+//! collected from the function's MIR. The summaries are then propagated from the callees to their
+//! callers over the call graph until a fixed point is reached. The local variables of a function
+//! are excluded from its summary, since they are not visible to the callers once it returns.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use log::*;
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir;
+
+use crate::builder::substs_specializer::SubstsSpecializer;
+use crate::graph::call_graph::{CGCallSite, CGFunction, CallGraph};
+use crate::graph::pag::{PAGEdgeEnum, PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::function::FuncId;
+use crate::mir::path::{Path, PathEnum};
+use crate::pta::points_to_view::{is_write_access, PointsToView};
+use crate::pta::DiffPTDataTy;
+use crate::util::type_util::PointerProjectionsCache;
+
+/// The objects that may be read and written by a function and its callees.
+#[derive(Clone, Default)]
+pub struct ModRefSummary {
+    pub ref_objects: HashSet<Rc<Path>>,
+    pub mod_objects: HashSet<Rc<Path>>,
+}
+
+impl ModRefSummary {
+    /// Adds the objects of `other` that are not locals of `func_id` to this summary.
+    /// Returns true if this summary is changed.
+    fn merge(&mut self, other: &ModRefSummary, func_id: FuncId) -> bool {
+        let mut changed = false;
+        for object in &other.ref_objects {
+            if !is_local_of(object, func_id) {
+                changed |= self.ref_objects.insert(object.clone());
+            }
+        }
+        for object in &other.mod_objects {
+            if !is_local_of(object, func_id) {
+                changed |= self.mod_objects.insert(object.clone());
+            }
+        }
+        changed
+    }
+}
+
+/// Computes the mod/ref summaries of the reachable functions and dumps them if required.
+pub fn compute_mod_ref<P: PAGPath, F, S>(
+    acx: &AnalysisContext,
+    call_graph: &CallGraph<F, S>,
+    pt_data: &DiffPTDataTy,
+    pag: &PAG<P>,
+) -> HashMap<FuncId, ModRefSummary>
+where
+    F: CGFunction + Into<FuncId>,
+    S: CGCallSite,
+{
+    let Some(modref_output) = &acx.analysis_options.modref_output else {
+        return HashMap::new();
+    };
+    let points_to = PointsToView::new(pt_data, pag, |path| path.ci_path());
+    let mut ptr_projs_cache = PointerProjectionsCache::new();
+
+    let mut summaries: HashMap<FuncId, ModRefSummary> = HashMap::new();
+    for func in call_graph.reach_funcs_iter() {
+        let func_id: FuncId = func.into();
+        if summaries.contains_key(&func_id) {
+            continue;
+        }
+        let mut summary = ModRefSummary::default();
+        add_load_store_objects(pag, &points_to, func_id, &mut summary);
+        add_non_pointer_accesses(acx, &points_to, &mut ptr_projs_cache, func_id, &mut summary);
+        summary.ref_objects.retain(|object| !is_local_of(object, func_id));
+        summary.mod_objects.retain(|object| !is_local_of(object, func_id));
+        summaries.insert(func_id, summary);
+    }
+
+    // Propagates the summaries from the callees to the callers.
+    let mut callers: HashMap<FuncId, HashSet<FuncId>> = HashMap::new();
+    for edge in call_graph.graph.edge_indices() {
+        let (caller, callee) = call_graph.graph.edge_endpoints(edge).unwrap();
+        let caller: FuncId = call_graph.graph[caller].func.into();
+        let callee: FuncId = call_graph.graph[callee].func.into();
+        callers.entry(callee).or_default().insert(caller);
+    }
+    let mut worklist: VecDeque<FuncId> = summaries.keys().copied().collect();
+    let mut in_worklist: HashSet<FuncId> = worklist.iter().copied().collect();
+    while let Some(callee) = worklist.pop_front() {
+        in_worklist.remove(&callee);
+        let Some(callee_callers) = callers.get(&callee) else {
+            continue;
+        };
+        let callee_summary = summaries[&callee].clone();
+        for caller in callee_callers {
+            let changed = summaries.get_mut(caller).unwrap().merge(&callee_summary, *caller);
+            if changed && in_worklist.insert(*caller) {
+                worklist.push_back(*caller);
+            }
+        }
+    }
+
+    info!("Dumping mod/ref summaries...");
+    dump_mod_ref(acx, &summaries, modref_output);
+    summaries
+}
+
+/// Adds the objects loaded from and stored to through the load and store edges of the function
+/// and its promoted constants.
+fn add_load_store_objects<P: PAGPath>(
+    pag: &PAG<P>,
+    points_to: &PointsToView<Rc<Path>>,
+    func_id: FuncId,
+    summary: &mut ModRefSummary,
+) {
+    let mut funcs = vec![func_id];
+    if let Some(promoted_funcs) = pag.promoted_funcs_map.get(&func_id) {
+        funcs.extend(promoted_funcs.iter().copied());
+    }
+    for func in funcs {
+        let Some(fpag) = pag.get_func_pag(&func) else {
+            continue;
+        };
+        for (src, dst, kind) in fpag.internal_edges_iter() {
+            let (base, proj, objects) = match kind {
+                PAGEdgeEnum::LoadPAGEdge(proj) => (src, proj, &mut summary.ref_objects),
+                PAGEdgeEnum::StorePAGEdge(proj) => (dst, proj, &mut summary.mod_objects),
+                _ => continue,
+            };
+            objects.extend(points_to.pointees(base).map(|pointee| Path::append_projection(pointee, proj)));
+        }
+    }
+}
+
+/// Adds the dereferenced places of the function's MIR that hold no pointers, which have no load
+/// or store edges in the PAG.
+fn add_non_pointer_accesses<'tcx>(
+    acx: &AnalysisContext<'tcx, '_>,
+    points_to: &PointsToView<Rc<Path>>,
+    ptr_projs_cache: &mut PointerProjectionsCache<'tcx>,
+    func_id: FuncId,
+    summary: &mut ModRefSummary,
+) {
+    let func_ref = acx.get_function_reference(func_id);
+    if func_ref.promoted.is_some() || !acx.tcx.is_mir_available(func_ref.def_id) {
+        return;
+    }
+    let mut collector = AccessCollector {
+        acx,
+        func_id,
+        mir: acx.tcx.optimized_mir(func_ref.def_id),
+        substs_specializer: SubstsSpecializer::new(acx.tcx, func_ref.generic_args.clone()),
+        ptr_projs_cache,
+        points_to,
+        summary,
+    };
+    collector.visit_body(collector.mir);
+}
+
+struct AccessCollector<'a, 'tcx, 'compilation> {
+    acx: &'a AnalysisContext<'tcx, 'compilation>,
+    func_id: FuncId,
+    mir: &'tcx mir::Body<'tcx>,
+    substs_specializer: SubstsSpecializer<'tcx>,
+    ptr_projs_cache: &'a mut PointerProjectionsCache<'tcx>,
+    points_to: &'a PointsToView<Rc<Path>>,
+    summary: &'a mut ModRefSummary,
+}
+
+impl<'a, 'tcx, 'compilation> Visitor<'tcx> for AccessCollector<'a, 'tcx, 'compilation> {
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, _location: mir::Location) {
+        if !place.is_indirect() {
+            return;
+        }
+        let Some(is_mod) = is_write_access(context) else {
+            return;
+        };
+        let ty = self
+            .substs_specializer
+            .specialize_generic_argument_type(place.ty(self.mir, self.acx.tcx).ty);
+        // The accesses of the values containing pointers are given by the load and store edges.
+        if ty.is_any_ptr()
+            || !self
+                .ptr_projs_cache
+                .get_pointer_projections(self.acx.tcx, ty, self.acx.analysis_options.index_sensitive_arrays)
+                .is_empty()
+        {
+            return;
+        }
+        let objects = self.points_to.place_objects(self.acx, self.func_id, self.mir, place);
+        if is_mod {
+            self.summary.mod_objects.extend(objects);
+        } else {
+            self.summary.ref_objects.extend(objects);
+        }
+    }
+}
+
+/// Returns true if the object is a local variable of the function or a part of it.
+fn is_local_of(object: &Rc<Path>, func_id: FuncId) -> bool {
+    match &object.value {
+        PathEnum::LocalVariable { func_id: id, .. }
+        | PathEnum::Parameter { func_id: id, .. }
+        | PathEnum::ReturnValue { func_id: id }
+        | PathEnum::Auxiliary { func_id: id, .. } => *id == func_id,
+        PathEnum::QualifiedPath { base, .. } | PathEnum::OffsetPath { base, .. } => is_local_of(base, func_id),
+        _ => false,
+    }
+}
+
+/// Describes the object with the path of the function it belongs to, e.g. `crate::main::local_1.0`.
+pub(crate) fn object_description(acx: &AnalysisContext, object: &Rc<Path>) -> String {
+    let func_name = |func_id: &FuncId| acx.get_function_reference(*func_id).to_string();
+    match &object.value {
+        PathEnum::LocalVariable { func_id, ordinal } => format!("{}::local_{}", func_name(func_id), ordinal),
+        PathEnum::Parameter { func_id, ordinal } => format!("{}::param_{}", func_name(func_id), ordinal),
+        PathEnum::ReturnValue { func_id } => format!("{}::ret", func_name(func_id)),
+        PathEnum::Auxiliary { func_id, ordinal } => format!("{}::aux_{}", func_name(func_id), ordinal),
+        PathEnum::HeapObj { func_id, location } => format!("{}::heap_{:?}", func_name(func_id), location),
+        PathEnum::QualifiedPath { base, projection } => {
+            let selectors: Vec<String> = projection.iter().map(|selector| format!("{:?}", selector)).collect();
+            format!("{}.{}", object_description(acx, base), selectors.join("."))
+        }
+        PathEnum::OffsetPath { base, offset } => format!("{}.ofs({})", object_description(acx, base), offset),
+        _ => format!("{:?}", object),
+    }
+}
+
+/// Dumps the mod/ref summaries in JSON format, e.g.
+/// ```json
+/// { "crate::set": { "mod": ["crate::main::local_1"], "ref": [] } }
+/// ```
+pub fn dump_mod_ref(acx: &AnalysisContext, summaries: &HashMap<FuncId, ModRefSummary>, modref_path: &String) {
+    let mut output: BTreeMap<String, BTreeMap<&str, BTreeSet<String>>> = BTreeMap::new();
+    for (func_id, summary) in summaries {
+        let func_summary = output.entry(acx.get_function_reference(*func_id).to_string()).or_default();
+        func_summary
+            .entry("mod")
+            .or_default()
+            .extend(summary.mod_objects.iter().map(|object| object_description(acx, object)));
+        func_summary
+            .entry("ref")
+            .or_default()
+            .extend(summary.ref_objects.iter().map(|object| object_description(acx, object)));
+    }
+    let output = serde_json::to_string_pretty(&output).expect("Unable to serialize data");
+    match &modref_path[..] {
+        "stdout" => println!("{}", output),
+        _ => std::fs::write(modref_path, output).expect("Unable to write data"),
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! A view of the points-to results from the places of the MIR bodies.
+//!
+//! The clients resolving the objects accessed through the places of the reachable functions use
+//! either the context-insensitive paths or the paths qualified by the contexts of the analysis as
+//! the objects of the view. In the latter case, a local variable stands for all of its variants
+//! under different contexts.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use rustc_middle::mir;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext};
+
+use crate::api::analysis_result::place_projection;
+use crate::graph::pag::{PAGPath, PAG};
+use crate::mir::analysis_context::AnalysisContext;
+use crate::mir::function::FuncId;
+use crate::mir::path::{Path, PathEnum, PathSelector};
+use crate::pta::DiffPTDataTy;
+use crate::pts_set::points_to::PointsToSet;
+
+pub(crate) struct PointsToView<O> {
+    pts: HashMap<O, HashSet<O>>,
+    /// The variants of the local variables of each function.
+    local_variants: HashMap<Rc<Path>, HashSet<O>>,
+    /// The pointees of the pointers rooted at each variant of the local variables.
+    local_pointees: HashMap<O, HashSet<O>>,
+}
+
+impl<O: PAGPath> PointsToView<O> {
+    /// Creates the view of the points-to results, whose paths are mapped to the objects of the
+    /// view by `to_object`, e.g. `PAGPath::ci_path` for a context-insensitive view.
+    pub(crate) fn new<P: PAGPath>(pt_data: &DiffPTDataTy, pag: &PAG<P>, to_object: impl Fn(&P) -> O) -> Self {
+        let mut view = PointsToView {
+            pts: HashMap::new(),
+            local_variants: HashMap::new(),
+            local_pointees: HashMap::new(),
+        };
+        for (node, node_pts) in &pt_data.propa_pts_map {
+            if node_pts.is_empty() {
+                continue;
+            }
+            let ptr = to_object(pag.node_path(*node));
+            let pointees: Vec<O> = node_pts.iter().map(|pointee| to_object(pag.node_path(pointee))).collect();
+            let base = ptr.base_path();
+            if matches!(
+                base.value(),
+                PathEnum::LocalVariable { .. }
+                    | PathEnum::Parameter { .. }
+                    | PathEnum::ReturnValue { .. }
+                    | PathEnum::Auxiliary { .. }
+            ) {
+                view.local_variants.entry(base.ci_path()).or_default().insert(base.clone());
+                view.local_pointees.entry(base).or_default().extend(pointees.iter().cloned());
+            }
+            view.pts.entry(ptr).or_default().extend(pointees);
+        }
+        view
+    }
+
+    /// Returns the pointees of the pointer.
+    pub(crate) fn pointees(&self, ptr: &O) -> impl Iterator<Item = &O> {
+        self.pts.get(ptr).into_iter().flatten()
+    }
+
+    /// Returns the variants of the local variable given by its context-insensitive path.
+    pub(crate) fn variants(&self, local: &Rc<Path>) -> impl Iterator<Item = &O> {
+        self.local_variants.get(local).into_iter().flatten()
+    }
+
+    /// Returns the variants of the local variable of the function.
+    pub(crate) fn local_objects(&self, func: FuncId, mir: &mir::Body, local: mir::Local) -> impl Iterator<Item = &O> {
+        self.variants(&Path::new_local_parameter_or_result(func, local.as_usize(), mir.arg_count))
+    }
+
+    /// Returns the pointees of the pointers rooted at the local variable of the function.
+    pub(crate) fn local_pointees(&self, func: FuncId, mir: &mir::Body, local: mir::Local) -> impl Iterator<Item = &O> {
+        self.local_objects(func, mir, local)
+            .filter_map(|variant| self.local_pointees.get(variant))
+            .flatten()
+    }
+
+    /// Returns the objects that may be denoted by the place of the function's MIR. The places
+    /// without dereferences only denote the local variables holding pointers.
+    pub(crate) fn place_objects<'tcx>(
+        &self,
+        acx: &AnalysisContext<'tcx, '_>,
+        func: FuncId,
+        mir: &mir::Body<'tcx>,
+        place: &mir::Place<'tcx>,
+    ) -> HashSet<O> {
+        let mut objects: HashSet<O> = self.local_objects(func, mir, place.local).cloned().collect();
+        for elem in place_projection(acx.tcx, mir, place, acx.analysis_options.index_sensitive_arrays) {
+            if let PathSelector::Deref = elem {
+                objects = objects.iter().flat_map(|ptr| self.pointees(ptr)).cloned().collect();
+            } else {
+                objects = objects.iter().map(|object| object.append_projection(&vec![elem])).collect();
+            }
+        }
+        objects
+    }
+}
+
+/// Returns `Some(true)` if the use of a place in the context writes the memory denoted by it,
+/// `Some(false)` if the use reads the memory, and `None` if the use does not access the memory,
+/// e.g. taking a reference to the place.
+pub(crate) fn is_write_access(context: PlaceContext) -> Option<bool> {
+    match context {
+        PlaceContext::MutatingUse(
+            MutatingUseContext::Store
+            | MutatingUseContext::Call
+            | MutatingUseContext::AsmOutput
+            | MutatingUseContext::Yield
+            | MutatingUseContext::SetDiscriminant
+            | MutatingUseContext::Deinit,
+        ) => Some(true),
+        PlaceContext::NonMutatingUse(
+            NonMutatingUseContext::Copy | NonMutatingUseContext::Move | NonMutatingUseContext::Inspect,
+        ) => Some(false),
+        _ => None,
+    }
+}
@@ -328,11 +328,14 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
     /// Process the given load edge.
     /// src --load--> dst:  node \in pts(src) ==> node --direct-->dst
     fn process_load(&mut self, load_edge: EdgeId, base_pts: &PointsTo<NodeId>) {
-        let (_src, dst) = self.pag.graph().edge_endpoints(load_edge).unwrap();
+        let (src, dst) = self.pag.graph().edge_endpoints(load_edge).unwrap();
         let PAGEdgeEnum::LoadPAGEdge(load_proj) = 
             self.pag.get_edge(load_edge).kind.clone() else { unreachable!() };
 
         let stack_filter_pred = Self::stack_filter_pred(load_edge);
+        let strong_type_filter_pred = Self::strong_type_filter_pred();
+        let (_src_path, src_ty) = self.node_path_and_ty(src);
+        let pointee_ty = type_util::get_dereferenced_type(src_ty);
         
         let dst_path = self.pag.node_path(dst).clone();
         for pointee in base_pts {
@@ -342,6 +345,9 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
             if stack_filter_pred(self.acx, self.stack_filter.as_deref(), &pointee_path) {
                 continue;
             }
+            if strong_type_filter_pred(self.acx, &pointee_path, pointee_ty) {
+                continue;
+            }
 
             if let Some(edge_id) = self.add_direct_edge(&src_path, &dst_path) {
                 self.new_edge_from_store_or_load(edge_id, load_edge);
@@ -353,11 +359,14 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
     /// Process the given store edge.
     /// src --store--> dst:  node \in pts(dst) ==> src --direct--> node
     fn process_store(&mut self, store_edge: EdgeId, base_pts: &PointsTo<NodeId>) {
-        let (src, _dst) = self.pag.graph().edge_endpoints(store_edge).unwrap();
+        let (src, dst) = self.pag.graph().edge_endpoints(store_edge).unwrap();
         let PAGEdgeEnum::StorePAGEdge(store_proj) = 
             self.pag.get_edge(store_edge).kind.clone() else { unreachable!() };
 
         let stack_filter_pred = Self::stack_filter_pred(store_edge);
+        let strong_type_filter_pred = Self::strong_type_filter_pred();
+        let (_dst_path, dst_ty) = self.node_path_and_ty(dst);
+        let pointee_ty = type_util::get_dereferenced_type(dst_ty);
         
         let src_path = self.pag.node_path(src).clone();
         for pointee in base_pts {
@@ -366,6 +375,9 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
             if stack_filter_pred(self.acx, self.stack_filter.as_deref(), &pointee_path) {
                 continue;
             }
+            if strong_type_filter_pred(self.acx, &pointee_path, pointee_ty) {
+                continue;
+            }
 
             let dst_path = pointee_path.append_projection(&store_proj);
 
@@ -378,11 +390,14 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
 
     /// Process the given gep edge.
     fn process_gep(&mut self, gep_edge: EdgeId, base_pts: &PointsTo<NodeId>) {
-        let (_src, dst) = self.pag.graph().edge_endpoints(gep_edge).unwrap();
+        let (src, dst) = self.pag.graph().edge_endpoints(gep_edge).unwrap();
         let PAGEdgeEnum::GepPAGEdge(gep_proj) = 
             self.pag.get_edge(gep_edge).kind.clone() else { unreachable!() };
 
         let stack_filter_pred = Self::stack_filter_pred(gep_edge);
+        let strong_type_filter_pred = Self::strong_type_filter_pred();
+        let (_src_path, src_ty) = self.node_path_and_ty(src);
+        let pointee_ty = type_util::get_dereferenced_type(src_ty);
         
         let mut changed = false;
         for pointee in base_pts {
@@ -392,6 +407,9 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
                 self.collect_filtered_pts(gep_edge, pointee);
                 continue;
             }
+            if strong_type_filter_pred(self.acx, &pointee_path, pointee_ty) {
+                continue;
+            }
 
             let src_path = pointee_path.append_projection(&gep_proj);
             let src_id = self.pag.get_or_insert_node(&src_path);
@@ -693,7 +711,17 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
     /// Returns the edge id if this edge is newly added to the graph
     fn add_direct_edge(&mut self, src: &P, dst: &P) -> Option<EdgeId> {
         if !self.pag.has_edge(src, dst, &PAGEdgeEnum::DirectPAGEdge) {
-            self.pag.add_direct_edge(src, dst)
+            let edge_id = self.pag.add_direct_edge(src, dst);
+            // A value written to an element of an index-sensitive array is also written to
+            // the paths referring to the same element.
+            for variant in dst.index_variants(self.acx) {
+                if !self.pag.has_edge(src, &variant, &PAGEdgeEnum::DirectPAGEdge) {
+                    if let Some(variant_edge_id) = self.pag.add_direct_edge(src, &variant) {
+                        self.propagate(variant_edge_id, false);
+                    }
+                }
+            }
+            edge_id
         } else {
             None
         }
@@ -791,7 +819,15 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
     /// Returns the edge id if this edge is newly added to the graph
     fn add_cast_edge(&mut self, src: &P, dst: &P) -> Option<EdgeId> {
         if !self.pag.has_edge(src, dst, &PAGEdgeEnum::CastPAGEdge) {
-            self.pag.add_cast_edge(src, dst)
+            let edge_id = self.pag.add_cast_edge(src, dst);
+            for variant in dst.index_variants(self.acx) {
+                if !self.pag.has_edge(src, &variant, &PAGEdgeEnum::CastPAGEdge) {
+                    if let Some(variant_edge_id) = self.pag.add_cast_edge(src, &variant) {
+                        self.propagate_cast(variant_edge_id, false);
+                    }
+                }
+            }
+            edge_id
         } else {
             None
         }
@@ -932,21 +968,12 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
         src_flattened_fields: Vec<(usize, P, Ty<'tcx>)>,
         tgt_flattened_fields: Vec<(usize, P, Ty<'tcx>)>,
     ) {
-        let src_len = src_flattened_fields.len();
-        let tgt_len = tgt_flattened_fields.len();
-        let mut src_field_index = 0;
-        let mut tgt_field_index = 0;
-        while tgt_field_index < tgt_len && src_field_index < src_len {
+        for (src_field_index, tgt_field_index) in
+            type_util::pair_flattened_fields(&src_flattened_fields, &tgt_flattened_fields)
+        {
             // Both the src_type and tgt_type should have been specialized.
-            let (tgt_offset, tgt_field, tgt_type) = &tgt_flattened_fields[tgt_field_index];
-            let (src_offset, src_field, src_type) = &src_flattened_fields[src_field_index];
-            if *tgt_offset < *src_offset {
-                tgt_field_index += 1;
-                continue;
-            } else if *tgt_offset > *src_offset {
-                src_field_index += 1;
-                continue;
-            }
+            let (_, tgt_field, tgt_type) = &tgt_flattened_fields[tgt_field_index];
+            let (_, src_field, src_type) = &src_flattened_fields[src_field_index];
 
             // if source type and target type are any kind of primitive pointer type (reference, raw pointer, fn pointer).
             if src_type.is_any_ptr() && tgt_type.is_any_ptr() {
@@ -982,8 +1009,6 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
                     }
                 }
             }
-            tgt_field_index += 1;
-            src_field_index += 1;
         }
     }
 
@@ -1146,6 +1171,27 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
         }
     }
 
+    /// Returns a predicate that tells whether a pointee should be filtered out with `--type-filter`,
+    /// i.e. whether its type cannot match the static pointee type of the pointer it is accessed
+    /// through. The objects that have been cast or transmuted are never filtered.
+    fn strong_type_filter_pred() -> impl Fn(&mut AnalysisContext<'tcx, '_>, &P, Ty<'tcx>) -> bool {
+        |acx: &mut AnalysisContext<'tcx, '_>, pointee: &P, pointee_ty: Ty<'tcx>| -> bool {
+            if !acx.analysis_options.type_filter {
+                return false;
+            }
+            if let PathEnum::QualifiedPath { projection, .. } = pointee.value() {
+                if matches!(projection.last(), Some(PathSelector::Cast(..))) {
+                    return false;
+                }
+            }
+            if pointee.regularize(acx).has_been_cast(acx) {
+                return false;
+            }
+            let obj_ty = pointee.try_eval_path_type(acx);
+            !type_util::is_compatible_pointee_type(acx.tcx, pointee_ty, obj_ty)
+        }
+    }
+
     fn stack_filter_pred(edge_id: EdgeId) -> impl Fn(&AnalysisContext<'_, '_>, Option<&StackFilter<F>>, &P) -> bool {
         move |acx:&AnalysisContext, stack_filter: Option<&StackFilter<F>>, pointee: &P| -> bool {
             if !acx.analysis_options.stack_filtering {
@@ -1153,7 +1199,8 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
             } 
             if let Some(sf) = stack_filter  {
                 if let Some(&edge_func) = sf.get_container_func_of_edge(&edge_id) {
-                    return !sf.is_potentially_alive(acx, edge_func, pointee);
+                    return !sf.is_potentially_alive(acx, edge_func, pointee)
+                        || sf.is_confined_elsewhere(acx, edge_func, pointee);
                 } 
             } 
 
@@ -1163,6 +1210,14 @@ impl<'pta, 'tcx, 'compilation, F, P> Propagator<'pta, 'tcx, 'compilation, F, P>
 
     fn collect_filtered_pts(&mut self, edge_id: EdgeId, filtered_pointee: NodeId) {
         if let Some(sf) = &mut self.stack_filter {
+            // Distinguish the points-to relations filtered out only by the borrowck information.
+            if let Some(&edge_func) = sf.get_container_func_of_edge(&edge_id) {
+                let pointee = self.pag.node_path(filtered_pointee);
+                if sf.is_potentially_alive(self.acx, edge_func, pointee) {
+                    sf.collect_borrowck_filtered_pts(edge_id, filtered_pointee);
+                    return;
+                }
+            }
             sf.collect_filtered_pts(edge_id, filtered_pointee);
         }
     }
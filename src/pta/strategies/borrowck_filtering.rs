@@ -0,0 +1,262 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Borrow-checker informed filtering of stack objects.
+//!
+//! The stack filter approximates the lifetime of a stack object with the call-graph reachability
+//! from its containing function. The borrow checker knows more: if no borrow of a local can flow
+//! into a returned value, a raw pointer or a region of the function signature, then the local can
+//! only be accessed by the activation of the function that declares it and by the calls its
+//! borrows are passed to. We call such a local a *confined local*. The points-to facts that make a
+//! confined local observable in a function that is not reachable from the callees of these calls,
+//! or in another context of its declaring function, are filtered out.
+//!
+//! The borrow checking results are collected by overriding the `mir_borrowck` query, as the MIR
+//! bodies with region information are stolen once the borrow checking is done. Since the analyzed
+//! MIR is the optimized MIR, whose locals may be renumbered, the confined locals are identified by
+//! the spans of their declarations, and the calls by the spans of their function calls.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use rustc_borrowck::consumers::{self, ConsumerOptions};
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::{self, BorrowCheckResult, Rvalue};
+use rustc_middle::ty::{self, RegionVid, Ty, TyCtxt};
+use rustc_middle::util::Providers;
+use rustc_session::Session;
+use rustc_span::Span;
+
+thread_local! {
+    /// The declaration spans of the confined locals of each borrow-checked function, mapped to the
+    /// spans of the calls their borrows are passed to.
+    static CONFINED_LOCALS: RefCell<HashMap<LocalDefId, HashMap<Span, HashSet<Span>>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Overrides the `mir_borrowck` query to collect the confined locals of each function.
+pub fn override_queries(_session: &Session, providers: &mut Providers) {
+    providers.mir_borrowck = mir_borrowck;
+}
+
+fn mir_borrowck(tcx: TyCtxt<'_>, def_id: LocalDefId) -> &BorrowCheckResult<'_> {
+    if !tcx.mir_promoted(def_id).0.borrow().should_skip() {
+        let body_with_facts =
+            consumers::get_body_with_borrowck_facts(tcx, def_id, ConsumerOptions::PoloniusInputFacts);
+        let confined_locals = compute_confined_locals(tcx, &body_with_facts);
+        CONFINED_LOCALS.with(|locals| locals.borrow_mut().insert(def_id, confined_locals));
+    }
+    (rustc_interface::DEFAULT_QUERY_PROVIDERS.mir_borrowck)(tcx, def_id)
+}
+
+/// Returns the spans of the calls the borrows of the local of the given function are passed to,
+/// or `None` if the local is not a confined local.
+pub fn confined_local_calls(tcx: TyCtxt<'_>, def_id: DefId, local: mir::Local) -> Option<HashSet<Span>> {
+    let local_def_id = def_id.as_local()?;
+    CONFINED_LOCALS.with(|locals| {
+        let locals = locals.borrow();
+        let confined_locals = locals.get(&local_def_id)?;
+        let body = tcx.optimized_mir(def_id);
+        let local_decl = body.local_decls.get(local)?;
+        confined_locals.get(&local_decl.source_info.span).cloned()
+    })
+}
+
+/// Computes the declaration spans of the locals whose borrows cannot escape the function, together
+/// with the spans of the calls their borrows are passed to.
+///
+/// A borrow escapes if its region is required to outlive a universal region, i.e. a region of the
+/// function signature, or a region in the type of a value that is dropped, cast to a raw pointer
+/// or an integer, or accessed through a raw pointer. A local whose address is taken with `&raw`
+/// also escapes. A borrow is passed to a call if its region is required to outlive a region in
+/// the type of the callee, the arguments or the destination of the call.
+fn compute_confined_locals<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body_with_facts: &consumers::BodyWithBorrowckFacts<'tcx>,
+) -> HashMap<Span, HashSet<Span>> {
+    let body = &body_with_facts.body;
+    let Some(facts) = &body_with_facts.input_facts else {
+        return HashMap::new();
+    };
+    // The locals of coroutines live across suspension points and are conservatively kept.
+    if body.coroutine.is_some() {
+        return HashMap::new();
+    }
+
+    let mut collector = EscapeCollector {
+        tcx,
+        body,
+        escaping_regions: facts.universal_region.iter().cloned().collect(),
+        escaping_locals: HashSet::new(),
+        calls: Vec::new(),
+    };
+    collector.visit_body(body);
+
+    // The loans in region `sup` flow into region `sub` for each constraint `sup: sub`, therefore
+    // a region escapes if it can reach an escaping region through the constraints.
+    let mut predecessors: HashMap<RegionVid, Vec<RegionVid>> = HashMap::new();
+    let mut successors: HashMap<RegionVid, Vec<RegionVid>> = HashMap::new();
+    for (sup, sub, _point) in &facts.subset_base {
+        predecessors.entry(*sub).or_default().push(*sup);
+        successors.entry(*sup).or_default().push(*sub);
+    }
+    let mut escaping_regions = collector.escaping_regions;
+    let mut worklist: Vec<RegionVid> = escaping_regions.iter().cloned().collect();
+    while let Some(region) = worklist.pop() {
+        if let Some(preds) = predecessors.get(&region) {
+            for pred in preds {
+                if escaping_regions.insert(*pred) {
+                    worklist.push(*pred);
+                }
+            }
+        }
+    }
+
+    let mut escaping_locals = collector.escaping_locals;
+    let mut passed_calls: HashMap<mir::Local, HashSet<Span>> = HashMap::new();
+    for borrow in body_with_facts.borrow_set.location_map.values() {
+        // A borrow through a dereference does not borrow the storage of the local.
+        if borrow.borrowed_place.is_indirect() {
+            continue;
+        }
+        let local = borrow.borrowed_place.local;
+        if escaping_regions.contains(&borrow.region) {
+            escaping_locals.insert(local);
+            continue;
+        }
+        let reachable_regions = reachable_regions(&successors, borrow.region);
+        for (fn_span, call_regions) in &collector.calls {
+            if !call_regions.is_disjoint(&reachable_regions) {
+                passed_calls.entry(local).or_default().insert(*fn_span);
+            }
+        }
+    }
+
+    // Different locals may be declared with the same span, a span is confined only if all
+    // of them are confined.
+    let mut confined_locals: HashMap<Span, HashSet<Span>> = HashMap::new();
+    let mut escaping_spans = HashSet::new();
+    for (local, local_decl) in body.local_decls.iter_enumerated() {
+        let span = local_decl.source_info.span;
+        if escaping_locals.contains(&local) {
+            escaping_spans.insert(span);
+        } else {
+            let calls = confined_locals.entry(span).or_default();
+            if let Some(passed) = passed_calls.get(&local) {
+                calls.extend(passed);
+            }
+        }
+    }
+    confined_locals.retain(|span, _| !escaping_spans.contains(span));
+    confined_locals
+}
+
+/// Returns the regions that the loans in the given region flow into.
+fn reachable_regions(
+    successors: &HashMap<RegionVid, Vec<RegionVid>>,
+    region: RegionVid,
+) -> HashSet<RegionVid> {
+    let mut reachable = HashSet::from([region]);
+    let mut worklist = vec![region];
+    while let Some(region) = worklist.pop() {
+        if let Some(succs) = successors.get(&region) {
+            for succ in succs {
+                if reachable.insert(*succ) {
+                    worklist.push(*succ);
+                }
+            }
+        }
+    }
+    reachable
+}
+
+/// Collects the regions of the values that may be observed outside the function, the locals
+/// whose addresses are taken as raw pointers, and the regions of the values passed to each call.
+struct EscapeCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a mir::Body<'tcx>,
+    escaping_regions: HashSet<RegionVid>,
+    escaping_locals: HashSet<mir::Local>,
+    calls: Vec<(Span, HashSet<RegionVid>)>,
+}
+
+impl<'a, 'tcx> EscapeCollector<'a, 'tcx> {
+    fn add_regions_in(&mut self, ty: Ty<'tcx>) {
+        collect_regions_in(self.tcx, ty, &mut self.escaping_regions);
+    }
+}
+
+fn collect_regions_in<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, regions: &mut HashSet<RegionVid>) {
+    tcx.for_each_free_region(&ty, |region| {
+        if let ty::ReVar(vid) = region.kind() {
+            regions.insert(vid);
+        }
+    });
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for EscapeCollector<'a, 'tcx> {
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, location: mir::Location) {
+        // The values accessed through raw pointers are not tracked by the borrow checker.
+        let through_raw_pointer = place.iter_projections().any(|(base, elem)| {
+            matches!(elem, mir::ProjectionElem::Deref) && base.ty(self.body, self.tcx).ty.is_unsafe_ptr()
+        });
+        if through_raw_pointer {
+            self.add_regions_in(place.ty(self.body, self.tcx).ty);
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: mir::Location) {
+        match rvalue {
+            Rvalue::AddressOf(_, place) if !place.is_indirect() => {
+                self.escaping_locals.insert(place.local);
+            }
+            // A raw pointer to the pointee of a reference, e.g. `&raw mut (*_2)` generated for
+            // `_2 as *mut T`, is regarded as a cast of the reference.
+            Rvalue::AddressOf(_, place) => {
+                self.add_regions_in(self.body.local_decls[place.local].ty);
+            }
+            Rvalue::Cast(cast_kind, operand, target_ty) => {
+                if target_ty.is_unsafe_ptr()
+                    || target_ty.is_integral()
+                    || matches!(cast_kind, mir::CastKind::Transmute)
+                {
+                    self.add_regions_in(operand.ty(self.body, self.tcx));
+                }
+            }
+            _ => {}
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: mir::Location) {
+        match &terminator.kind {
+            mir::TerminatorKind::Call { func, args, destination, fn_span, .. } => {
+                let mut call_regions = HashSet::new();
+                collect_regions_in(self.tcx, func.ty(self.body, self.tcx), &mut call_regions);
+                for arg in args {
+                    collect_regions_in(self.tcx, arg.node.ty(self.body, self.tcx), &mut call_regions);
+                }
+                collect_regions_in(self.tcx, destination.ty(self.body, self.tcx).ty, &mut call_regions);
+                self.calls.push((*fn_span, call_regions));
+            }
+            mir::TerminatorKind::Drop { place, .. } => {
+                self.add_regions_in(place.ty(self.body, self.tcx).ty);
+            }
+            mir::TerminatorKind::InlineAsm { operands, .. } => {
+                for operand in operands {
+                    if let mir::InlineAsmOperand::In { value, .. }
+                    | mir::InlineAsmOperand::InOut { in_value: value, .. } = operand
+                    {
+                        self.add_regions_in(value.ty(self.body, self.tcx));
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.super_terminator(terminator, location);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod borrowck_filtering;
 pub mod context_strategy;
 pub mod stack_filtering;
\ No newline at end of file
@@ -5,7 +5,9 @@ use std::sync::{Arc, Mutex};
 use std::time::{Instant, Duration};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
+use petgraph::visit::EdgeRef;
 use rustc_hir::def::DefKind;
+use rustc_middle::mir;
 
 use crate::graph::call_graph::CallGraph;
 use crate::mir::call_site::BaseCallSite;
@@ -17,6 +19,7 @@ use crate::graph::pag::PAGPath;
 use crate::pta::{FuncId, PointsTo, EdgeId, NodeId};
 use crate::pts_set::points_to::PointsToSet;
 
+use super::borrowck_filtering;
 use super::context_strategy::{KCallSiteSensitive, ContextStrategy};
 
 
@@ -205,6 +208,8 @@ pub struct StackFilter<F> {
     pub(crate) kcs_context_strategy: NonNull<KCallSiteSensitive>,
     pub(crate) collect_filtered_pts: bool,
     pub(crate) filtered_pts: HashMap<EdgeId, PointsTo<NodeId>>,
+    // The points-to relations filtered out only by the borrowck information
+    pub(crate) borrowck_filtered_pts: HashMap<EdgeId, PointsTo<NodeId>>,
     // Function reachability analysis time
     pub(crate) fra_time: Duration,
 }
@@ -225,6 +230,7 @@ impl<F> StackFilter<F> where
             kcs_context_strategy: NonNull::dangling(),
             collect_filtered_pts: false,
             filtered_pts: HashMap::new(),
+            borrowck_filtered_pts: HashMap::new(),
             fra_time,
         }
     }
@@ -266,6 +272,12 @@ impl<F> StackFilter<F> where
         }
     }
 
+    pub fn collect_borrowck_filtered_pts(&mut self, edge_id: EdgeId, filtered_pointee: NodeId) {
+        if self.collect_filtered_pts {
+            self.borrowck_filtered_pts.entry(edge_id).or_insert(PointsTo::new()).insert(filtered_pointee);
+        }
+    }
+
     pub fn fra_time(&self) -> Duration {
         self.fra_time
     }
@@ -311,6 +323,64 @@ impl<F> StackFilter<F> where
         return true;
     }
 
+    /// Returns true if the target path is a local confined to the activation of its function
+    /// according to the borrow checker, and the current function is another context of the same
+    /// function or a function not reachable from the callees its borrows are passed to.
+    pub fn is_confined_elsewhere<P: PAGPath<FuncTy = F>>(
+        &self,
+        acx: &AnalysisContext,
+        current_func: F,
+        target_path: &P
+    ) -> bool {
+        if !acx.analysis_options.borrowck_filtering {
+            return false;
+        }
+        let Some(ordinal) = local_ordinal(target_path.value()) else {
+            return false;
+        };
+        let Some(path_container_func) = target_path.get_containing_func() else {
+            return false;
+        };
+        if path_container_func == current_func {
+            return false;
+        }
+        let func_ref = acx.get_function_reference(path_container_func.into());
+        if func_ref.promoted.is_some() {
+            return false;
+        }
+        let Some(passed_calls) = borrowck_filtering::confined_local_calls(acx.tcx, func_ref.def_id, ordinal.into()) else {
+            return false;
+        };
+        if passed_calls.is_empty() {
+            return true;
+        }
+        let (Some(container_node), Some(current_node)) = (
+            self.call_graph.func_nodes.get(&path_container_func.into()),
+            self.call_graph.func_nodes.get(&current_func.into()),
+        ) else {
+            return false;
+        };
+        // The callees of the calls the local is passed to, and the functions reachable from them,
+        // may observe the local.
+        let body = acx.tcx.optimized_mir(func_ref.def_id);
+        for edge in self.call_graph.graph.edges(*container_node) {
+            let location = edge.weight().callsite.location;
+            let Some(block) = body.basic_blocks.get(location.block) else {
+                continue;
+            };
+            if let mir::TerminatorKind::Call { fn_span, .. } = &block.terminator().kind {
+                if !passed_calls.contains(fn_span) {
+                    continue;
+                }
+            }
+            let callee_node = edge.target();
+            if callee_node == *current_node || self.reach_relation.is_reachable(callee_node.index(), current_node.index()) {
+                return false;
+            }
+        }
+        true
+    }
+
     pub fn naive_reachability_relation(&self, from: F, to: F) -> bool {
         let from_id = self.call_graph.func_nodes.get(&from.into()).unwrap();
         let to_id = self.call_graph.func_nodes.get(&to.into()).unwrap();
@@ -339,10 +409,28 @@ impl<F> StackFilter<F> {
                     num_filtered_relations).as_bytes()
                 )
                 .expect("Unable to write data");
+            let mut num_borrowck_filtered_relations = 0;
+            for filtered_pts in self.borrowck_filtered_pts.values() {
+                num_borrowck_filtered_relations += filtered_pts.count();
+            }
+            stat_writer
+                .write_all(format!("#Filtered points-to relations by borrowck: {}\n", 
+                    num_borrowck_filtered_relations).as_bytes()
+                )
+                .expect("Unable to write data");
         }
     }
 }
 
+/// Returns the ordinal of the MIR local that the path is based on.
+fn local_ordinal(path: &PathEnum) -> Option<usize> {
+    match path {
+        PathEnum::LocalVariable { ordinal, .. } | PathEnum::Parameter { ordinal, .. } => Some(*ordinal),
+        PathEnum::QualifiedPath { base, .. } | PathEnum::OffsetPath { base, .. } => local_ordinal(&base.value),
+        _ => None,
+    }
+}
+
 pub trait SFReachable where Self: Sized {
     fn is_reachable_from(&self, from: &Self, stack_filter: &StackFilter<Self>) -> bool;
 
@@ -291,7 +291,9 @@ impl<'a, 'rta, 'tcx, 'compilation> BodyVisitor<'a, 'rta, 'tcx, 'compilation> {
         if !util::is_trait_method(self.tcx(), *callee_def_id) 
         {
             // Static functions or methods or associated functions not declared on a trait.
-            let callee_func_id = self.acx().get_func_id(*callee_def_id, gen_args);
+            // Functions declared in `extern` blocks are resolved to their definitions if exist.
+            let callee_def_id = self.acx().resolve_foreign_item(*callee_def_id).unwrap_or(*callee_def_id);
+            let callee_func_id = self.acx().get_func_id(callee_def_id, gen_args);
             let callsite = BaseCallSite::new(self.func_id, location);
             self.rta.add_static_callsite(callsite);
             self.rta.add_call_edge(callsite, callee_func_id);
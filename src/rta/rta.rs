@@ -9,7 +9,7 @@ use crate::builder::call_graph_builder;
 use crate::graph::call_graph::CallGraph;
 use crate::mir::analysis_context::AnalysisContext;
 use crate::mir::call_site::{BaseCallSite, CallType};
-use crate::mir::function::{FuncId, FunctionReference, GenericArgE};
+use crate::mir::function::{FuncId, GenericArgE};
 use crate::util::{type_util, chunked_queue, results_dumper};
 
 use super::body_visitor::BodyVisitor;
@@ -72,12 +72,10 @@ impl<'a, 'tcx, 'compilation> RapidTypeAnalysis<'a, 'tcx, 'compilation> {
     pub fn analyze(&mut self) {
         let now = Instant::now();
 
-        // add the entry point to the call graph
-        let entry_point = self.acx.entry_point;
-        let entry_func_id = self.acx.get_or_add_function_reference(
-            FunctionReference::new_function_reference(entry_point, vec![])
-        );
-        self.call_graph.add_node(entry_func_id);
+        // add the entry points to the call graph
+        for entry_func_id in self.acx.entry_points.clone() {
+            self.call_graph.add_node(entry_func_id);
+        }
 
         // process terminators of reachable functions
         self.iteratively_process_reachable_functions();
@@ -10,8 +10,11 @@ use itertools::Itertools;
 use clap::{Arg, Command};
 use clap::error::ErrorKind;
 use rustc_tools_util::VersionInfo;
+use regex::Regex;
 
 
+use crate::mir::entry_points::EntryMode;
+use crate::pta::free_errors::FreeErrorsFormat;
 use crate::pta::PTAType;
 
 const RUPTA_USAGE: &str = r#"pta [OPTIONS] INPUT -- [RUSTC OPTIONS]"#;
@@ -33,13 +36,29 @@ fn make_options_parser() -> Command<'static> {
         .version(version())
         .arg(Arg::new("entry-func-name")
             .long("entry-func")
-            .takes_value(true) 
-            .help("The name of entry function from which the pointer analysis begins."))
+            .takes_value(true)
+            .multiple_occurrences(true)
+            .help("The name or path of entry function from which the pointer analysis begins.")
+            .long_help("The name or the full path (e.g. `crate::module::Type::method`) of entry function \
+                from which the pointer analysis begins. Can be provided multiple times to analyze from multiple entries."))
         .arg(Arg::new("entry-func-id")
             .long("entry-id")
             .takes_value(true)
             .value_parser(clap::value_parser!(u32))
             .help("The def_id of entry function from which the pointer analysis begins."))
+        .arg(Arg::new("list-entries")
+            .long("list-entries")
+            .takes_value(false)
+            .help("List the functions that can be used as entry functions with their paths and def ids."))
+        .arg(Arg::new("entry-mode")
+            .long("entry-mode")
+            .takes_value(true)
+            .value_parser(["main", "lib", "tests"])
+            .default_value("main")
+            .help("The way to select the entry functions of the analysis.")
+            .long_help("`main` analyzes the program from the function specified by `--entry-func` or `--entry-id`, \
+                or the `main` function by default. `lib` analyzes a library from all of its public functions and methods. \
+                `tests` compiles the crate with `--test` and analyzes it from all of its test functions."))
         .arg(Arg::new("pta-type")
             .long("pta-type")
             .takes_value(true)
@@ -58,10 +77,97 @@ fn make_options_parser() -> Command<'static> {
             .takes_value(false)
             .hide(true)
             .help("Disable the cast optimization that constrains an object cast from a simple pointer type."))
+        .arg(Arg::new("index-sensitive-arrays")
+            .long("index-sensitive-arrays")
+            .takes_value(true)
+            .value_name("MAX_LEN")
+            .value_parser(clap::value_parser!(usize))
+            .help("Distinguish the elements of arrays of at most MAX_LEN elements accessed with constant indices.")
+            .long_help("Distinguish the elements of arrays of at most MAX_LEN elements accessed with constant indices, \
+                e.g. the entries of a dispatch table of function pointers. The elements accessed with non-constant \
+                indices are summarized by a single element."))
+        .arg(Arg::new("variant-sensitive-enums")
+            .long("variant-sensitive-enums")
+            .takes_value(false)
+            .help("Keep the fields of different enum variants distinct through casts and transmutes.")
+            .long_help("Keep the fields of different enum variants distinct when casting, transmuting or \
+                copying enum values through raw pointers, instead of treating an enum value as a whole. \
+                The points-to sets of the pointers in each variant are also dumped separately with --dump-pts."))
+        .arg(Arg::new("type-filter")
+            .long("type-filter")
+            .takes_value(false)
+            .help("Filter out the objects whose types cannot match the pointee types of the pointers they are accessed through.")
+            .long_help("Filter out the objects whose types cannot match the static pointee types of the pointers \
+                that load from, store to or get fields of them. The objects that have been cast or transmuted \
+                to other types are not filtered."))
+        .arg(Arg::new("prune-shared-ref-stores")
+            .long("prune-shared-ref-stores")
+            .takes_value(false)
+            .help("Drop the stores through shared references to types without interior mutability.")
+            .long_help("Drop the stores through shared references to types without interior mutability, \
+                i.e. types that do not contain an `UnsafeCell`, as such references cannot be used to write \
+                their pointees. The stores through raw pointers are kept, except the ones that the models of \
+                memcpy and memmove trace back to such shared references, as writing through them is undefined behavior."))
         .arg(Arg::new("stack-filtering")
             .long("stack-filtering")
             .takes_value(false)
             .help("Enable stack filtering in pointer analysis."))
+        .arg(Arg::new("borrowck-filtering")
+            .long("borrowck-filtering")
+            .takes_value(false)
+            .help("Filter out the stack objects whose borrows cannot escape their functions, implies --stack-filtering.")
+            .long_help("Consult the region information computed by the borrow checker to find the locals \
+                whose borrows are never returned or cast to raw pointers. Such locals are only accessible in \
+                the activations of their functions and in the callees their borrows are passed to, the flows \
+                of them into other functions are filtered out. Implies --stack-filtering."))
+        .arg(Arg::new("model-std-smart-pointers")
+            .long("model-std-smart-pointers")
+            .takes_value(false)
+            .help("Model the methods of Rc, Arc, Cell, RefCell, Mutex and RwLock with summaries instead of analyzing their bodies."))
+        .arg(Arg::new("model-std-collections")
+            .long("model-std-collections")
+            .takes_value(false)
+            .help("Model the methods of Vec, VecDeque, HashMap, HashSet, BTreeMap and BTreeSet with abstract containers instead of analyzing their bodies."))
+        .arg(Arg::new("model-lazy-globals")
+            .long("model-lazy-globals")
+            .takes_value(false)
+            .help("Model thread locals, OnceCell, OnceLock, LazyCell, LazyLock and lazy_static globals as static variables instead of analyzing the bodies of their accessors.")
+            .long_help("Model thread locals, OnceCell, OnceLock, LazyCell, LazyLock and lazy_static globals as static \
+                variables that the results of their initializers flow into, instead of analyzing the bodies of their \
+                accessors. Without the models, the flows from the initializers to the readers are routed through the \
+                function pointers and the Once machinery of the standard library and mostly lost."))
+        .arg(Arg::new("resolve-global-allocator")
+            .long("resolve-global-allocator")
+            .takes_value(false)
+            .help("Connect the allocations and deallocations to the methods of the registered #[global_allocator]."))
+        .arg(Arg::new("resolve-panic-handler")
+            .long("resolve-panic-handler")
+            .takes_value(false)
+            .help("Connect the panics to the #[panic_handler] and the panic hooks registered with std::panic::set_hook."))
+        .arg(Arg::new("flow-sensitive-funcs")
+            .long("flow-sensitive-funcs")
+            .takes_value(true)
+            .value_name("REGEX")
+            .value_parser(|pattern: &str| Regex::new(pattern))
+            .help("Refine the points-to results of the functions whose names match REGEX flow-sensitively.")
+            .long_help("Refine the points-to results of the reachable functions whose names match REGEX with a \
+                flow-sensitive intraprocedural analysis seeded by the flow-insensitive results, which strongly \
+                updates the locals and the local objects pointed to by a single pointer."))
+        .arg(Arg::new("check-races")
+            .long("check-races")
+            .takes_value(false)
+            .help("Report the conflicting accesses of the objects shared between threads that are not protected by a common lock.")
+            .long_help("Report the pairs of accesses, at least one of which is a write, to the objects shared between \
+                threads that may run concurrently without holding a common Mutex or RwLock. The locks are \
+                identified by the objects the guards are acquired from, and held until the guards are dropped."))
+        .arg(Arg::new("check-deadlocks")
+            .long("check-deadlocks")
+            .takes_value(false)
+            .help("Report the cycles in the lock acquisition order and the double locking of a Mutex or RwLock.")
+            .long_help("Report the cycles in the order the Mutex and RwLock objects are acquired across the call graph, \
+                where a lock is ordered before the locks acquired by the functions called while it is held, \
+                together with the call chains producing the cycles. A lock acquired again while it is held \
+                is reported as a double locking."))
         .arg(Arg::new("dump-stats")
             .long("dump-stats")
             .takes_value(false)
@@ -74,6 +180,34 @@ fn make_options_parser() -> Command<'static> {
             .long("dump-pts")
             .takes_value(true)
             .help("Dump points-to results to the output file."))
+        .arg(Arg::new("fs-pts-output")
+            .long("dump-fs-pts")
+            .takes_value(true)
+            .help("Dump the flow-sensitive points-to results before each MIR location of the functions selected by --flow-sensitive-funcs."))
+        .arg(Arg::new("modref-output")
+            .long("dump-modref")
+            .takes_value(true)
+            .help("Dump the objects that may be read and written by each reachable function and its callees in JSON format to the output file."))
+        .arg(Arg::new("escape-output")
+            .long("dump-escape")
+            .takes_value(true)
+            .help("Dump whether each stack and heap object escapes to the caller, a global or another thread in JSON format to the output file."))
+        .arg(Arg::new("free-errors-output")
+            .long("dump-free-errors")
+            .takes_value(true)
+            .help("Dump the possible uses after free and double frees of the heap objects to the output file.")
+            .long_help("Dump the loads and stores through pointers to the heap objects that may have been freed before, \
+                and the deallocations of the heap objects that may have been freed before, with their source locations."))
+        .arg(Arg::new("free-errors-format")
+            .long("free-errors-format")
+            .takes_value(true)
+            .value_parser(["json", "sarif"])
+            .default_value("json")
+            .help("The format of the free errors dumped by `--dump-free-errors`."))
+        .arg(Arg::new("entry-reachability-output")
+            .long("dump-entry-reachability")
+            .takes_value(true)
+            .help("Dump the functions reachable from each entry function in JSON format to the output file."))
         .arg(Arg::new("mir-output")
             .long("dump-mir")
             .takes_value(true)
@@ -103,18 +237,45 @@ fn make_options_parser() -> Command<'static> {
 
 #[derive(Clone, Debug)]
 pub struct AnalysisOptions {
-    pub entry_func: String,
+    pub entry_func: Vec<String>,
     pub entry_def_id: Option<u32>,
+    pub list_entries: bool,
+    pub entry_mode: EntryMode,
     pub pta_type: PTAType,
     // options for context-sensitive analysis
     pub context_depth: u32,
     // options for handling cast propagation
     pub cast_constraint: bool,
+    // distinguish the elements of arrays not longer than the given length by their constant indices
+    pub index_sensitive_arrays: Option<usize>,
+    // distinguish the fields of different enum variants when flattening and casting enum values
+    pub variant_sensitive_enums: bool,
+    pub type_filter: bool,
+    pub prune_shared_ref_stores: bool,
     pub stack_filtering: bool,
+    pub borrowck_filtering: bool,
+    // summarize the std smart pointer and interior mutability types
+    pub model_std_smart_pointers: bool,
+    // summarize the std collections with abstract containers
+    pub model_std_collections: bool,
+    // model the lazily initialized globals as static variables
+    pub model_lazy_globals: bool,
+    pub resolve_global_allocator: bool,
+    pub resolve_panic_handler: bool,
+    // refine the points-to results of the matching functions flow-sensitively
+    pub flow_sensitive_funcs: Option<Regex>,
+    pub check_races: bool,
+    pub check_deadlocks: bool,
     
     pub dump_stats: bool,
     pub call_graph_output: Option<String>,
     pub pts_output: Option<String>,
+    pub fs_pts_output: Option<String>,
+    pub modref_output: Option<String>,
+    pub escape_output: Option<String>,
+    pub free_errors_output: Option<String>,
+    pub free_errors_format: FreeErrorsFormat,
+    pub entry_reachability_output: Option<String>,
     pub mir_output: Option<String>,
     pub type_indices_output: Option<String>,
     pub dyn_calls_output: Option<String>,
@@ -125,15 +286,36 @@ pub struct AnalysisOptions {
 impl Default for AnalysisOptions {
     fn default() -> Self {
         Self {
-            entry_func: String::new(),
+            entry_func: Vec::new(),
             entry_def_id: None,
+            list_entries: false,
+            entry_mode: EntryMode::Main,
             pta_type: PTAType::CallSiteSensitive,
             context_depth: 1,
             cast_constraint: true,
+            index_sensitive_arrays: None,
+            variant_sensitive_enums: false,
+            type_filter: false,
+            prune_shared_ref_stores: false,
             stack_filtering: false,
+            borrowck_filtering: false,
+            model_std_smart_pointers: false,
+            model_std_collections: false,
+            model_lazy_globals: false,
+            resolve_global_allocator: false,
+            resolve_panic_handler: false,
+            flow_sensitive_funcs: None,
+            check_races: false,
+            check_deadlocks: false,
             dump_stats: true,
             call_graph_output: None,
             pts_output: None,
+            fs_pts_output: None,
+            modref_output: None,
+            escape_output: None,
+            free_errors_output: None,
+            free_errors_format: FreeErrorsFormat::Json,
+            entry_reachability_output: None,
             mir_output: None,
             type_indices_output: None,
             dyn_calls_output: None,
@@ -196,10 +378,20 @@ impl AnalysisOptions {
             }
         };
 
-        if let Some(s) = matches.get_one::<String>("entry-func-name") {
-            self.entry_func = s.clone();
+        if let Some(entry_funcs) = matches.get_many::<String>("entry-func-name") {
+            self.entry_func = entry_funcs.cloned().collect();
         }
         self.entry_def_id = matches.get_one::<u32>("entry-func-id").cloned();
+        self.list_entries = matches.contains_id("list-entries");
+
+        if matches.contains_id("entry-mode") {
+            self.entry_mode = match matches.get_one::<String>("entry-mode").unwrap().as_str() {
+                "main" => EntryMode::Main,
+                "lib" => EntryMode::Lib,
+                "tests" => EntryMode::Tests,
+                _ => unreachable!(),
+            }
+        }
 
         if matches.contains_id("pta-type") {
             self.pta_type = match matches.get_one::<String>("pta-type").unwrap().as_str() {
@@ -214,11 +406,36 @@ impl AnalysisOptions {
         }
 
         self.cast_constraint = !matches.contains_id("no-cast-constraint");
-        self.stack_filtering = matches.contains_id("stack-filtering");
+        self.index_sensitive_arrays = matches.get_one::<usize>("index-sensitive-arrays").cloned();
+        self.variant_sensitive_enums = matches.contains_id("variant-sensitive-enums");
+        self.type_filter = matches.contains_id("type-filter");
+        self.prune_shared_ref_stores = matches.contains_id("prune-shared-ref-stores");
+        self.borrowck_filtering = matches.contains_id("borrowck-filtering");
+        self.stack_filtering = matches.contains_id("stack-filtering") || self.borrowck_filtering;
+        self.model_std_smart_pointers = matches.contains_id("model-std-smart-pointers");
+        self.model_std_collections = matches.contains_id("model-std-collections");
+        self.model_lazy_globals = matches.contains_id("model-lazy-globals");
+        self.resolve_global_allocator = matches.contains_id("resolve-global-allocator");
+        self.resolve_panic_handler = matches.contains_id("resolve-panic-handler");
+        self.flow_sensitive_funcs = matches.get_one::<Regex>("flow-sensitive-funcs").cloned();
+        self.check_races = matches.contains_id("check-races");
+        self.check_deadlocks = matches.contains_id("check-deadlocks");
         
         self.dump_stats = matches.contains_id("dump-stats");
         self.call_graph_output = matches.get_one::<String>("call-graph-output").cloned();
         self.pts_output = matches.get_one::<String>("pts-output").cloned();
+        self.fs_pts_output = matches.get_one::<String>("fs-pts-output").cloned();
+        self.modref_output = matches.get_one::<String>("modref-output").cloned();
+        self.escape_output = matches.get_one::<String>("escape-output").cloned();
+        self.free_errors_output = matches.get_one::<String>("free-errors-output").cloned();
+        if matches.contains_id("free-errors-format") {
+            self.free_errors_format = match matches.get_one::<String>("free-errors-format").unwrap().as_str() {
+                "json" => FreeErrorsFormat::Json,
+                "sarif" => FreeErrorsFormat::Sarif,
+                _ => unreachable!(),
+            }
+        }
+        self.entry_reachability_output = matches.get_one::<String>("entry-reachability-output").cloned();
         self.mir_output = matches.get_one::<String>("mir-output").cloned();
         self.unsafe_stat_output = matches.get_one::<String>("unsafe-stats-output").cloned();
         self.dyn_calls_output = matches.get_one::<String>("dyn-calls-output").cloned();
@@ -37,6 +37,9 @@ impl<'pta, 'tcx, 'compilation> AndersenStat<'pta, 'tcx, 'compilation> {
             .write_all("----------------------------------------------------------\n".as_bytes())
             .expect("Unable to write data");
         self.dump_pts_stat(&mut stat_writer);
+        if let Some(sf) = &self.pta.stack_filter {
+            sf.dump_stack_filter_stat(&mut stat_writer);
+        }
         stat_writer
             .write_all("##########################################################\n".as_bytes())
             .expect("Unable to write data");
@@ -89,6 +92,9 @@ impl<'pta, 'tcx, 'compilation, S: ContextStrategy> ContextSensitiveStat<'pta, 't
             .write_all("----------------------------------------------------------\n".as_bytes())
             .expect("Unable to write data");
         self.dump_pts_stat(&mut stat_writer);
+        if let Some(sf) = &self.pta.stack_filter {
+            sf.dump_stack_filter_stat(&mut stat_writer);
+        }
         stat_writer
             .write_all("##########################################################\n".as_bytes())
             .expect("Unable to write data");
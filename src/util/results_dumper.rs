@@ -4,8 +4,8 @@
 // LICENSE file in the root directory of this source tree.
 
 use log::*;
-use petgraph::visit::EdgeRef;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use petgraph::visit::{Dfs, EdgeRef};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::rc::Rc;
@@ -16,7 +16,7 @@ use crate::mir::call_site::{BaseCallSite, CallType};
 use crate::mir::context::{Context, ContextId};
 use crate::mir::function::FuncId;
 use crate::mir::analysis_context::AnalysisContext;
-use crate::mir::path::PathEnum;
+use crate::mir::path::{Path, PathEnum, PathSelector};
 use crate::pta::DiffPTDataTy;
 use crate::pta::strategies::context_strategy::ContextStrategy;
 use crate::pts_set::points_to::PointsToSet;
@@ -65,6 +65,12 @@ pub fn dump_results<P: PAGPath, F, S>(
         info!("Dumping dynamically resolved calls...");
         dump_dyn_calls(acx, call_graph, dyn_calls_output);
     }
+
+    // dump reachable functions of each entry function
+    if let Some(entry_reachability_output) = &acx.analysis_options.entry_reachability_output {
+        info!("Dumping reachable functions of entry functions...");
+        dump_entry_reachability(acx, call_graph, entry_reachability_output);
+    }
 }
 
 
@@ -133,6 +139,8 @@ pub fn dump_pts_for<P: PAGPath>(pt_data: &DiffPTDataTy, pag: &PAG<P>, node_id: P
 
 pub fn dump_ci_pts<P: PAGPath>(acx: &AnalysisContext, pt_data: &DiffPTDataTy, pag: &PAG<P>, grouped_pts_path: &String) {
     let mut grouped_pts: BTreeMap<FuncId, HashMap<&PathEnum, HashSet<&PathEnum>>> = BTreeMap::new();
+    // The points-to sets of the pointers contained in each variant of the enum values
+    let mut variant_pts: BTreeMap<FuncId, HashMap<Rc<Path>, HashSet<&PathEnum>>> = BTreeMap::new();
     let pts_map = &pt_data.propa_pts_map;
     let mut pts_writer = BufWriter::new(match &grouped_pts_path[..] {
         "stdout" => Box::new(std::io::stdout()) as Box<dyn Write>,
@@ -150,6 +158,17 @@ pub fn dump_ci_pts<P: PAGPath>(acx: &AnalysisContext, pt_data: &DiffPTDataTy, pa
             for pointee in pts {
                 tmp_pts.insert(pag.node_path(pointee).value());
             }
+            if acx.analysis_options.variant_sensitive_enums {
+                if let PathEnum::QualifiedPath { projection, .. } = value {
+                    if let Some(i) = projection.iter().rposition(|elem| matches!(elem, PathSelector::Downcast(..))) {
+                        let variant = Path::truncate_projection_elems(&var.ci_path(), i + 1);
+                        let tmp_pts = variant_pts.entry(func_id).or_default().entry(variant).or_default();
+                        for pointee in pts {
+                            tmp_pts.insert(pag.node_path(pointee).value());
+                        }
+                    }
+                }
+            }
         }
     }
     for (func_id, pts_map) in grouped_pts {
@@ -169,6 +188,21 @@ pub fn dump_ci_pts<P: PAGPath>(acx: &AnalysisContext, pt_data: &DiffPTDataTy, pa
                 .write_all("}\n".as_bytes())
                 .expect("Unable to write data");
         }
+        if let Some(variant_pts_map) = variant_pts.get(&func_id) {
+            for (variant, pts) in variant_pts_map {
+                pts_writer
+                    .write_all(format!("\t[variant] {:?} ({:?}) ==> {{ ", variant, pts.len()).as_bytes())
+                    .expect("Unable to write data");
+                for pointee in pts {
+                    pts_writer
+                        .write_all(format!("{:?} ", pointee).as_bytes())
+                        .expect("Unable to write data");
+                }
+                pts_writer
+                    .write_all("}\n".as_bytes())
+                    .expect("Unable to write data");
+            }
+        }
     }
 }
 
@@ -203,6 +237,38 @@ pub fn dump_mir<F: CGFunction + Into<FuncId>, S: CGCallSite>(
     }
 }
 
+/// Dumps the functions reachable from each entry function in JSON format, which can be used as
+/// a static coverage map of the tests when analyzing with `--entry-mode tests`.
+pub fn dump_entry_reachability<F, S>(
+    acx: &AnalysisContext,
+    call_graph: &CallGraph<F, S>,
+    reachability_path: &String,
+) where
+    F: CGFunction + Into<FuncId>,
+    S: CGCallSite,
+{
+    let mut entry_reachability: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for node_id in call_graph.graph.node_indices() {
+        let func_id = call_graph.graph[node_id].func.into();
+        if !acx.entry_points.contains(&func_id) {
+            continue;
+        }
+        let reach_funcs = entry_reachability
+            .entry(acx.get_function_reference(func_id).to_string())
+            .or_default();
+        let mut dfs = Dfs::new(&call_graph.graph, node_id);
+        while let Some(reach_node_id) = dfs.next(&call_graph.graph) {
+            let reach_func_id = call_graph.graph[reach_node_id].func.into();
+            reach_funcs.insert(acx.get_function_reference(reach_func_id).to_string());
+        }
+    }
+    let output = serde_json::to_string_pretty(&entry_reachability).expect("Unable to serialize data");
+    match &reachability_path[..] {
+        "stdout" => println!("{}", output),
+        _ => std::fs::write(reachability_path, output).expect("Unable to write data"),
+    }
+}
+
 pub fn dump_dyn_calls<F: CGFunction, S: CGCallSite>(
     acx: &AnalysisContext, 
     call_graph: &CallGraph<F, S>, 
@@ -405,7 +471,8 @@ fn path_func_id(value: &PathEnum) -> Option<FuncId> {
         PathEnum::Function(..) 
         | PathEnum::PromotedArgumentV1Array 
         | PathEnum::PromotedStrRefArray 
-        | PathEnum::Type(..) => None,
+        | PathEnum::Type(..)
+        | PathEnum::AbstractObject { .. } => None,
     }
 }
 
@@ -6,6 +6,7 @@
 use log::*;
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::{GenericArgKind, GenericArgsRef};
+use rustc_middle::ty::layout::LayoutCx;
 use rustc_middle::ty::{
     Const, ExistentialPredicate, FieldDef, ParamEnv, 
     PolyFnSig, Ty, TyCtxt, TyKind, TypeAndMut
@@ -89,11 +90,12 @@ impl<'tcx> PointerProjectionsCache<'tcx> {
     pub fn get_pointer_projections(
         &mut self,
         tcx: TyCtxt<'tcx>,
-        base_ty: Ty<'tcx>
+        base_ty: Ty<'tcx>,
+        index_sensitive_arrays: Option<usize>,
     ) -> &Vec<(ProjectionElems, Ty<'tcx>)> {
         match self.ptr_projs_cache.entry(base_ty) {
             Entry::Occupied(o) => o.into_mut(),
-            Entry::Vacant(v) => v.insert(get_pointer_projections(tcx, base_ty)),
+            Entry::Vacant(v) => v.insert(get_pointer_projections(tcx, base_ty, index_sensitive_arrays)),
         }
     }
 }
@@ -315,7 +317,12 @@ impl<'tcx> PathCastCache<'tcx> {
         } else {
             // When casting a pointer to a struct to its first field, we return the first field directly
             let fields_at_start_location =
-                fields_at_start_location(acx.tcx, path.clone(), original_ty);
+                fields_at_start_location(
+                    acx.tcx,
+                    path.clone(),
+                    original_ty,
+                    acx.analysis_options.variant_sensitive_enums,
+                );
             for (field, field_ty) in fields_at_start_location {
                 if equal_types(acx.tcx, field_ty, ty) {
                     return Some(field);
@@ -359,7 +366,12 @@ impl<'tcx> PathCastCache<'tcx> {
             return Some(path);
         } else {
             let fields_at_start_location =
-                fields_at_start_location(acx.tcx, path.clone(), original_ty);
+                fields_at_start_location(
+                    acx.tcx,
+                    path.clone(),
+                    original_ty,
+                    acx.analysis_options.variant_sensitive_enums,
+                );
             for (field, field_ty) in fields_at_start_location {
                 if equal_types(acx.tcx, field_ty, ty) {
                     return Some(field);
@@ -388,7 +400,7 @@ impl<'tcx> PathCastCache<'tcx> {
                     // to get the orginal path
                     Self::get_regularized_path(acx, Path::truncate_projection_elems(&path, projection.len() - 1))
                 }
-                PathSelector::Index | PathSelector::UnionField(..) => {
+                PathSelector::Index | PathSelector::ConstantIndex(..) | PathSelector::UnionField(..) => {
                     // If this path is an index path of an array, remove the index selector
                     Self::get_regularized_path(acx, Path::truncate_projection_elems(&path, projection.len() - 1))
                 }
@@ -415,8 +427,13 @@ impl<'tcx> PathCastCache<'tcx> {
                     }
                 }
                 PathSelector::Downcast(_) => {
-                    // If this path is an downcast path of an enum, remove the downcast selector
-                    Self::get_regularized_path(acx, Path::truncate_projection_elems(&path, projection.len() - 1))
+                    if acx.analysis_options.variant_sensitive_enums {
+                        // Keep the variants of an enum distinct
+                        path
+                    } else {
+                        // If this path is an downcast path of an enum, remove the downcast selector
+                        Self::get_regularized_path(acx, Path::truncate_projection_elems(&path, projection.len() - 1))
+                    }
                 }
                 _ => path,
             }
@@ -658,6 +675,16 @@ pub fn is_basic_pointer(ty: Ty<'_>) -> bool {
     }
 }
 
+/// Returns true if `ty` is a shared reference to a type without interior mutability, i.e. a type
+/// that does not contain an `UnsafeCell`. Such a reference cannot be used to write the pointee.
+/// Raw pointers are not considered, as they may be derived from mutable references.
+pub fn is_shared_ref_to_freeze<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    match ty.kind() {
+        TyKind::Ref(_, pointee_ty, rustc_middle::mir::Mutability::Not) => pointee_ty.is_freeze(tcx, ParamEnv::reveal_all()),
+        _ => false,
+    }
+}
+
 /// repr(transparent) is used on structs with a single non-zero-sized field (there may be
 /// additional zero-sized fields).
 /// Get the type and field index after removing the transparent wrapper
@@ -815,8 +842,14 @@ pub fn projections_and_types<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(Proj
     return fields;
 }
 
-/// Returns all the projections of pointer type fields contained in the given type
-pub fn get_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(ProjectionElems, Ty<'tcx>)> {
+/// Returns all the projections of pointer type fields contained in the given type.
+/// The elements of the arrays not longer than `index_sensitive_arrays` are selected by their
+/// constant indices, while the elements of the other arrays and slices are selected by `Index`.
+pub fn get_pointer_projections<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    index_sensitive_arrays: Option<usize>,
+) -> Vec<(ProjectionElems, Ty<'tcx>)> {
     let mut ptr_projs = Vec::new();
     match ty.kind() {
         TyKind::Adt(adt_def, args) if adt_def.is_struct() || adt_def.is_union() => {
@@ -833,7 +866,7 @@ pub fn get_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(Pr
                     ptr_projs.push((vec![field], field_ty));
                 } else {
                     // recursively get the pointer type subfields of this field
-                    for (mut subfield, subfield_ty) in get_pointer_projections(tcx, field_ty) {
+                    for (mut subfield, subfield_ty) in get_pointer_projections(tcx, field_ty, index_sensitive_arrays) {
                         subfield.insert(0, field);
                         ptr_projs.push((subfield, subfield_ty));
                     }
@@ -855,7 +888,7 @@ pub fn get_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(Pr
                                 ptr_projs.push((vec![downcast, field], field_ty));
                             } else {
                                 // recursively get the pointer type subfields of this field
-                                for (mut subfield, subfield_ty) in get_pointer_projections(tcx, field_ty) {
+                                for (mut subfield, subfield_ty) in get_pointer_projections(tcx, field_ty, index_sensitive_arrays) {
                                     let mut projection = vec![downcast, field];
                                     projection.append(&mut subfield);
                                     ptr_projs.push((projection, subfield_ty));
@@ -868,13 +901,21 @@ pub fn get_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(Pr
         TyKind::Array(elem_ty, _) | TyKind::Slice(elem_ty) => {
             // Slice is the pointee of an array slice. Written as [T].
             // It doesn't have a size known at compile-time, therefore it must be referenced in program.
-            if elem_ty.is_any_ptr() {
-                ptr_projs.push((vec![PathSelector::Index], *elem_ty));
+            let indices = match index_sensitive_array_len(tcx, ty, index_sensitive_arrays) {
+                Some(len) => (0..len).map(PathSelector::ConstantIndex).collect(),
+                None => vec![PathSelector::Index],
+            };
+            let elem_ptr_projs = if elem_ty.is_any_ptr() {
+                vec![(vec![], *elem_ty)]
             } else {
                 // recursively get the pointer type subfields of the array element
-                for (mut subfield, subfield_ty) in get_pointer_projections(tcx, *elem_ty) {
-                    subfield.insert(0, PathSelector::Index);
-                    ptr_projs.push((subfield, subfield_ty));
+                get_pointer_projections(tcx, *elem_ty, index_sensitive_arrays)
+            };
+            for index in indices {
+                for (subfield, subfield_ty) in &elem_ptr_projs {
+                    let mut projection = vec![index];
+                    projection.extend_from_slice(subfield);
+                    ptr_projs.push((projection, *subfield_ty));
                 }
             }
         }
@@ -888,7 +929,7 @@ pub fn get_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(Pr
                     ptr_projs.push((vec![field], *field_ty));
                 } else {
                     // recursively get the pointer type subfields of this field
-                    for (mut subfield, subfield_ty) in get_pointer_projections(tcx, *field_ty) {
+                    for (mut subfield, subfield_ty) in get_pointer_projections(tcx, *field_ty, index_sensitive_arrays) {
                         subfield.insert(0, field);
                         ptr_projs.push((subfield, subfield_ty));
                     }
@@ -901,7 +942,7 @@ pub fn get_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(Pr
                     ptr_projs.push((vec![PathSelector::Field(i)], field_ty));
                 } else {
                     // recursively get the pointer type subfields of this field
-                    for (mut subfield, subfield_ty) in get_pointer_projections(tcx, field_ty) {
+                    for (mut subfield, subfield_ty) in get_pointer_projections(tcx, field_ty, index_sensitive_arrays) {
                         subfield.insert(0, PathSelector::Field(i));
                         ptr_projs.push((subfield, subfield_ty));
                     }
@@ -916,6 +957,48 @@ pub fn get_pointer_projections<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<(Pr
     ptr_projs
 }
 
+/// Returns the length of the given array type if its elements are distinguished by constant
+/// indices, i.e. if the length is known and not larger than `index_sensitive_arrays`.
+///
+/// Only the innermost dimension of nested arrays is distinguished: an array whose elements contain
+/// an index-sensitive array is summarized by a single element. Otherwise each nested dimension
+/// would multiply the index variants of the paths into the array.
+pub fn index_sensitive_array_len<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    index_sensitive_arrays: Option<usize>,
+) -> Option<usize> {
+    let max_len = index_sensitive_arrays?;
+    if let TyKind::Array(elem_ty, length) = ty.kind() {
+        let length = length.try_eval_target_usize(tcx, ParamEnv::reveal_all())? as usize;
+        if length <= max_len && !contains_index_sensitive_array(tcx, *elem_ty, max_len) {
+            return Some(length);
+        }
+    }
+    None
+}
+
+/// Returns true if the given type contains an array whose elements are distinguished by constant
+/// indices, without going through pointers.
+fn contains_index_sensitive_array<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, max_len: usize) -> bool {
+    match ty.kind() {
+        TyKind::Array(elem_ty, length) => {
+            matches!(length.try_eval_target_usize(tcx, ParamEnv::reveal_all()), Some(length) if length as usize <= max_len)
+                || contains_index_sensitive_array(tcx, *elem_ty, max_len)
+        }
+        TyKind::Adt(adt_def, args) => adt_def
+            .all_fields()
+            .any(|field| contains_index_sensitive_array(tcx, field_ty(tcx, field, args), max_len)),
+        TyKind::Closure(..) | TyKind::Coroutine(..) => closure_field_types(ty)
+            .into_iter()
+            .any(|field_ty| contains_index_sensitive_array(tcx, field_ty, max_len)),
+        TyKind::Tuple(tuple_types) => tuple_types
+            .iter()
+            .any(|field_ty| contains_index_sensitive_array(tcx, field_ty, max_len)),
+        _ => false,
+    }
+}
+
 #[inline]
 pub fn get_array_length<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -961,9 +1044,14 @@ pub fn flatten_fields<'tcx>(
     param_env: ParamEnv<'tcx>,
     path: Rc<Path>,
     path_ty: Ty<'tcx>,
+    variant_sensitive_enums: bool,
 ) -> Vec<(usize, Rc<Path>, Ty<'tcx>)> {
     let mut flattened_fields = Vec::new();
-    flatten_fields_recursively(tcx, param_env, path, path_ty, 0, &mut flattened_fields);
+    flatten_fields_recursively(tcx, param_env, path, path_ty, 0, &mut flattened_fields, variant_sensitive_enums);
+    if variant_sensitive_enums {
+        // The fields of different variants overlap, keep the flattened fields ordered by their offsets.
+        flattened_fields.sort_by_key(|(offset, _, _)| *offset);
+    }
     return flattened_fields;
 }
 
@@ -974,12 +1062,17 @@ fn flatten_fields_recursively<'tcx>(
     path_ty: Ty<'tcx>,
     base_offset: usize,
     flattened_fields: &mut Vec<(usize, Rc<Path>, Ty<'tcx>)>,
+    variant_sensitive_enums: bool,
 ) {
     match path_ty.kind() {
         TyKind::Adt(adt_def, args) => {
             if adt_def.is_enum() {
-                // Todo: we currently do not flatten a enum type variable
-                flattened_fields.push((base_offset, path, path_ty));
+                if variant_sensitive_enums {
+                    flatten_enum_variants(tcx, param_env, path, path_ty, base_offset, flattened_fields);
+                } else {
+                    // Todo: we currently do not flatten a enum type variable
+                    flattened_fields.push((base_offset, path, path_ty));
+                }
                 return;
             }
             if adt_def.is_union() {
@@ -1004,6 +1097,7 @@ fn flatten_fields_recursively<'tcx>(
                         field_ty,
                         base_offset,
                         flattened_fields,
+                        variant_sensitive_enums,
                     );
                 }
                 return;
@@ -1032,6 +1126,7 @@ fn flatten_fields_recursively<'tcx>(
                                 field_ty,
                                 offset,
                                 flattened_fields,
+                                variant_sensitive_enums,
                             );
                         }
                     } 
@@ -1055,6 +1150,7 @@ fn flatten_fields_recursively<'tcx>(
                     *elem_ty,
                     offset,
                     flattened_fields,
+                    variant_sensitive_enums,
                 );
                 offset += elem_size;
             }
@@ -1079,6 +1175,7 @@ fn flatten_fields_recursively<'tcx>(
                             field_ty,
                             offset,
                             flattened_fields,
+                            variant_sensitive_enums,
                         );
                     }
                 }
@@ -1097,6 +1194,7 @@ fn flatten_fields_recursively<'tcx>(
                 *elem_ty,
                 base_offset,
                 flattened_fields,
+                variant_sensitive_enums,
             );
         }
         _ => {
@@ -1109,14 +1207,107 @@ fn flatten_fields_recursively<'tcx>(
     }
 }
 
+/// Returns the indices of the pairs of source and target flattened fields located at the same offsets.
+/// Both vectors of flattened fields should be ordered by offsets. Multiple fields can be located at
+/// the same offset if they belong to different variants of an enum.
+pub fn pair_flattened_fields<P, Q>(
+    src_flattened_fields: &[(usize, P, Ty<'_>)],
+    tgt_flattened_fields: &[(usize, Q, Ty<'_>)],
+) -> Vec<(usize, usize)> {
+    let src_len = src_flattened_fields.len();
+    let tgt_len = tgt_flattened_fields.len();
+    let mut pairs = Vec::new();
+    let mut src_field_index = 0;
+    let mut tgt_field_index = 0;
+    while tgt_field_index < tgt_len && src_field_index < src_len {
+        let tgt_offset = tgt_flattened_fields[tgt_field_index].0;
+        let src_offset = src_flattened_fields[src_field_index].0;
+        match tgt_offset.cmp(&src_offset) {
+            std::cmp::Ordering::Less => {
+                tgt_field_index += 1;
+                continue;
+            }
+            std::cmp::Ordering::Greater => {
+                src_field_index += 1;
+                continue;
+            }
+            std::cmp::Ordering::Equal => {}
+        }
+        let src_end = (src_field_index..src_len)
+            .find(|i| src_flattened_fields[*i].0 != src_offset)
+            .unwrap_or(src_len);
+        let tgt_end = (tgt_field_index..tgt_len)
+            .find(|i| tgt_flattened_fields[*i].0 != tgt_offset)
+            .unwrap_or(tgt_len);
+        for src_index in src_field_index..src_end {
+            for tgt_index in tgt_field_index..tgt_end {
+                pairs.push((src_index, tgt_index));
+            }
+        }
+        src_field_index = src_end;
+        tgt_field_index = tgt_end;
+    }
+    pairs
+}
+
+/// Flattens the fields of each variant of an enum according to the layout of the variant,
+/// so that the fields of different variants are kept distinct.
+fn flatten_enum_variants<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    path: Rc<Path>,
+    path_ty: Ty<'tcx>,
+    base_offset: usize,
+    flattened_fields: &mut Vec<(usize, Rc<Path>, Ty<'tcx>)>,
+) {
+    let TyKind::Adt(adt_def, args) = path_ty.kind() else {
+        return;
+    };
+    let Ok(layout) = layout_of(tcx, param_env, path_ty) else {
+        warn!("Failed to get the layout of the enum type: {:?}", path_ty);
+        flattened_fields.push((base_offset, path, path_ty));
+        return;
+    };
+    let layout_cx = LayoutCx { tcx, param_env };
+    for (variant_idx, variant) in adt_def.variants().iter_enumerated() {
+        let variant_layout = layout.for_variant(&layout_cx, variant_idx);
+        // Uninhabited variants have no fields to be flattened
+        if let rustc_target::abi::FieldsShape::Arbitrary { offsets, .. } = variant_layout.layout.fields() {
+            let variant_path = Path::new_downcast(path.clone(), variant_idx.as_usize());
+            for (index, field) in variant.fields.iter().enumerate() {
+                let field_path = Path::new_field(variant_path.clone(), index);
+                let field_ty = field_ty(tcx, field, args);
+                let offset = offsets[index.into()].bytes_usize() + base_offset;
+                flatten_fields_recursively(
+                    tcx,
+                    param_env,
+                    field_path,
+                    field_ty,
+                    offset,
+                    flattened_fields,
+                    true,
+                );
+            }
+        }
+    }
+}
+
 pub fn fields_at_start_location<'tcx>(
     tcx: TyCtxt<'tcx>,
     path: Rc<Path>,
     path_ty: Ty<'tcx>,
+    variant_sensitive_enums: bool,
 ) -> Vec<(Rc<Path>, Ty<'tcx>)> {
     let param_env = rustc_middle::ty::ParamEnv::reveal_all();
     let mut fields_at_start_location = Vec::new();
-    find_fields_at_start_location(tcx, param_env, path, path_ty, &mut fields_at_start_location);
+    find_fields_at_start_location(
+        tcx,
+        param_env,
+        path,
+        path_ty,
+        &mut fields_at_start_location,
+        variant_sensitive_enums,
+    );
     return fields_at_start_location;
 }
 
@@ -1126,10 +1317,14 @@ fn find_fields_at_start_location<'tcx>(
     path: Rc<Path>,
     path_ty: Ty<'tcx>,
     fields_at_start_location: &mut Vec<(Rc<Path>, Ty<'tcx>)>,
+    variant_sensitive_enums: bool,
 ) {
     match path_ty.kind() {
         TyKind::Adt(adt_def, args) => {
             if adt_def.is_enum() {
+                if variant_sensitive_enums {
+                    find_variant_fields_at_start_location(tcx, param_env, path, path_ty, fields_at_start_location);
+                }
                 return;
             }
             if adt_def.is_union() {
@@ -1145,6 +1340,7 @@ fn find_fields_at_start_location<'tcx>(
                         field_path,
                         field_ty,
                         fields_at_start_location,
+                        variant_sensitive_enums,
                     );
                 });
                 return;
@@ -1176,6 +1372,7 @@ fn find_fields_at_start_location<'tcx>(
                                     field_path,
                                     field_ty,
                                     fields_at_start_location,
+                                    variant_sensitive_enums,
                                 );
                             }
                         }
@@ -1195,6 +1392,7 @@ fn find_fields_at_start_location<'tcx>(
                 index_path,
                 *elem_ty,
                 fields_at_start_location,
+                variant_sensitive_enums,
             );
         }
         TyKind::Tuple(types) => {
@@ -1219,6 +1417,7 @@ fn find_fields_at_start_location<'tcx>(
                                 field_path,
                                 field_ty,
                                 fields_at_start_location,
+                                variant_sensitive_enums,
                             );
                         }
                     }
@@ -1232,6 +1431,44 @@ fn find_fields_at_start_location<'tcx>(
     }
 }
 
+/// Finds the fields of each variant of an enum that are located at the start of the enum.
+fn find_variant_fields_at_start_location<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    path: Rc<Path>,
+    path_ty: Ty<'tcx>,
+    fields_at_start_location: &mut Vec<(Rc<Path>, Ty<'tcx>)>,
+) {
+    let TyKind::Adt(adt_def, args) = path_ty.kind() else {
+        return;
+    };
+    let Ok(layout) = layout_of(tcx, param_env, path_ty) else {
+        return;
+    };
+    let layout_cx = LayoutCx { tcx, param_env };
+    for (variant_idx, variant) in adt_def.variants().iter_enumerated() {
+        let variant_layout = layout.for_variant(&layout_cx, variant_idx);
+        if let rustc_target::abi::FieldsShape::Arbitrary { offsets, .. } = variant_layout.layout.fields() {
+            let variant_path = Path::new_downcast(path.clone(), variant_idx.as_usize());
+            for (index, field) in variant.fields.iter().enumerate() {
+                if offsets[index.into()].bytes_usize() == 0 {
+                    let field_path = Path::new_field(variant_path.clone(), index);
+                    let field_ty = field_ty(tcx, field, args);
+                    fields_at_start_location.push((field_path.clone(), field_ty));
+                    find_fields_at_start_location(
+                        tcx,
+                        param_env,
+                        field_path,
+                        field_ty,
+                        fields_at_start_location,
+                        true,
+                    );
+                }
+            }
+        }
+    }
+}
+
 
 /// Returns true if the two given types are equal after erasing regions
 pub fn equal_types<'tcx>(tcx: TyCtxt<'tcx>, ty1: Ty<'tcx>, ty2: Ty<'tcx>) -> bool {
@@ -1253,6 +1490,40 @@ pub fn equal_types<'tcx>(tcx: TyCtxt<'tcx>, ty1: Ty<'tcx>, ty2: Ty<'tcx>) -> boo
     return ty1 == ty2;
 }
 
+/// Returns true if an object of type `obj_ty` can be the target of a pointer whose static
+/// pointee type is `pointee_ty`.
+/// Trait objects, slices, type parameters and raw bytes can refer to objects of different
+/// types, therefore they are compatible with any object type in an element-wise sense.
+pub fn is_compatible_pointee_type<'tcx>(tcx: TyCtxt<'tcx>, pointee_ty: Ty<'tcx>, obj_ty: Ty<'tcx>) -> bool {
+    if equal_types(tcx, pointee_ty, obj_ty) {
+        return true;
+    }
+    let is_imprecise = |ty: Ty<'tcx>| {
+        matches!(
+            ty.kind(),
+            TyKind::Dynamic(..)
+                | TyKind::Param(..)
+                | TyKind::Alias(..)
+                | TyKind::Foreign(..)
+                | TyKind::Str
+                | TyKind::Never
+                | TyKind::Int(rustc_middle::ty::IntTy::I8)
+                | TyKind::Uint(rustc_middle::ty::UintTy::U8)
+        ) || ty.is_unit()
+    };
+    if is_imprecise(pointee_ty) || is_imprecise(obj_ty) {
+        return true;
+    }
+    match (pointee_ty.kind(), obj_ty.kind()) {
+        // A slice pointer can point to an array or a single element.
+        (TyKind::Slice(elem_ty), TyKind::Array(obj_elem_ty, _)) => {
+            is_compatible_pointee_type(tcx, *elem_ty, *obj_elem_ty)
+        }
+        (TyKind::Slice(elem_ty), _) => is_compatible_pointee_type(tcx, *elem_ty, obj_ty),
+        _ => false,
+    }
+}
+
 /// Returns true if the given two pointer types are equivalent.
 /// We suppose that a reference type and a mut/const raw pointer type are equivalent if
 /// their dereference types are equivalent.  
@@ -1337,6 +1608,7 @@ pub fn try_eval_path_type<'tcx>(acx: &mut AnalysisContext<'tcx, '_>, path: &Rc<P
         PathEnum::HeapObj { .. } => Some(acx.tcx.types.u8),
         PathEnum::Constant => None,
         PathEnum::StaticVariable { def_id } => Some(acx.tcx.type_of(def_id).skip_binder()),
+        PathEnum::AbstractObject { type_index } => acx.get_type_by_index(*type_index),
         PathEnum::QualifiedPath { base, projection } => {
             let mut base_ty = try_eval_path_type(acx, base).expect("Unable to evaluate the base type");
             let mut projection = &projection[..];
@@ -1358,7 +1630,7 @@ pub fn try_eval_path_type<'tcx>(acx: &mut AnalysisContext<'tcx, '_>, path: &Rc<P
                             }
                         }
                     }
-                    PathSelector::Index => {
+                    PathSelector::Index | PathSelector::ConstantIndex(..) => {
                         match base_ty.kind() {
                             // the type `Str` cannot be indexed
                             TyKind::Array(..) | TyKind::Slice(..) => get_element_type(acx.tcx, base_ty),
@@ -0,0 +1,236 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! End-to-end tests of the library-level API. The analysis is run on the programs in
+//! `tests/unit_tests` and the results passed to the result hook are checked against known answers.
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_middle;
+
+use std::collections::BTreeSet;
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+
+use rupta::api::{AnalysisResult, MemoryObject, ObjectBase, Place};
+use rupta::mir::path::PathSelector;
+use rupta::pta::PTACallbacks;
+use rupta::util;
+use rupta::util::options::AnalysisOptions;
+
+/// Runs the analysis with the given options on the program `tests/unit_tests/<name>.rs` and
+/// returns what `query` computes from the results.
+fn analyze<T, Q>(name: &str, pta_args: &[&str], query: Q) -> T
+where
+    T: Send + 'static,
+    Q: for<'tcx> FnMut(TyCtxt<'tcx>, &AnalysisResult) -> T + Send + 'static,
+{
+    let mut options = AnalysisOptions::default();
+    let pta_args: Vec<String> = std::iter::once("pta").chain(pta_args.iter().copied()).map(String::from).collect();
+    options.parse_from_args(&pta_args, true);
+
+    let out_dir = env::temp_dir().join(format!("rupta-test-{}-{}", name, std::process::id()));
+    let rustc_args: Vec<String> = vec![
+        "rustc".into(),
+        format!("{}/tests/unit_tests/{}.rs", env!("CARGO_MANIFEST_DIR"), name),
+        "--edition=2021".into(),
+        "--emit=metadata".into(),
+        "--out-dir".into(),
+        out_dir.to_string_lossy().into_owned(),
+        "--sysroot".into(),
+        util::find_sysroot(),
+        "-Z".into(),
+        "always-encode-mir".into(),
+    ];
+
+    let answer = Arc::new(Mutex::new(None));
+    let hook_answer = answer.clone();
+    let mut query = query;
+    let mut callbacks = PTACallbacks::new(options).with_result_hook(Box::new(move |tcx, result| {
+        *hook_answer.lock().unwrap() = Some(query(tcx, result));
+    }));
+    let status = rustc_driver::catch_fatal_errors(|| rustc_driver::RunCompiler::new(&rustc_args, &mut callbacks).run());
+    let _ = std::fs::remove_dir_all(&out_dir);
+    assert!(matches!(status, Ok(Ok(()))), "failed to compile {name}.rs");
+    let answer = answer.lock().unwrap().take();
+    answer.expect("the analysis has not been run")
+}
+
+/// Returns the id of the function with the given name in the local crate.
+fn def_id_of(tcx: TyCtxt<'_>, name: &str) -> DefId {
+    tcx.hir()
+        .body_owners()
+        .map(|def_id| def_id.to_def_id())
+        .find(|def_id| tcx.opt_item_name(*def_id).is_some_and(|item| item.as_str() == name))
+        .unwrap_or_else(|| panic!("no function named {name}"))
+}
+
+/// Returns the local of the user variable with the given name in the function.
+fn local_of(tcx: TyCtxt<'_>, def_id: DefId, name: &str) -> mir::Local {
+    tcx.optimized_mir(def_id)
+        .var_debug_info
+        .iter()
+        .find_map(|info| match info.value {
+            mir::VarDebugInfoContents::Place(place) if info.name.as_str() == name && place.projection.is_empty() => {
+                Some(place.local)
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no variable named {name}"))
+}
+
+/// Names a memory object by its function and user variable, e.g. `main::a`, or by its debug
+/// representation if it is not a user variable.
+fn object_name(tcx: TyCtxt<'_>, result: &AnalysisResult, object: &MemoryObject) -> String {
+    if let (ObjectBase::Local { func, local }, []) = (object.base, object.projection.as_slice()) {
+        let def_id = result.function_info(func).unwrap().def_id;
+        let variable = tcx.optimized_mir(def_id).var_debug_info.iter().find_map(|info| match info.value {
+            mir::VarDebugInfoContents::Place(place) if place.local == local && place.projection.is_empty() => {
+                Some(info.name)
+            }
+            _ => None,
+        });
+        if let Some(variable) = variable {
+            return format!("{}::{}", tcx.item_name(def_id), variable);
+        }
+    }
+    format!("{:?}", object)
+}
+
+/// Returns the names of the objects pointed to by the variable `func::var`.
+fn points_to_names(tcx: TyCtxt<'_>, result: &AnalysisResult, func: &str, var: &str) -> BTreeSet<String> {
+    let def_id = def_id_of(tcx, func);
+    result
+        .points_to(def_id, local_of(tcx, def_id, var), &[])
+        .into_iter()
+        .map(|object| object_name(tcx, result, object))
+        .collect()
+}
+
+fn names(names: &[&str]) -> BTreeSet<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn analysis_result_test() {
+    analyze("analysis_result", &[], |tcx, result| {
+        let run_id = def_id_of(tcx, "run");
+        let pick_id = def_id_of(tcx, "pick");
+        let run = result.function_instances(run_id).next().expect("run is not reachable");
+        let pick = result.function_instances(pick_id).next().expect("pick is not reachable");
+        let reachable: BTreeSet<_> = result.reachable_functions().collect();
+        assert!(reachable.contains(&run) && reachable.contains(&pick));
+
+        assert_eq!(points_to_names(tcx, result, "run", "p"), names(&["run::a", "run::b"]));
+        assert_eq!(points_to_names(tcx, result, "run", "r"), names(&["run::a", "run::b"]));
+        assert_eq!(points_to_names(tcx, result, "run", "q"), names(&["run::c"]));
+
+        let place = |var: &str, projection: Vec<PathSelector>| Place::new(run, local_of(tcx, run_id, var), projection);
+        let q_pointees: Vec<_> = result
+            .place_points_to(&place("q", vec![]))
+            .into_iter()
+            .map(|id| object_name(tcx, result, result.object(id)))
+            .collect();
+        assert_eq!(q_pointees, vec!["run::c".to_string()]);
+        assert!(result.may_alias(&place("p", vec![PathSelector::Deref]), &place("r", vec![PathSelector::Deref])));
+        assert!(result.may_alias(&place("q", vec![PathSelector::Deref]), &place("c", vec![])));
+        assert!(!result.may_alias(&place("p", vec![PathSelector::Deref]), &place("q", vec![PathSelector::Deref])));
+
+        let callers = result.callers(pick);
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].func, run);
+        assert_eq!(result.callees(&callers[0]), vec![pick]);
+    });
+}
+
+#[test]
+fn shared_ref_store_test() {
+    let query = |tcx: TyCtxt<'_>, result: &AnalysisResult| points_to_names(tcx, result, "shared", "_f");
+    assert_eq!(analyze("shared_ref_store", &[], query), names(&["shared::a", "shared::b"]));
+    assert_eq!(analyze("shared_ref_store", &["--prune-shared-ref-stores"], query), names(&["shared::a"]));
+}
+
+#[test]
+fn flow_sensitive_test() {
+    analyze("flow_sensitive", &["--flow-sensitive-funcs", "reassign"], |tcx, result| {
+        let reassign_id = def_id_of(tcx, "reassign");
+        let use_ref_id = def_id_of(tcx, "use_ref");
+        let reassign = result.function_instances(reassign_id).next().expect("reassign is not reachable");
+        let body = tcx.optimized_mir(reassign_id);
+        let use_ref_calls: Vec<mir::Location> = body
+            .basic_blocks
+            .iter_enumerated()
+            .filter(|(_, block)| match &block.terminator().kind {
+                mir::TerminatorKind::Call { func, .. } => func.const_fn_def().is_some_and(|(def_id, _)| def_id == use_ref_id),
+                _ => false,
+            })
+            .map(|(bb, _)| body.terminator_loc(bb))
+            .collect();
+        assert_eq!(use_ref_calls.len(), 3);
+
+        let place = |var: &str, projection: Vec<PathSelector>| Place::new(reassign, local_of(tcx, reassign_id, var), projection);
+        let pts_at = |place: &Place, location: mir::Location| -> BTreeSet<String> {
+            result
+                .pts_at(place, location)
+                .expect("no flow-sensitive results")
+                .into_iter()
+                .map(|id| object_name(tcx, result, result.object(id)))
+                .collect()
+        };
+        assert_eq!(points_to_names(tcx, result, "reassign", "p"), names(&["reassign::a", "reassign::b"]));
+        assert_eq!(pts_at(&place("p", vec![]), use_ref_calls[0]), names(&["reassign::a"]));
+        assert_eq!(pts_at(&place("p", vec![]), use_ref_calls[1]), names(&["reassign::b"]));
+        assert_eq!(pts_at(&place("s", vec![PathSelector::Field(0)]), use_ref_calls[2]), names(&["reassign::b"]));
+        assert_eq!(
+            pts_at(&place("q", vec![PathSelector::Deref, PathSelector::Field(0)]), use_ref_calls[2]),
+            names(&["reassign::b"])
+        );
+
+        let main = result.function_instances(def_id_of(tcx, "main")).next().unwrap();
+        assert!(result.pts_at(&Place::new(main, mir::Local::from_usize(1), vec![]), use_ref_calls[0]).is_none());
+    });
+}
+
+#[test]
+fn constant_index_place_test() {
+    let query = |tcx: TyCtxt<'_>, result: &AnalysisResult| {
+        let patch_id = def_id_of(tcx, "patch");
+        let patch = result.function_instances(patch_id).next().expect("patch is not reachable");
+        let body = tcx.optimized_mir(patch_id);
+        let handlers = local_of(tcx, patch_id, "handlers");
+        let element = |offset: u64, from_end: bool| {
+            let elem = mir::ProjectionElem::ConstantIndex { offset, min_length: 2, from_end };
+            let place = mir::Place { local: handlers, projection: tcx.mk_place_elems(&[elem]) };
+            let place = result.place_from_mir(tcx, body, patch, place);
+            let callees: BTreeSet<String> = result
+                .place_points_to(&place)
+                .into_iter()
+                .map(|id| match result.object(id).base {
+                    ObjectBase::Function(func) => result.function_info(func).unwrap().name.clone(),
+                    _ => object_name(tcx, result, result.object(id)),
+                })
+                .collect();
+            (place.projection, callees)
+        };
+        (element(0, false), element(1, false), element(1, true))
+    };
+    let (first, second, last) = analyze("index_sensitive_array", &["--index-sensitive-arrays", "8"], query);
+    let add_neg = names(&["index_sensitive_array::add", "index_sensitive_array::neg"]);
+    let sub_neg = names(&["index_sensitive_array::neg", "index_sensitive_array::sub"]);
+    assert_eq!(first, (vec![PathSelector::ConstantIndex(0)], add_neg));
+    assert_eq!(second, (vec![PathSelector::ConstantIndex(1)], sub_neg.clone()));
+    assert_eq!(last, (vec![PathSelector::ConstantIndex(1)], sub_neg));
+
+    let (first, _, last) = analyze("index_sensitive_array", &[], query);
+    let add_sub_neg = names(&["index_sensitive_array::add", "index_sensitive_array::neg", "index_sensitive_array::sub"]);
+    assert_eq!(first, (vec![PathSelector::Index], add_sub_neg.clone()));
+    assert_eq!(last, (vec![PathSelector::Index], add_sub_neg));
+}
@@ -0,0 +1,27 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the library-level API, checked by `tests/analysis_result.rs`.
+// `p` and `r` are expected to point to both `a` and `b` and `q` to `c` only, so that `*p` may
+// alias `*r` but not `*q`. `pick` is called once from `run`.
+
+fn pick<'a>(c: bool, x: &'a i32, y: &'a i32) -> &'a i32 {
+    if c {
+        x
+    } else {
+        y
+    }
+}
+
+fn run(a: i32, b: i32, c: i32) {
+    let p = pick(a < b, &a, &b);
+    let q = &c;
+    let r = p;
+    let _sum = *p + *q + *r;
+}
+
+fn main() {
+    run(1, 2, 3);
+}
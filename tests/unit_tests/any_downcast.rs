@@ -0,0 +1,61 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the downcasts of `dyn Any` trait objects.
+// The registry stores boxed plugins of different types. Each downcast is expected to yield only
+// the plugins of the requested type, e.g. `p` in the first loop only points to the `P1` object,
+// so each dynamic call to `run` resolves to the method of a single plugin type. The failed
+// downcast of `Box<dyn Any>` returns the original box in the `Err` variant.
+
+use std::any::Any;
+
+trait Plugin {
+    fn run(&self);
+}
+
+struct P1;
+struct P2;
+struct P3;
+
+impl Plugin for P1 {
+    fn run(&self) {}
+}
+
+impl Plugin for P2 {
+    fn run(&self) {}
+}
+
+impl Plugin for P3 {
+    fn run(&self) {}
+}
+
+fn main() {
+    let registry: Vec<Box<dyn Any>> = vec![Box::new(P1), Box::new(P2), Box::new(P3)];
+    for b in registry.iter() {
+        if let Some(p) = b.downcast_ref::<P1>() {
+            let d: &dyn Plugin = p;
+            d.run();
+        }
+    }
+    for b in registry {
+        match b.downcast::<P2>() {
+            Ok(p) => {
+                let d: Box<dyn Plugin> = p;
+                d.run();
+            }
+            Err(b) => {
+                if let Ok(p) = b.downcast::<P3>() {
+                    let d: Box<dyn Plugin> = p;
+                    d.run();
+                }
+            }
+        }
+    }
+    let mut s: Box<dyn Any + Send> = Box::new(P1);
+    if let Some(p) = s.downcast_mut::<P1>() {
+        let d: &dyn Plugin = p;
+        d.run();
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+// 
+// A test of atomic pointer operations.
+// The pointers stored via `AtomicPtr::{new, store, swap, compare_exchange, fetch_update}` 
+// are expected to flow to the pointers loaded from the same `AtomicPtr`.
+
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+struct Node {
+    value: i32,
+}
+
+fn load_store() {
+    let mut a = Node { value: 1 };
+    let mut b = Node { value: 2 };
+    let atomic = AtomicPtr::new(&mut a as *mut Node);
+    atomic.store(&mut b as *mut Node, Ordering::SeqCst);
+    let p = atomic.load(Ordering::SeqCst);
+    unsafe { (*p).value = 3; }
+}
+
+fn swap() {
+    let mut a = Node { value: 1 };
+    let mut b = Node { value: 2 };
+    let atomic = AtomicPtr::new(&mut a as *mut Node);
+    let old = atomic.swap(&mut b as *mut Node, Ordering::AcqRel);
+    let new = atomic.load(Ordering::Acquire);
+    unsafe { (*old).value = (*new).value; }
+}
+
+fn compare_exchange() {
+    let mut a = Node { value: 1 };
+    let mut b = Node { value: 2 };
+    let pa = &mut a as *mut Node;
+    let atomic = AtomicPtr::new(pa);
+    let res = atomic.compare_exchange(pa, &mut b as *mut Node, Ordering::SeqCst, Ordering::Relaxed);
+    let p = match res {
+        Ok(old) => old,
+        Err(cur) => cur,
+    };
+    let q = atomic.load(Ordering::SeqCst);
+    unsafe { (*p).value = (*q).value; }
+}
+
+fn fetch_update() {
+    let mut a = Node { value: 1 };
+    let mut b = Node { value: 2 };
+    let pb = &mut b as *mut Node;
+    let atomic = AtomicPtr::new(&mut a as *mut Node);
+    let _ = atomic.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(pb));
+    let p = atomic.load(Ordering::SeqCst);
+    unsafe { (*p).value = 3; }
+}
+
+fn main() {
+    load_store();
+    swap();
+    compare_exchange();
+    fetch_update();
+}
@@ -0,0 +1,48 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the borrowck-informed filtering of stack objects with `--borrowck-filtering`.
+// In `compute`, the borrow of `x` is only passed to `first`, so `x` is a confined local that can
+// only be observed by `first` and `id`. Both `compute` and `log` call `first`, whose merged
+// results make `q` in `log` point to `x`. As `log` is called by `compute`, the stack filtering
+// alone keeps this fact, while the borrowck filtering removes it, and `--dump-stats` reports
+// "#Filtered points-to relations by borrowck: 1" under both `--pta-type andersen` and
+// `--pta-type cs`. `y` is borrowed by `s`, which is passed to `read`, and `z` is passed to
+// `compute`, therefore their facts in `read` and `compute` are kept.
+
+struct Holder<'a> {
+    r: &'a i32,
+}
+
+fn read(h: &Holder) -> i32 {
+    *h.r
+}
+
+fn id<'a>(p: &'a i32) -> &'a i32 {
+    p
+}
+
+fn first<'a>(p: &'a i32) -> &'a i32 {
+    id(p)
+}
+
+fn log(p: &i32) -> i32 {
+    let q = first(p);
+    *q
+}
+
+fn compute(p: &i32) -> i32 {
+    let x = 1;
+    let y = 2;
+    let r = first(&x);
+    let s = Holder { r: &y };
+    let t = read(&s);
+    *r + t + log(p)
+}
+
+fn main() {
+    let z = 3;
+    compute(&z);
+}
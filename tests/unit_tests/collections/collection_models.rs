@@ -0,0 +1,140 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the abstract models for std collections, enabled with `--model-std-collections`.
+// The pointers inserted into a collection are expected to flow to the references and
+// iterators returned from the same collection. This includes the pointers inserted through
+// map entries or `extend`, and the pointers in the collections created by `collect` or `clone`.
+
+use std::collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+struct Node {
+    value: i32,
+}
+
+fn vec() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut v = Vec::new();
+    v.push(&a);
+    v.insert(0, &b);
+    let p = v[0];
+    let q = *v.get(1).unwrap();
+    for r in v.iter() {
+        let _ = r.value;
+    }
+    let s = v.pop().unwrap();
+    let _ = (p.value, q.value, s.value);
+
+    let w = vec![&a, &b];
+    for t in w.into_iter() {
+        let _ = t.value;
+    }
+}
+
+fn vec_deque() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut d = VecDeque::new();
+    d.push_back(&a);
+    d.push_front(&b);
+    let p = *d.front().unwrap();
+    for q in d.iter() {
+        let _ = q.value;
+    }
+    let r = d.pop_back().unwrap();
+    let _ = (p.value, r.value);
+}
+
+fn hash_map() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut m = HashMap::new();
+    m.insert(1, &a);
+    m.insert(2, &b);
+    let p = *m.get(&1).unwrap();
+    let q = m[&2];
+    for (_, r) in m.iter() {
+        let _ = r.value;
+    }
+    for s in m.values() {
+        let _ = s.value;
+    }
+    let t = m.remove(&1).unwrap();
+    let _ = (p.value, q.value, t.value);
+}
+
+fn hash_set() {
+    let a = Node { value: 1 };
+    let mut s = HashSet::new();
+    s.insert(&a as *const Node);
+    for p in s.iter() {
+        let _ = unsafe { (**p).value };
+    }
+}
+
+fn btree_map() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut m = BTreeMap::new();
+    m.insert(1, &a);
+    m.insert(2, &b);
+    let p = *m.get(&1).unwrap();
+    for (_, q) in m.iter() {
+        let _ = q.value;
+    }
+    for (_, r) in m.into_iter() {
+        let _ = r.value;
+    }
+    let _ = p.value;
+}
+
+fn btree_set() {
+    let a = Node { value: 1 };
+    let mut s = BTreeSet::new();
+    s.insert(&a as *const Node);
+    let p = *s.first().unwrap();
+    for q in s.iter() {
+        let _ = unsafe { (**q).value };
+    }
+    let _ = unsafe { (*p).value };
+}
+
+fn entry() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut m = HashMap::new();
+    m.entry(1).or_insert(&a);
+    let p = *m.get(&1).unwrap();
+    let mut n = BTreeMap::new();
+    if let btree_map::Entry::Vacant(e) = n.entry(1) {
+        e.insert(&b);
+    }
+    let q = n[&1];
+    let _ = (p.value, q.value);
+}
+
+fn extend() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut v = Vec::new();
+    v.extend([&a]);
+    let p = v[0];
+    let mut w: Vec<&Node> = v.iter().copied().collect();
+    w.extend(Some(&b));
+    let q = w.clone()[1];
+    let _ = (p.value, q.value);
+}
+
+fn main() {
+    vec();
+    vec_deque();
+    hash_map();
+    hash_set();
+    btree_map();
+    btree_set();
+    entry();
+    extend();
+}
@@ -4,6 +4,8 @@
 // LICENSE file in the root directory of this source tree.
 // 
 // A test that defines a custom allocator.
+// With `--resolve-global-allocator`, the allocation in `Box::new` is expected to call
+// `MyAllocator::alloc` through the allocator shim `__rust_alloc`.
 
 use std::alloc::{GlobalAlloc, System, Layout};
 
@@ -0,0 +1,79 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the data race detector enabled by `--check-races`.
+// `SharedCell` is marked `Sync` without synchronization, so the writes of `racy_incr` in the
+// spawned threads race with each other and with the read in `main` before the threads are joined.
+// The counter in the `Mutex` is only accessed with the lock held, and the value written by
+// `main` before spawning the threads is not accessed concurrently. `guarded_incr` writes `DATA`
+// holding two different mutexes allocated by `make_lock`, which do not exclude each other, so the
+// writes race although the two mutexes are one abstract object.
+
+use std::cell::UnsafeCell;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+struct SharedCell {
+    value: UnsafeCell<i32>,
+}
+
+unsafe impl Sync for SharedCell {}
+
+static CELL: SharedCell = SharedCell { value: UnsafeCell::new(0) };
+
+static DATA: SharedCell = SharedCell { value: UnsafeCell::new(0) };
+
+fn racy_incr() {
+    unsafe {
+        *CELL.value.get() += 1;
+    }
+}
+
+fn locked_incr(counter: &Mutex<i32>) {
+    let mut guard = counter.lock().unwrap();
+    *guard += 1;
+}
+
+fn make_lock() -> Arc<Mutex<()>> {
+    Arc::new(Mutex::new(()))
+}
+
+fn guarded_incr(lock: &Mutex<()>) {
+    let _guard = lock.lock().unwrap();
+    unsafe {
+        *DATA.value.get() += 1;
+    }
+}
+
+fn main() {
+    let init = Arc::new(SharedCell { value: UnsafeCell::new(0) });
+    unsafe {
+        *init.value.get() = 1;
+    }
+    let counter = Arc::new(Mutex::new(0));
+    let mut handles = Vec::new();
+    for _ in 0..2 {
+        let counter = counter.clone();
+        let init = init.clone();
+        handles.push(thread::spawn(move || {
+            racy_incr();
+            locked_incr(&counter);
+            let seen = unsafe { *init.value.get() };
+            assert_eq!(seen, 1);
+        }));
+    }
+    let current = unsafe { *CELL.value.get() };
+    println!("{}", current);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    locked_incr(&counter);
+
+    let first = make_lock();
+    let second = make_lock();
+    let handle = thread::spawn(move || guarded_incr(&first));
+    guarded_incr(&second);
+    handle.join().unwrap();
+}
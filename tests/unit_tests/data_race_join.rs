@@ -0,0 +1,31 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the joins of the data race detector enabled by `--check-races`.
+// Only the handle of the first thread is joined before `main` writes `FIRST` and `SECOND`, so the
+// write of `SECOND` is expected to race with the second thread, while the write of `FIRST` is not
+// with `--pta-type cs`. The context-insensitive analysis merges the two handles in the
+// `Result::expect` called by `thread::spawn`, hence neither join is known to end a single thread
+// and both writes are reported.
+
+use std::thread;
+
+static mut FIRST: i32 = 0;
+static mut SECOND: i32 = 0;
+
+fn main() {
+    let first = thread::spawn(|| unsafe {
+        FIRST += 1;
+    });
+    let second = thread::spawn(|| unsafe {
+        SECOND += 1;
+    });
+    first.join().unwrap();
+    unsafe {
+        FIRST += 1;
+        SECOND += 1;
+    }
+    second.join().unwrap();
+}
@@ -0,0 +1,63 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the deadlock detector enabled by `--check-deadlocks`.
+// `transfer` locks the `from` account and then calls `deposit` locking the `to` account, while
+// `refund` locks the two accounts in the opposite order, giving a lock order cycle between the
+// threads. `log_twice` locks `LOG` again in `append` while holding it. The locks of `CONFIG` and
+// `STATS` are always acquired in the same order and are not reported.
+
+use std::sync::{Mutex, RwLock};
+use std::thread;
+
+static CHECKING: Mutex<i32> = Mutex::new(100);
+static SAVINGS: Mutex<i32> = Mutex::new(100);
+static LOG: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+static CONFIG: RwLock<i32> = RwLock::new(0);
+static STATS: Mutex<i32> = Mutex::new(0);
+
+fn deposit(to: &Mutex<i32>, amount: i32) {
+    *to.lock().unwrap() += amount;
+}
+
+fn transfer(from: &Mutex<i32>, to: &Mutex<i32>, amount: i32) {
+    let mut from_balance = from.lock().unwrap();
+    *from_balance -= amount;
+    deposit(to, amount);
+}
+
+fn refund(from: &Mutex<i32>, to: &Mutex<i32>, amount: i32) {
+    let mut to_balance = to.lock().unwrap();
+    let mut from_balance = from.lock().unwrap();
+    *to_balance -= amount;
+    *from_balance += amount;
+}
+
+fn append(value: i32) {
+    LOG.lock().unwrap().push(value);
+}
+
+fn log_twice(value: i32) {
+    let log = LOG.lock().unwrap();
+    if log.len() < 10 {
+        append(value);
+    }
+}
+
+fn update_stats() {
+    let config = CONFIG.read().unwrap();
+    *STATS.lock().unwrap() += *config;
+}
+
+fn main() {
+    let handle = thread::spawn(|| {
+        transfer(&CHECKING, &SAVINGS, 10);
+        update_stats();
+    });
+    refund(&CHECKING, &SAVINGS, 10);
+    update_stats();
+    handle.join().unwrap();
+    log_twice(1);
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the escape analysis dumped with `--dump-escape`.
+// The local `a` of `local_only` is expected to be function-local, as well as the locals of `main`
+// borrowed by `h`, while `v` of `dangling` escapes to the caller. The `Box` allocations escape to the callers of `Box::new`,
+// the one in `boxed_local` is confined to it and could live on its stack, whereas the one
+// returned by `boxed_returned` is confined to `main`. The allocation stored into `GLOBAL` escapes
+// to a global, and the one captured by the spawned closure escapes to another thread. Under
+// `--pta-type cs`, each `Box` allocation is given the callsite of `Box::new` allocating it.
+
+use std::sync::Arc;
+use std::thread;
+
+struct Holder<'a> {
+    r: &'a i32,
+}
+
+static mut GLOBAL: Option<&'static i32> = None;
+
+fn use_ref(_r: &i32) {}
+
+fn local_only() {
+    let a = 1;
+    let h = Holder { r: &a };
+    use_ref(h.r);
+}
+
+fn to_caller<'a>(h: &mut Holder<'a>, b: &'a i32) {
+    h.r = b;
+}
+
+fn dangling() -> *const i32 {
+    let v = 2;
+    &v as *const i32
+}
+
+fn boxed_local() -> i32 {
+    let b = Box::new(2);
+    *b + 1
+}
+
+fn boxed_returned() -> Box<i32> {
+    Box::new(3)
+}
+
+fn to_global() {
+    let g: &'static i32 = Box::leak(Box::new(4));
+    unsafe {
+        GLOBAL = Some(g);
+    }
+}
+
+fn to_thread() {
+    let shared = Arc::new(5);
+    let handle = thread::spawn(move || {
+        use_ref(&shared);
+    });
+    let _ = handle.join();
+}
+
+fn main() {
+    local_only();
+    let x = 6;
+    let mut h = Holder { r: &x };
+    let y = 7;
+    to_caller(&mut h, &y);
+    use_ref(h.r);
+    let _p = dangling();
+    boxed_local();
+    let r = boxed_returned();
+    use_ref(&r);
+    to_global();
+    to_thread();
+}
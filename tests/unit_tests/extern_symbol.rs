@@ -0,0 +1,39 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of resolving the functions declared in `extern` blocks.
+// The call to `on_event` is expected to be resolved to the `#[no_mangle]` function `on_event`,
+// and the call to `get_config` is expected to be resolved to `config` exported with
+// `#[export_name = "get_config"]`. `p` is expected to point to the heap object allocated in
+// `config`.
+
+mod ffi {
+    extern "Rust" {
+        pub fn on_event(data: &mut Box<i32>);
+    }
+
+    extern "C" {
+        pub fn get_config() -> *mut i32;
+    }
+}
+
+#[no_mangle]
+fn on_event(data: &mut Box<i32>) {
+    **data += 1;
+}
+
+#[export_name = "get_config"]
+extern "C" fn config() -> *mut i32 {
+    Box::into_raw(Box::new(0))
+}
+
+fn main() {
+    let mut data = Box::new(1);
+    unsafe {
+        ffi::on_event(&mut data);
+        let p = ffi::get_config();
+        *p = *data;
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the flow-sensitive refinement with `--flow-sensitive-funcs reassign`.
+// The flow-insensitive analysis finds that `p` points to both `a` and `b`. With the refinement,
+// `p` is expected to point to `a` only before it is reassigned, and to `b` only afterwards. The
+// store through `q` is a strong update of `s.f`, since `q` only points to `s`.
+
+struct S<'a> {
+    f: &'a i32,
+}
+
+fn use_ref(_r: &i32) {}
+
+fn reassign(a: i32, b: i32) {
+    let mut p = &a;
+    use_ref(p);
+    p = &b;
+    use_ref(p);
+    let mut s = S { f: &a };
+    let q = &mut s;
+    q.f = &b;
+    use_ref(s.f);
+}
+
+fn main() {
+    reassign(1, 2);
+}
@@ -0,0 +1,25 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the flow-sensitive refinement of arrays with
+// `--flow-sensitive-funcs read_after_write --index-sensitive-arrays 4`.
+// The array is created with `arr[0]` and `arr[1]` pointing to `a`, then `arr[1]` is overwritten
+// with `&b` through a constant index. The read `arr[i]` with a non-constant index is expected to
+// point to both `a` and `b`, while `arr[1]` is expected to point to `b` only after the write.
+
+fn use_ref(_r: &i32) {}
+
+fn read_after_write(i: usize) {
+    let a = 1;
+    let b = 2;
+    let mut arr = [&a, &a];
+    arr[1] = &b;
+    use_ref(arr[1]);
+    use_ref(arr[i]);
+}
+
+fn main() {
+    read_after_write(0);
+}
@@ -0,0 +1,67 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the use-after-free and double-free detector enabled by `--dump-free-errors`.
+// `use_after_drop` reads through a raw pointer to a `Box` after dropping it, and `double_dealloc`
+// deallocates the same allocation twice through a stale pointer. The boxes allocated and dropped
+// in each iteration of the loop of `no_error_in_loop` are not reported, as the pointer is
+// derived from the box allocated in the same iteration. The box moved into `consume` in
+// `no_error_after_move` is not dropped again at the end of the scope, whose drop is guarded by the
+// drop flag cleared by the move.
+
+use std::alloc::{alloc, dealloc, Layout};
+
+fn use_after_drop() -> i32 {
+    let b = Box::new(1);
+    let p = &*b as *const i32;
+    drop(b);
+    unsafe { *p }
+}
+
+fn release(ptr: *mut u8, layout: Layout) {
+    unsafe { dealloc(ptr, layout) };
+}
+
+fn double_dealloc() {
+    let layout = Layout::new::<u64>();
+    unsafe {
+        let ptr = alloc(layout);
+        *ptr = 1;
+        release(ptr, layout);
+        dealloc(ptr, layout);
+    }
+}
+
+fn no_error_in_loop() -> i32 {
+    let mut sum = 0;
+    for i in 0..10 {
+        let b = Box::new(i);
+        let p = &*b as *const i32;
+        sum += unsafe { *p };
+        drop(b);
+    }
+    sum
+}
+
+fn consume(b: Box<i64>) -> i64 {
+    *b
+}
+
+fn log() {}
+
+fn no_error_after_move(c: bool) {
+    let b = Box::new(1i64);
+    if c {
+        consume(b);
+    }
+    log();
+}
+
+fn main() {
+    use_after_drop();
+    double_dealloc();
+    no_error_in_loop();
+    no_error_after_move(true);
+}
@@ -0,0 +1,54 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of generic entry functions.
+// With `--entry-func run`, `run` is expected to be instantiated with each local implementor of
+// `Plugin`, i.e. `Logger` and `Counter`, so that both `Plugin::execute` implementations are
+// reachable. With `--entry-func 'process::<Logger, Vec<u8>>'`, only `Logger::execute` is
+// reachable and `process` is instantiated with `Logger` and `Vec<u8>`.
+
+trait Plugin {
+    fn execute(&self, input: &i32) -> Box<i32>;
+}
+
+struct Logger {
+    level: i32,
+}
+
+struct Counter {
+    count: i32,
+}
+
+struct Unrelated;
+
+impl Plugin for Logger {
+    fn execute(&self, input: &i32) -> Box<i32> {
+        Box::new(*input + self.level)
+    }
+}
+
+impl Plugin for Counter {
+    fn execute(&self, input: &i32) -> Box<i32> {
+        Box::new(*input + self.count)
+    }
+}
+
+fn run<P: Plugin>(plugin: &P, input: &i32) -> Box<i32> {
+    plugin.execute(input)
+}
+
+fn process<P: Plugin, D: Default>(plugin: &P, input: &i32) -> (Box<i32>, D) {
+    (plugin.execute(input), D::default())
+}
+
+fn main() {
+    let _ = Unrelated;
+    let logger = Logger { level: 1 };
+    let counter = Counter { count: 0 };
+    let x = 1;
+    run(&logger, &x);
+    run(&counter, &x);
+    let _: (Box<i32>, Vec<u8>) = process(&logger, &x);
+}
@@ -0,0 +1,60 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of index-sensitive arrays with `--index-sensitive-arrays 8`.
+// `TABLE[1]()` is expected to call `sub` only, while `TABLE[i]()` calls all the entries.
+// In `patch`, `handlers[0]()` calls `add` and `neg` written with a non-constant index, and
+// `handlers[1]()` also calls `neg` written through a reference to the element.
+// In `dispatch`, the entries are read through a slice, therefore all of them are called.
+// In `grid`, only the inner dimension of the nested array is index-sensitive, so `grid[0][1]()`
+// calls `sub` and `mul`, and `grid[1][0]()` calls `add` and `neg`, but not the other two.
+
+const TABLE: [fn(i32) -> i32; 3] = [add, sub, mul];
+
+fn add(x: i32) -> i32 {
+    x + 1
+}
+
+fn sub(x: i32) -> i32 {
+    x - 1
+}
+
+fn mul(x: i32) -> i32 {
+    x * 2
+}
+
+fn neg(x: i32) -> i32 {
+    -x
+}
+
+fn patch(mut handlers: [fn(i32) -> i32; 2], i: usize) {
+    handlers[i] = neg;
+    handlers[0](1);
+    let second = &mut handlers[1];
+    *second = neg;
+    handlers[1](2);
+}
+
+fn dispatch(handlers: &[fn(i32) -> i32], x: i32) -> i32 {
+    handlers[0](x)
+}
+
+fn grid(i: usize) {
+    let mut grid: [[fn(i32) -> i32; 2]; 2] = [[add, sub], [add, mul]];
+    grid[i][0] = neg;
+    grid[0][1](5);
+    grid[1][0](6);
+}
+
+fn main() {
+    let i = std::env::args().count();
+    TABLE[1](1);
+    TABLE[i](2);
+    patch([add, sub], i);
+    dispatch(&TABLE, 3);
+    let [first, ..] = TABLE;
+    first(4);
+    grid(i);
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+// 
+// A test of lazily initialized globals, enabled with `--model-lazy-globals`.
+// The pointers returned from the initializers of `OnceCell`, `OnceLock`, `LazyCell` and
+// `LazyLock`, or set into them, are expected to flow to the references read from them.
+
+#![feature(lazy_cell)]
+
+use std::cell::{LazyCell, OnceCell};
+use std::sync::{LazyLock, OnceLock};
+
+struct Node {
+    value: i32,
+}
+
+static A: Node = Node { value: 1 };
+static B: Node = Node { value: 2 };
+
+static ONCE_LOCK: OnceLock<&'static Node> = OnceLock::new();
+static LAZY_LOCK: LazyLock<&'static Node> = LazyLock::new(|| &A);
+
+fn once_cell() {
+    let cell = OnceCell::new();
+    let p = *cell.get_or_init(|| &A);
+    let _ = cell.set(&B);
+    let q = *cell.get().unwrap();
+    let _ = (p.value, q.value);
+}
+
+fn once_lock() {
+    let p = *ONCE_LOCK.get_or_init(|| &A);
+    let _ = ONCE_LOCK.set(&B);
+    let q = *ONCE_LOCK.get().unwrap();
+    let _ = (p.value, q.value);
+}
+
+fn lazy_cell() {
+    let lazy = LazyCell::new(|| &B);
+    let p: &Node = *lazy;
+    let _ = p.value;
+}
+
+fn lazy_lock() {
+    let p: &Node = *LAZY_LOCK;
+    let _ = p.value;
+}
+
+fn main() {
+    once_cell();
+    once_lock();
+    lazy_cell();
+    lazy_lock();
+}
@@ -0,0 +1,48 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of analyzing a library with `--entry-mode lib`.
+// All the public functions and methods are expected to be entries, whose pointer arguments
+// point to the abstract objects of their pointee types. The private function `helper` is only
+// reachable from `Registry::register`, and `describe` is instantiated with the unit type.
+// Both parameters of `first` are expected to point to the same abstract `Node` object.
+
+#![crate_type = "lib"]
+
+pub struct Node {
+    pub value: i32,
+    pub next: Option<Box<Node>>,
+}
+
+pub struct Registry<'a> {
+    pub nodes: Vec<&'a Node>,
+    pub last: Option<&'a Node>,
+}
+
+impl<'a> Registry<'a> {
+    pub fn register(&mut self, node: &'a Node) {
+        self.last = Some(helper(node));
+    }
+
+    pub fn last(&self) -> Option<&'a Node> {
+        self.last
+    }
+}
+
+fn helper(node: &Node) -> &Node {
+    node
+}
+
+pub fn first<'a>(a: &'a Node, _b: &'a Node) -> &'a Node {
+    a
+}
+
+pub fn describe<T: Clone>(value: &T) -> T {
+    value.clone()
+}
+
+pub fn count(registry: &Registry) -> usize {
+    registry.nodes.len()
+}
@@ -0,0 +1,71 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the memory functions of the C library.
+// The pointers returned from `malloc`, `calloc`, `strdup` and `mmap` are expected to point to
+// heap objects, `realloc` returns the reallocated object, and `memcpy`/`memmove` copy the
+// pointers stored in the source memory to the destination memory. In `grow`, the buffer starts
+// as `NULL`, so `realloc` allocates a new heap object, and `next` is expected to point to `x`.
+
+use std::ffi::{c_char, c_int, c_void};
+
+extern "C" {
+    fn malloc(size: usize) -> *mut c_void;
+    fn calloc(nobj: usize, size: usize) -> *mut c_void;
+    fn realloc(p: *mut c_void, size: usize) -> *mut c_void;
+    fn free(p: *mut c_void);
+    fn memcpy(dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void;
+    fn memmove(dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void;
+    fn strdup(cs: *const c_char) -> *mut c_char;
+    fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: i64) -> *mut c_void;
+}
+
+struct Node {
+    value: i32,
+    next: *const Node,
+}
+
+fn alloc() {
+    unsafe {
+        let p = malloc(std::mem::size_of::<Node>()) as *mut Node;
+        let q = calloc(1, std::mem::size_of::<Node>()) as *mut Node;
+        (*p).next = q;
+        let r = realloc(p as *mut c_void, 2 * std::mem::size_of::<Node>()) as *mut Node;
+        let _ = (*r).next;
+        let s = strdup(b"node\0".as_ptr() as *const c_char);
+        let m = mmap(std::ptr::null_mut(), 4096, 3, 0x22, -1, 0);
+        free(q as *mut c_void);
+        free(r as *mut c_void);
+        let _ = (s, m);
+    }
+}
+
+fn grow(x: &Node) {
+    unsafe {
+        let buf = realloc(std::ptr::null_mut(), std::mem::size_of::<Node>()) as *mut Node;
+        (*buf).next = x;
+        let next = (*buf).next;
+        let _ = (*next).value;
+        free(buf as *mut c_void);
+    }
+}
+
+fn copy() {
+    let a = Node { value: 1, next: std::ptr::null() };
+    let b = Node { value: 2, next: &a };
+    let mut c = Node { value: 3, next: std::ptr::null() };
+    let mut d = Node { value: 4, next: std::ptr::null() };
+    unsafe {
+        memcpy(&mut c as *mut Node as *mut c_void, &b as *const Node as *const c_void, std::mem::size_of::<Node>());
+        memmove(&mut d as *mut Node as *mut c_void, &c as *const Node as *const c_void, std::mem::size_of::<Node>());
+    }
+    let _ = (c.next, d.next, a.value, b.value);
+}
+
+fn main() {
+    alloc();
+    grow(&Node { value: 5, next: std::ptr::null() });
+    copy();
+}
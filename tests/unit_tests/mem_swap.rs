@@ -0,0 +1,63 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+// 
+// A test of moving values through mutable references.
+// The pointers contained in the values moved by `std::mem::{swap, take, replace}`
+// are expected to flow between the referenced values. The value left behind by
+// `std::mem::take` comes from `Default::default`, i.e. `*h` in `take_default`
+// points to `DEFAULT_NODE`.
+
+struct Node {
+    value: i32,
+}
+
+fn swap() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut x = &a;
+    let mut y = &b;
+    std::mem::swap(&mut x, &mut y);
+    let _ = (x.value, y.value);
+}
+
+fn take() {
+    let a = Node { value: 1 };
+    let mut v: Option<&Node> = Some(&a);
+    let taken = std::mem::take(&mut v);
+    let _ = taken.unwrap().value;
+}
+
+static DEFAULT_NODE: Node = Node { value: 0 };
+
+struct Holder {
+    node: &'static Node,
+}
+
+impl Default for Holder {
+    fn default() -> Self {
+        Holder { node: &DEFAULT_NODE }
+    }
+}
+
+fn take_default(h: &mut Holder) {
+    let taken = std::mem::take(h);
+    let _ = (taken.node.value, h.node.value);
+}
+
+fn replace() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut x = (&a, 0);
+    let old = std::mem::replace(&mut x, (&b, 1));
+    let _ = (old.0.value, x.0.value);
+}
+
+fn main() {
+    swap();
+    take();
+    static OTHER_NODE: Node = Node { value: 1 };
+    take_default(&mut Holder { node: &OTHER_NODE });
+    replace();
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the mod/ref summaries dumped with `--dump-modref`.
+// `get` is expected to read `main::local_1.0` only, `incr` to read and write it and `rename` to write
+// `main::local_1.1`. `update` includes the summaries of its callees, while the summary of
+// `pure_add` is empty and `record` reads and writes the static variable `TOTAL`. The objects of
+// `main` do not appear in its own summary.
+
+struct Counter {
+    count: usize,
+    name: &'static str,
+}
+
+static mut TOTAL: usize = 0;
+
+fn get(c: &Counter) -> usize {
+    c.count
+}
+
+fn incr(c: &mut Counter) {
+    c.count += 1;
+}
+
+fn rename(c: &mut Counter, name: &'static str) {
+    c.name = name;
+}
+
+fn update(c: &mut Counter) {
+    incr(c);
+    rename(c, "b");
+}
+
+fn pure_add(a: usize, b: usize) -> usize {
+    a + b
+}
+
+fn record(n: usize) {
+    unsafe {
+        TOTAL += n;
+    }
+}
+
+fn main() {
+    let mut c = Counter { count: 0, name: "a" };
+    update(&mut c);
+    let n = get(&c);
+    record(pure_add(n, 1));
+}
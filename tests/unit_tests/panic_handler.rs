@@ -0,0 +1,31 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of resolving the `#[panic_handler]` with `--resolve-panic-handler`.
+// Analyzed with `--entry-func start`, the panic in `start` is expected to call `on_panic`,
+// in which `LAST` is expected to point to `ERROR`.
+
+#![no_std]
+#![crate_type = "lib"]
+
+use core::panic::PanicInfo;
+
+static mut LAST: Option<&'static i32> = None;
+static ERROR: i32 = 1;
+
+#[panic_handler]
+fn on_panic(_info: &PanicInfo) -> ! {
+    unsafe {
+        LAST = Some(&ERROR);
+    }
+    loop {}
+}
+
+pub fn start(x: Option<&i32>) -> i32 {
+    match x {
+        Some(v) => *v,
+        None => panic!("no value"),
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of resolving the panic hooks with `--resolve-panic-handler`.
+// The hook registered with `set_hook`, taken back with `take_hook` and registered again,
+// is expected to be called from the panic in `main`.
+
+use std::panic;
+
+static mut REPORTED: Option<&'static i32> = None;
+static CODE: i32 = 1;
+
+fn report(_info: &panic::PanicInfo) {
+    unsafe {
+        REPORTED = Some(&CODE);
+    }
+}
+
+fn main() {
+    panic::set_hook(Box::new(report));
+    let hook = panic::take_hook();
+    panic::set_hook(hook);
+    let v: Vec<i32> = Vec::new();
+    if v.is_empty() {
+        panic!("empty");
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+// 
+// A test of moving values through raw pointers.
+// The pointers contained in the values moved by `std::ptr::{read, write, replace, swap, copy}`
+// and the corresponding raw pointer methods are expected to flow between the pointees.
+
+struct Node {
+    value: i32,
+}
+
+struct Pair<'a> {
+    first: &'a Node,
+    second: *const Node,
+}
+
+fn read() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let pair = Pair { first: &a, second: &b };
+    let p = &pair as *const Pair;
+    let copied = unsafe { std::ptr::read(p) };
+    let q = unsafe { p.read() };
+    let _ = (copied.first.value, q.second);
+}
+
+fn write() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut r = &a;
+    let p = &mut r as *mut &Node;
+    unsafe { std::ptr::write(p, &b) };
+    unsafe { p.write_volatile(&b) };
+    let _ = r.value;
+}
+
+fn replace() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut r = &a;
+    let p = &mut r as *mut &Node;
+    let old = unsafe { std::ptr::replace(p, &b) };
+    let _ = (old.value, r.value);
+}
+
+fn swap() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let mut x = Pair { first: &a, second: &a };
+    let mut y = Pair { first: &b, second: &b };
+    unsafe { std::ptr::swap(&mut x as *mut Pair, &mut y as *mut Pair) };
+    unsafe { std::ptr::swap_nonoverlapping(&mut x.first as *mut &Node, &mut y.first as *mut &Node, 1) };
+    let _ = (x.first.value, y.second);
+}
+
+fn copy() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let src = [&a, &a];
+    let mut dst = [&b, &b];
+    unsafe { std::ptr::copy(src.as_ptr(), dst.as_mut_ptr(), 2) };
+    let _ = dst[0].value;
+}
+
+fn main() {
+    read();
+    write();
+    replace();
+    swap();
+    copy();
+}
@@ -0,0 +1,34 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of selecting entry functions by their def paths.
+// `--entry-func run` is expected to be ambiguous between the free function `run`, the method
+// `Worker::run` and `worker::run`, while `--entry-func crate::Worker::run` and
+// `--entry-func worker::run` select a single entry each and can be provided together.
+
+struct Worker {
+    data: Box<i32>,
+}
+
+impl Worker {
+    fn run(&self) -> &i32 {
+        &self.data
+    }
+}
+
+mod worker {
+    pub fn run() -> Box<i32> {
+        Box::new(1)
+    }
+}
+
+fn run() {
+    let w = Worker { data: worker::run() };
+    let _r = w.run();
+}
+
+fn main() {
+    run();
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of pruning the stores through shared references with `--prune-shared-ref-stores`.
+// `Config` has no interior mutability, so it can only be written through `&mut Config` in
+// `update`, and `config.data` is expected to point to both `a` and `b`. `Slot` contains a `Cell`,
+// so the store through `&Slot` in `set` is kept and `slot.data` is expected to point to `c`.
+// In `overwrite`, `memcpy` writes through a pointer cast from `&Config`, which is undefined
+// behavior. The store is pruned with the option, so `_f` in `shared` is expected to point to `a`
+// only, while it points to both `a` and `b` without the option, as checked by
+// `tests/analysis_result.rs`.
+
+use std::cell::Cell;
+use std::ffi::c_void;
+
+extern "C" {
+    fn memcpy(dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void;
+}
+
+struct Config<'a> {
+    data: &'a i32,
+}
+
+struct Slot<'a> {
+    data: Cell<&'a i32>,
+}
+
+fn update<'a>(config: &mut Config<'a>, data: &'a i32) {
+    config.data = data;
+}
+
+fn read<'a>(config: &Config<'a>) -> &'a i32 {
+    config.data
+}
+
+fn set<'a>(slot: &Slot<'a>, data: &'a i32) {
+    slot.data.set(data);
+}
+
+fn overwrite<'a>(config: &Config<'a>, data: &'a i32) {
+    let src = Config { data };
+    unsafe {
+        memcpy(
+            config as *const Config as *mut c_void,
+            &src as *const Config as *const c_void,
+            std::mem::size_of::<Config>(),
+        );
+    }
+}
+
+fn shared(a: i32, b: i32) {
+    let shared = Config { data: &a };
+    overwrite(&shared, &b);
+    let _f = shared.data;
+}
+
+fn main() {
+    let a = 1;
+    let b = 2;
+    let c = 3;
+    let mut config = Config { data: &a };
+    update(&mut config, &b);
+    let _d = read(&config);
+    let slot = Slot { data: Cell::new(&a) };
+    set(&slot, &c);
+    let _e = slot.data.get();
+    shared(1, 2);
+}
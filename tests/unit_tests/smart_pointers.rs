@@ -0,0 +1,84 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the summaries for smart pointer and interior mutability types,
+// enabled with `--model-std-smart-pointers`.
+// The pointers wrapped in `Rc`, `Arc`, `Cell`, `RefCell`, `Mutex` and `RwLock`
+// are expected to flow to the pointers read from the same wrapper.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+struct Node {
+    value: i32,
+}
+
+fn rc() {
+    let a = Node { value: 1 };
+    let rc1 = Rc::new(&a);
+    let rc2 = rc1.clone();
+    let p: &Node = *rc2;
+    let _ = p.value;
+    let q = Rc::into_inner(rc1);
+    let _ = q;
+}
+
+fn arc() {
+    let a = Node { value: 1 };
+    let arc1 = Arc::new(&a);
+    let arc2 = Arc::clone(&arc1);
+    let p: &Node = *arc2;
+    let _ = p.value;
+}
+
+fn cell() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let c = Cell::new(&a);
+    c.set(&b);
+    let p = c.get();
+    let q = c.replace(&a);
+    let r = c.into_inner();
+    let _ = (p.value, q.value, r.value);
+}
+
+fn ref_cell() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let c = RefCell::new(&a);
+    *c.borrow_mut() = &b;
+    let p: &Node = *c.borrow();
+    let q = c.replace(&a);
+    let _ = (p.value, q.value);
+}
+
+fn mutex() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let m = Mutex::new(&a);
+    *m.lock().unwrap() = &b;
+    let p: &Node = *m.lock().unwrap();
+    let q = m.into_inner().unwrap();
+    let _ = (p.value, q.value);
+}
+
+fn rw_lock() {
+    let a = Node { value: 1 };
+    let b = Node { value: 2 };
+    let l = RwLock::new(&a);
+    *l.write().unwrap() = &b;
+    let p: &Node = *l.read().unwrap();
+    let _ = p.value;
+}
+
+fn main() {
+    rc();
+    arc();
+    cell();
+    ref_cell();
+    mutex();
+    rw_lock();
+}
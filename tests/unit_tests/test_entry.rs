@@ -0,0 +1,52 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of analyzing the test functions with `--entry-mode tests`.
+// Both `test_push` and `test_swap` are expected to be entries, while `main` and the non-test
+// function `helper` are not. `Stack::push` is only reachable from `test_push`, and `swap` is
+// only reachable from `test_swap`.
+
+struct Stack<'a> {
+    items: Vec<&'a i32>,
+}
+
+impl<'a> Stack<'a> {
+    fn push(&mut self, item: &'a i32) {
+        self.items.push(item);
+    }
+}
+
+fn swap<'a>(a: &mut &'a i32, b: &mut &'a i32) {
+    std::mem::swap(a, b);
+}
+
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helper() -> i32 {
+        1
+    }
+
+    #[test]
+    fn test_push() {
+        let x = helper();
+        let mut stack = Stack { items: Vec::new() };
+        stack.push(&x);
+        assert_eq!(stack.items.len(), 1);
+    }
+
+    #[test]
+    fn test_swap() {
+        let x = 1;
+        let y = 2;
+        let mut a = &x;
+        let mut b = &y;
+        swap(&mut a, &mut b);
+        assert_eq!(*a, 2);
+    }
+}
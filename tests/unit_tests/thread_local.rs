@@ -0,0 +1,43 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+// 
+// A test of thread locals declared with `thread_local!`, enabled with `--model-lazy-globals`.
+// The pointers returned from the initializer of a thread local are expected to flow
+// to the references passed to the closures of `LocalKey::with`.
+
+use std::cell::Cell;
+
+struct Node {
+    value: i32,
+}
+
+static A: Node = Node { value: 1 };
+static B: Node = Node { value: 2 };
+
+thread_local! {
+    static CURRENT: &'static Node = &A;
+    static SLOT: Cell<&'static Node> = Cell::new(&A);
+}
+
+fn with() {
+    CURRENT.with(|p| {
+        let _ = p.value;
+    });
+}
+
+fn set_get() {
+    SLOT.set(&B);
+    let q = SLOT.get();
+    let _ = q.value;
+    SLOT.with(|cell| {
+        let r = cell.get();
+        let _ = r.value;
+    });
+}
+
+fn main() {
+    with();
+    set_get();
+}
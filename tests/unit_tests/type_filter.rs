@@ -0,0 +1,72 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of the type filter with `--type-filter`.
+// The trait objects in `items` are downcast to `Holder` through `dyn Any`, therefore only the
+// shape stored in a `Holder`, i.e. `Square`, is expected to be the receiver of `h.shape.area()`.
+// The pointers erased to `*const ()` are cast back to their own types, so `a.area()` is expected
+// to call `Square::area` and `b.area()` is expected to call `Circle::area`.
+
+use std::any::Any;
+
+trait Shape {
+    fn area(&self) -> i32;
+}
+
+struct Square(i32);
+
+struct Circle(i32);
+
+impl Shape for Square {
+    fn area(&self) -> i32 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> i32 {
+        self.0 * 3
+    }
+}
+
+struct Holder {
+    shape: Box<dyn Shape>,
+}
+
+struct Other {
+    shape: Box<dyn Shape>,
+}
+
+struct Slot {
+    ptr: *const (),
+}
+
+fn erase<T>(x: &T) -> Slot {
+    Slot { ptr: x as *const T as *const () }
+}
+
+fn main() {
+    let items: Vec<Box<dyn Any>> = vec![
+        Box::new(Holder { shape: Box::new(Square(2)) }),
+        Box::new(Other { shape: Box::new(Circle(1)) }),
+    ];
+    for item in &items {
+        if let Some(h) = item.downcast_ref::<Holder>() {
+            h.shape.area();
+        }
+    }
+
+    let s = Box::new(Square(2));
+    let c = Box::new(Circle(1));
+    let slots = vec![erase(&s), erase(&c)];
+    let p = slots[0].ptr as *const Box<Square>;
+    let q = slots[1].ptr as *const Box<Circle>;
+    unsafe {
+        let a: &dyn Shape = &**p;
+        let b: &dyn Shape = &**q;
+        a.area();
+        b.area();
+    }
+}
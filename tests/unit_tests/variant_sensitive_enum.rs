@@ -0,0 +1,38 @@
+// Copyright (c) 2024 <Wei Li>.
+//
+// This source code is licensed under the GNU license found in the
+// LICENSE file in the root directory of this source tree.
+//
+// A test of keeping the enum variants distinct with `--variant-sensitive-enums`.
+// The payloads of `Option<Box<dyn Handler>>` values are accessed through raw pointers and
+// transmutes. The dynamic calls through `pa` and `o` are expected to resolve to `A::handle`
+// only, and the dynamic call through `pb` to `B::handle` only. Without the option, the enum
+// values are not flattened into their variants and the calls are not resolved.
+
+trait Handler {
+    fn handle(&self);
+}
+
+struct A;
+struct B;
+
+impl Handler for A {
+    fn handle(&self) {}
+}
+
+impl Handler for B {
+    fn handle(&self) {}
+}
+
+fn main() {
+    let a: Option<Box<dyn Handler>> = Some(Box::new(A));
+    let b: Option<Box<dyn Handler>> = Some(Box::new(B));
+    let pa = &a as *const Option<Box<dyn Handler>> as *const Box<dyn Handler>;
+    unsafe { (*pa).handle(); }
+    let pb = &b as *const Option<Box<dyn Handler>> as *const u8;
+    let pb = pb as *const Box<dyn Handler>;
+    unsafe { (*pb).handle(); }
+    let o: Option<&dyn Handler> = Some(&A);
+    let p = unsafe { std::mem::transmute::<Option<&dyn Handler>, *const dyn Handler>(o) };
+    unsafe { (*p).handle(); }
+}